@@ -1,23 +1,194 @@
 //! # A Concordium V1 smart contract
-use concordium_std::*;
+use concordium_std::{collections::BTreeMap, *};
 use core::fmt::Debug;
 
-/// Tag for the NewAdmin event. The CIS-2 library already uses the
-/// event tags from `u8::MAX` to `u8::MAX - 4`.
-pub const TOKEN_NEW_ADMIN_EVENT_TAG: u8 = u8::MAX - 5;
+// Versus events are tagged from 0 upwards, so that they stay clear of the tags
+// the CIS standards assign from `u8::MAX` downwards.
+
+/// Tag for the NewAdmin event.
+pub const NEW_ADMIN_EVENT_TAG: u8 = 0;
+
+/// Tag for the ResultHookFailed event.
+pub const RESULT_HOOK_FAILED_EVENT_TAG: u8 = 1;
+
+/// Tag for the StatsReset event.
+pub const STATS_RESET_EVENT_TAG: u8 = 2;
+
+/// Tag for the RewardSkipped event.
+pub const REWARD_SKIPPED_EVENT_TAG: u8 = 3;
+
+/// Tag for the ResultApplied event.
+pub const RESULT_APPLIED_EVENT_TAG: u8 = 4;
+
+/// The lowest event tag reserved by the CIS-2 library, which uses the tags
+/// from `u8::MAX` to `u8::MAX - 4`.
+const CIS2_RESERVED_EVENT_TAG_FLOOR: u8 = u8::MAX - 4;
+
+/// All event tags logged by this contract.
+const EVENT_TAGS: &[u8] = &[
+    NEW_ADMIN_EVENT_TAG,
+    RESULT_HOOK_FAILED_EVENT_TAG,
+    STATS_RESET_EVENT_TAG,
+    REWARD_SKIPPED_EVENT_TAG,
+    RESULT_APPLIED_EVENT_TAG,
+];
+
+// Turn colliding event tags or tags in the CIS-2 range into a build error.
+const _: () = {
+    let mut i = 0;
+    while i < EVENT_TAGS.len() {
+        assert!(EVENT_TAGS[i] < CIS2_RESERVED_EVENT_TAG_FLOOR, "Event tag is reserved by CIS-2");
+        let mut j = i + 1;
+        while j < EVENT_TAGS.len() {
+            assert!(EVENT_TAGS[i] != EVENT_TAGS[j], "Event tags have to be distinct");
+            j += 1;
+        }
+        i += 1;
+    }
+};
+
+/// Version of this implementation. Increase it with every deployed upgrade.
+const IMPLEMENTATION_VERSION: u32 = 1;
+
+/// Names of all entrypoints of this contract, in the order they are declared
+/// in. Add the name of every new entrypoint.
+const ENTRYPOINTS: &[&str] = &[
+    "initialize",
+    "view",
+    "getProtocolAddresses",
+    "getVersion",
+    "getEntrypoints",
+    "amIAdmin",
+    "updatePlayerState",
+    "updateBattleResult",
+    "recordMatch",
+    "reportTournamentMatch",
+    "advanceRound",
+    "joinQueue",
+    "leaveQueue",
+    "joinPairingQueue",
+    "leavePairingQueue",
+    "pairPlayers",
+    "stakeMatch",
+    "completeMatch",
+    "fundRewards",
+    "disputeMatch",
+    "finalizeResults",
+    "rollOverSeason",
+    "decayRatings",
+    "resetPlayer",
+    "resetAllPlayers",
+    "createGame",
+    "createTournament",
+    "distributePrizes",
+    "addPlayer",
+    "registerAndStake",
+    "updateAdmin",
+    "setRegistrationFee",
+    "setMinStake",
+    "setRewardPerWin",
+    "getRewardPoolBalance",
+    "setResultHook",
+    "getResultHook",
+    "cancelMatch",
+    "resolveDispute",
+    "setSeasonLength",
+    "setRolloverLock",
+    "setLeaderboardMinMatches",
+    "setEloParams",
+    "setDecayParams",
+    "setBattleGracePeriod",
+    "setResultRateLimit",
+    "setFinalityDelay",
+    "addReferee",
+    "removeReferee",
+    "setRefereeOnly",
+    "setAccountsOnly",
+    "emergencyShutdown",
+    "refundStake",
+    "withdrawPools",
+    "selfTest",
+    "transferProfile",
+    "setEventsEnabled",
+    "setRecoveryMode",
+    "setMaintenance",
+    "setPausedCacheSeconds",
+    "forceDeletePlayer",
+    "setDefaultPlayerState",
+    "pause",
+    "pauseWithReason",
+    "unpause",
+    "getPlayerData",
+    "getPlayerFull",
+    "getPlayerStateHistory",
+    "getWinStreak",
+    "getPlayerDataOrDefault",
+    "getPausedSince",
+    "getQueueLength",
+    "getGames",
+    "getTournament",
+    "getQueue",
+    "getActivePlayerCount",
+    "getPlayerRank",
+    "getPlayerSummary",
+    "getPlayersAddedInSeason",
+    "getMatchDurationStats",
+    "getExtendedState",
+    "getTopByElo",
+];
+
+/// Identifier of a match.
+type MatchId = u64;
+
+/// Identifier of a game.
+type GameId = u32;
+
+/// Identifier of a tournament.
+type TournamentId = u64;
+
+/// Maximum number of seconds the paused flag of the state contract can be
+/// cached for.
+const MAX_PAUSED_CACHE_SECONDS: u64 = 60;
+
+/// The game that exists in every deployment.
+const DEFAULT_GAME_ID: GameId = 0;
 
 // Types
 
 enum VersusEvent {
     /// A new admin event.
     NewAdmin(NewAdminEvent),
+    /// The result hook rejected a battle result notification.
+    ResultHookFailed(ResultHookFailedEvent),
+    /// The admin reset the stats of one or all players.
+    StatsReset(StatsResetEvent),
+    /// The reward pool could not pay the reward of a winner.
+    RewardSkipped(RewardSkippedEvent),
+    /// A battle result was applied to the stats of a player.
+    ResultApplied(ResultAppliedEvent),
 }
 
 impl Serial for VersusEvent {
     fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
         match self {
             VersusEvent::NewAdmin(event) => {
-                out.write_u8(TOKEN_NEW_ADMIN_EVENT_TAG)?;
+                out.write_u8(NEW_ADMIN_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ResultHookFailed(event) => {
+                out.write_u8(RESULT_HOOK_FAILED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::StatsReset(event) => {
+                out.write_u8(STATS_RESET_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::RewardSkipped(event) => {
+                out.write_u8(REWARD_SKIPPED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::ResultApplied(event) => {
+                out.write_u8(RESULT_APPLIED_EVENT_TAG)?;
                 event.serial(out)
             }
         }
@@ -28,32 +199,263 @@ impl Serial for VersusEvent {
 #[derive(Serial, Deserial, Clone, SchemaType)]
 struct StateImplementation {
     /// The admin address can pause/unpause the contract
-    admin:              Address,
+    admin:                  Address,
     /// Addresses of the protocol
-    protocol_addresses: ProtocolAddressesImplementation,
+    protocol_addresses:     ProtocolAddressesImplementation,
+    /// The amount a player has to pay to be added.
+    registration_fee:       Amount,
+    /// The account receiving the registration fees.
+    fee_recipient:          AccountAddress,
+    /// The admin can update players directly while recovery mode is enabled.
+    recovery_mode:          bool,
+    /// The deposit held for the player waiting in the matchmaking queue of
+    /// each game.
+    queue_deposits:         BTreeMap<GameId, (AccountAddress, Amount)>,
+    /// Events are only logged while enabled.
+    events_enabled:         bool,
+    /// The minimum amount a player can stake on a match.
+    min_stake:              Amount,
+    /// Contract notified after every recorded battle result.
+    result_hook:            Option<ContractAddress>,
+    /// Entrypoint of the result hook contract that is invoked with the
+    /// `UpdateBattleResultParams`.
+    result_hook_entrypoint: String,
+    /// The amount paid from the reward pool to the winner of a match.
+    reward_per_win:         Amount,
+    /// The amount funded for win rewards and not paid out yet.
+    reward_pool:            Amount,
+    /// Entrypoints called through the proxy reject while maintenance mode is
+    /// enabled. Admin entrypoints remain usable.
+    maintenance:            bool,
+    /// The amount staked on all tournaments with `registerAndStake` and not
+    /// paid out as prizes yet.
+    tournament_pool:        Amount,
+    /// Seconds the paused flag of the state contract is cached for. Every
+    /// check invokes the state contract if zero.
+    paused_cache_seconds:   u64,
+    /// The cached paused flag of the state contract.
+    paused_cache:           Option<PausedCache>,
+}
+
+/// The paused flag of the state contract, as cached by `when_not_paused`.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+struct PausedCache {
+    /// The paused flag of the state contract.
+    paused:     bool,
+    /// The cached flag is used until this time.
+    expires_at: Timestamp,
 }
 
-#[derive(Debug, Serialize, SchemaType, Clone, Copy)]
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
 enum PlayerState {
     NotAdded,
     Active,
     Suspended
 }
 
-#[derive(Debug, Serialize, SchemaType, Clone, Copy)]
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
 enum BattleResult {
     NoResult,
     Win,
     Loss
 }
 
-#[derive(SchemaType, Serialize, PartialEq, Clone)]
+/// The data the state contract tracks for each player.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct PlayerData {
+    /// Serialization version of the player data.
+    version:        u8,
+    /// The player's state
+    state:          PlayerState,
+    /// Result of the last battle counted in `wins` or `losses`, `NoResult`
+    /// if there is none.
+    last_result:    BattleResult,
+    /// Number of battles the player has won.
+    wins:           u32,
+    /// Number of battles the player has lost.
+    losses:         u32,
+    /// The player's Elo rating.
+    elo:            i32,
+    /// The most recent state transitions of the player.
+    state_history:  StateHistory,
+    /// Number of consecutive wins if positive, of consecutive losses if
+    /// negative.
+    current_streak: i32,
+    /// Longest run of consecutive wins.
+    best_streak:    u32,
+    /// Time the player was added or their last battle result was recorded.
+    last_updated:   Timestamp,
+    /// Time the player was added.
+    registered_at:  Timestamp,
+    /// Battle results submitted for the player within the rate limit window.
+    rate_window:    RateWindow,
+    /// Season the stats were recorded in.
+    season:         u32,
+}
+
+/// The return type for the state contract function `getPlayerSummary`.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct PlayerSummary {
+    /// The complete data of the player.
+    player_data:    PlayerData,
+    /// Number of battles the player has won or lost.
+    total_matches:  u64,
+    /// The leaderboard rank of the player.
+    rank:           u32,
+    /// Number of consecutive wins if positive, of consecutive losses if
+    /// negative.
+    current_streak: i32,
+    /// The player's Elo rating.
+    elo:            i32,
+}
+
+/// The return type for the state contract function `getExtendedState`.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct ExtendedState {
+    /// Address of the versus proxy contract.
+    proxy_address:          ContractAddress,
+    /// Address of the versus implementation contract.
+    implementation_address: ContractAddress,
+    /// Contract is paused/unpaused.
+    paused:                 bool,
+    /// Why the contract was paused, cleared when it is unpaused.
+    pause_reason:           Option<String>,
+    /// The contract was shut down and rejects all mutations.
+    shutdown:               bool,
+    /// Number of players over all games.
+    player_count:           u32,
+    /// Number of active players.
+    active_player_count:    u32,
+    /// The number of the current season.
+    current_season:         u32,
+    /// Number of wins recorded over all players and seasons.
+    total_wins:             u64,
+    /// Number of losses recorded over all players and seasons.
+    total_losses:           u64,
+}
+
+/// The return type for the function `getExtendedState`.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct OperationalState {
+    /// The state and the counters of the state contract.
+    state:       ExtendedState,
+    /// The implementation is in maintenance mode and only accepts admin calls.
+    maintenance: bool,
+}
+
+/// The return type for the state contract function `getMatchDurationStats`.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+struct MatchDurationStats {
+    /// Number of recorded matches.
+    matches:         u64,
+    /// Sum of the durations of the recorded matches in seconds.
+    total_seconds:   u64,
+    /// Average duration of the recorded matches in whole seconds, zero if no
+    /// match was recorded.
+    average_seconds: u64,
+}
+
+/// The return type for the state contract function `getWinStreak`.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+struct WinStreak {
+    /// Number of consecutive wins if positive, of consecutive losses if
+    /// negative.
+    current: i32,
+    /// Longest run of consecutive wins.
+    best:    u32,
+}
+
+/// The battle results submitted for a player within the rate limit window.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct RateWindow {
+    /// Times of the submitted results, oldest first.
+    submitted: Vec<Timestamp>,
+}
+
+/// Fixed-length ring buffer of the most recent state transitions of a player.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct StateHistory {
+    /// The transitions, `None` for slots that were not written yet.
+    entries: [Option<(PlayerState, Timestamp)>; 8],
+    /// Index of the slot written next.
+    next:    u8,
+}
+
+/// The K-factors of the Elo rating update. Players with fewer completed
+/// matches than `provisional_matches` use `provisional_k`, so that the
+/// ratings of new players settle faster.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+struct EloParams {
+    /// K-factor of players with fewer than `provisional_matches` matches.
+    provisional_k:       i32,
+    /// K-factor of all other players.
+    established_k:       i32,
+    /// Number of completed matches after which a player is established.
+    provisional_matches: u32,
+}
+
+/// The rating decay of inactive players.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+struct DecayParams {
+    /// Seconds without activity after which the rating of a player decays.
+    inactivity_seconds: u64,
+    /// Rating lost by an inactive player per decay. Zero disables the decay.
+    amount:             i32,
+    /// Ratings do not decay below the floor.
+    floor:              i32,
+}
+
+/// The maximum number of battle results that can be submitted for a player
+/// within a sliding window: a result is rejected if `max_results` results were
+/// submitted for the player within the last `window_seconds`.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+struct RateLimit {
+    /// Results allowed per window, at most 32. Zero disables the rate limit.
+    max_results:    u32,
+    /// Length of the window in seconds, not zero while results are limited.
+    window_seconds: u64,
+}
+
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+enum MatchStatus {
+    Pending,
+    Completed,
+    Cancelled,
+    Disputed,
+    Resolved
+}
+
+/// A match between two players, as tracked by the state contract.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct Match {
+    /// The game the match is played in.
+    game_id:      GameId,
+    /// First player of the match.
+    player_a:     Address,
+    /// Second player of the match.
+    player_b:     Address,
+    /// Pending, Completed, Cancelled, Disputed or Resolved
+    status:       MatchStatus,
+    /// The winner of a completed match.
+    winner:       Option<Address>,
+    /// Amount staked by the first player.
+    stake_a:      Amount,
+    /// Amount staked by the second player.
+    stake_b:      Amount,
+    /// Time the match was completed at.
+    completed_at: Option<Timestamp>,
+    /// Elo rating gained by the winner and lost by the loser when the match
+    /// was completed.
+    elo_deltas:   (i32, i32),
+}
+
+#[derive(SchemaType, Serialize, PartialEq, Clone, Debug)]
 enum ProtocolAddressesImplementation {
     UnInitialized,
     Initialized {
-        /// Address of the w_ccd proxy contract.
+        /// Address of the versus proxy contract.
         proxy_address: ContractAddress,
-        /// Address of the w_ccd state contract.
+        /// Address of the versus state contract.
         state_address: ContractAddress,
     },
 }
@@ -65,19 +467,68 @@ struct NewAdminEvent {
     new_admin: Address,
 }
 
-/// NewImplementationEvent.
+/// ResultHookFailedEvent.
+#[derive(Serial)]
+struct ResultHookFailedEvent {
+    /// The result hook contract that rejected the notification.
+    hook:   ContractAddress,
+    /// The player whose battle result was recorded.
+    player: Address,
+}
+
+/// StatsResetEvent.
+#[derive(Serial)]
+struct StatsResetEvent {
+    /// The reset player, `None` if all players were reset.
+    player: Option<Address>,
+    /// The season the stats were reset in.
+    season: u32,
+}
+
+/// RewardSkippedEvent.
+#[derive(Serial)]
+struct RewardSkippedEvent {
+    /// The winner that was not rewarded.
+    winner: Address,
+    /// The reward per win.
+    reward: Amount,
+    /// The reward pool at the time of the match.
+    pool:   Amount,
+}
+
+/// ResultAppliedEvent.
 #[derive(Serial)]
-struct NewImplementationEvent {
-    /// New implementation address.
-    new_implementation: ContractAddress,
+struct ResultAppliedEvent {
+    /// The player whose battle result was applied.
+    player:      Address,
+    /// The applied battle result.
+    result:      BattleResult,
+    /// Wins of the player before the result.
+    wins_before: u32,
+    /// Wins of the player after the result.
+    wins_after:  u32,
+    /// Elo rating of the player before the result.
+    elo_before:  i32,
+    /// Elo rating of the player after the result.
+    elo_after:   i32,
+}
+
+/// The parameter type for the implementation contract function
+/// `setResultHook` and the return type of `getResultHook`.
+#[derive(Debug, Serialize, SchemaType, PartialEq)]
+struct ResultHook {
+    /// Contract to notify, or `None` to remove the hook.
+    hook:       Option<ContractAddress>,
+    /// Entrypoint of the hook contract to invoke.
+    entrypoint: String,
 }
 
 /// The parameter type for the implementation contract function `initialize`.
 #[derive(Serialize, SchemaType)]
 struct InitializeImplementationParams {
-    /// Address of the w_ccd proxy contract.
+    /// Address of the versus proxy contract.
     proxy_address: ContractAddress,
-    /// Address of the w_ccd state contract.
+    /// Address of the versus state contract.
     state_address: ContractAddress,
 }
 
@@ -91,19 +542,320 @@ struct SetPausedParams {
 /// The parameter type for the state contract function `updatePlayerState`.
 #[derive(Serialize, SchemaType)]
 struct UpdatePlayerStateParams {
+    /// The game of the player.
+    game_id: GameId,
     /// Player to update state.
-    player: Address,
+    player:  Address,
     /// Active or Suspended
-    state:  PlayerState,
+    state:   PlayerState,
+}
+
+/// The metadata and the number of players of a game, as returned by
+/// `getGames`.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct GameInfo {
+    /// Name of the game.
+    name:         Option<String>,
+    /// Time the game was created at.
+    created_at:   Timestamp,
+    /// Number of players added to the game.
+    player_count: u32,
+}
+
+/// A pairing of a round of a tournament.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct BracketMatch {
+    /// First player of the pairing.
+    player_a: Address,
+    /// Second player of the pairing.
+    player_b: Address,
+    /// The winner, once the result was reported.
+    winner:   Option<Address>,
+}
+
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+enum TournamentStatus {
+    /// The matches of the current round are played.
+    InProgress,
+    /// The final was played.
+    Completed {
+        /// The winner of the final.
+        champion: Address,
+    },
+}
+
+/// A single-elimination tournament, as returned by `getTournament`.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct Tournament {
+    /// Identifier of the tournament.
+    id:                 TournamentId,
+    /// The game the tournament is played in.
+    game_id:            GameId,
+    /// The players of the tournament.
+    players:            Vec<Address>,
+    /// The rounds played so far, the last one is the current round.
+    rounds:             Vec<Vec<BracketMatch>>,
+    /// InProgress or Completed
+    status:             TournamentStatus,
+    /// Shares of the prize pool of the top finishers in basis points, starting
+    /// with the champion.
+    payout_split:       Vec<u16>,
+    /// Whether the prizes of the completed tournament were distributed.
+    prizes_distributed: bool,
+}
+
+/// The parameter type for the implementation contract function
+/// `createTournament`.
+#[derive(Debug, Serialize, SchemaType, PartialEq)]
+struct CreateTournamentParams {
+    /// The game the tournament is played in.
+    game_id:      GameId,
+    /// The players of the tournament.
+    players:      Vec<Address>,
+    /// Shares of the prize pool of the top finishers in basis points, starting
+    /// with the champion. They have to add up to 10 000.
+    payout_split: Vec<u16>,
+}
+
+/// The parameter type for the implementation contract functions `addPlayer`,
+/// `getPlayerData`, `getPlayerSummary`, `resetPlayer`, the queue functions and
+/// the player queries.
+#[derive(Debug, Serialize, SchemaType, PartialEq)]
+struct GamePlayerParams {
+    /// The game of the player.
+    game_id: GameId,
+    /// The player.
+    player:  Address,
 }
 
 /// The parameter type for the state contract function `updateBattleResult`.
 #[derive(Serialize, SchemaType)]
 struct UpdateBattleResultParams {
+    /// The game the battle was played in.
+    game_id:    GameId,
     /// Player to update state.
-    player: Address,
+    player:     Address,
     /// Win or Loss
-    result: BattleResult,
+    result:     BattleResult,
+    /// Reference chosen by the client, so that a retried submission is
+    /// rejected instead of being applied twice.
+    client_ref: Option<[u8; 32]>,
+}
+
+/// The return type for the state contract function `updateBattleResult`.
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq)]
+struct ResultSnapshot {
+    /// Wins of the player before the result.
+    wins_before: u32,
+    /// Wins of the player after the result.
+    wins_after:  u32,
+    /// Elo rating of the player before the result.
+    elo_before:  i32,
+    /// Elo rating of the player after the result.
+    elo_after:   i32,
+}
+
+/// The return type for the state contract function `completeMatch`.
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq)]
+struct CompletedMatch {
+    /// The total amount staked on the match, owed to the winner.
+    pot:        Amount,
+    /// The winner can be rewarded, the players of the match were not rewarded
+    /// recently.
+    rewardable: bool,
+}
+
+/// The parameter type for the state contract function `recordMatch`.
+#[derive(Serialize, SchemaType)]
+struct RecordMatchParams {
+    /// The game the match was played in.
+    game_id:          GameId,
+    /// Player who won the match.
+    winner:           Address,
+    /// Player who lost the match.
+    loser:            Address,
+    /// How long the match took in seconds, has to be nonzero.
+    duration_seconds: u32,
+}
+
+/// The parameter type for the state contract function `reportTournamentMatch`.
+#[derive(Serialize, SchemaType)]
+struct ReportTournamentMatchParams {
+    /// The tournament the match was played in.
+    tournament_id: TournamentId,
+    /// Player who won the match.
+    winner:        Address,
+    /// Player who lost the match.
+    loser:         Address,
+}
+
+/// The parameter type for the state contract function `completeMatch`.
+#[derive(Serialize, SchemaType)]
+struct CompleteMatchParams {
+    /// The match to complete.
+    match_id: MatchId,
+    /// Player who won the match.
+    winner:   Address,
+}
+
+/// The parameter type for the implementation contract function `stakeMatch`.
+#[derive(Serialize, SchemaType)]
+struct StakeMatchParams {
+    /// The match to stake on.
+    match_id: MatchId,
+    /// Player of the match adding the stake.
+    player:   Address,
+}
+
+/// The parameter type for the state contract function `stakeMatch`.
+#[derive(Serialize, SchemaType)]
+struct StateStakeMatchParams {
+    /// The match to stake on.
+    match_id: MatchId,
+    /// Player of the match adding the stake.
+    player:   Address,
+    /// The staked amount.
+    amount:   Amount,
+}
+
+/// The parameter type for the state contract function `stakeTournament`.
+#[derive(Serialize, SchemaType)]
+struct StakeTournamentParams {
+    /// The tournament to stake on.
+    tournament_id: TournamentId,
+    /// Player adding the stake.
+    player:        Address,
+    /// The staked amount.
+    amount:        Amount,
+}
+
+/// The parameter type for the implementation contract function
+/// `registerAndStake`.
+#[derive(Serialize, SchemaType)]
+struct RegisterAndStakeParams {
+    /// The game of the player.
+    game_id:       GameId,
+    /// The player.
+    player:        Address,
+    /// The tournament to stake on.
+    tournament_id: TournamentId,
+}
+
+/// The parameter type for the state contract function `refundStake`.
+#[derive(Serialize, SchemaType)]
+struct RefundStakeParams {
+    /// The pending match the stake was placed on.
+    match_id: MatchId,
+    /// Player of the match refunded their stake.
+    player:   Address,
+}
+
+/// The parameter type for the state contract function `disputeMatch`.
+#[derive(Serialize, SchemaType)]
+struct DisputeMatchParams {
+    /// The match to dispute.
+    match_id: MatchId,
+    /// Player of the match disputing the result.
+    player:   Address,
+}
+
+/// The parameter type for the state contract function `resolveDispute`.
+#[derive(Serialize, SchemaType)]
+struct ResolveDisputeParams {
+    /// The disputed match.
+    match_id: MatchId,
+    /// Whether the result is overturned instead of upheld.
+    overturn: bool,
+}
+
+/// The parameter type for the implementation contract function
+/// `setRegistrationFee`.
+#[derive(Serialize, SchemaType)]
+struct SetRegistrationFeeParams {
+    /// The amount a player has to pay to be added.
+    registration_fee: Amount,
+    /// The account receiving the registration fees.
+    fee_recipient:    AccountAddress,
+}
+
+/// The parameter type for the paginated state contract functions, e.g.
+/// `getGames`.
+#[derive(Serialize, SchemaType)]
+struct PaginationParams {
+    /// Number of players to skip.
+    offset: u32,
+    /// Maximum number of players to visit.
+    limit:  u32,
+}
+
+/// The parameter type for the state contract function `getQueue`.
+#[derive(Serialize, SchemaType)]
+struct GetQueueParams {
+    /// First ticket to look at, the `next` cursor of the previous page.
+    from:  u64,
+    /// Maximum number of players to return.
+    limit: u32,
+}
+
+/// The return type for the state contract function `getQueue`.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct QueuePage {
+    /// The waiting players of this page with the game they wait in, in the
+    /// order they joined the queue.
+    players: Vec<(GameId, Address)>,
+    /// Cursor to request the next page with, if there are more tickets.
+    next:    Option<u64>,
+}
+
+/// The parameter type for the state contract functions `resetAllPlayers` and
+/// `decayRatings`, which visit the players in pages.
+#[derive(Serialize, SchemaType)]
+struct PlayerCursorParams {
+    /// Key of the last player of the previous page, `None` for the first page.
+    after: Option<(GameId, Address)>,
+    /// Maximum number of players to visit.
+    limit: u32,
+}
+
+/// The return type for the state contract function `resetAllPlayers`.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+struct ResetProgress {
+    /// Key of the last reset player to continue the reset after.
+    next: Option<(GameId, Address)>,
+    /// Whether all players were reset.
+    done: bool,
+}
+
+/// The parameter type for the state contract function `getTopByElo`.
+#[derive(Serialize, SchemaType)]
+struct TopByEloParams {
+    /// The game to get the leaderboard of.
+    game_id: GameId,
+    /// Maximum number of players to return.
+    limit:   u32,
+}
+
+/// The parameter type for the state contract function `transferProfile`.
+#[derive(Serialize, SchemaType)]
+struct TransferProfileParams {
+    /// The game the player data is moved in.
+    game_id: GameId,
+    /// Address the player data is moved from.
+    from:    Address,
+    /// Address the player data is moved to.
+    to:      Address,
+}
+
+/// The return value of `selfTest`. Each field records whether a step of the
+/// self test passed.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+struct SelfTestReport {
+    /// The state contract returned its extended state.
+    reachable: bool,
+    /// The state contract points to the proxy and this implementation.
+    wired:     bool,
+    /// The player data of the throwaway player was read back as not added.
+    read_back: bool,
 }
 
 /// Your smart contract errors.
@@ -132,6 +884,32 @@ enum CustomContractError {
     OnlyAdmin,
     /// Already added as player
     AlreadyAdded,
+    /// Failed to invoke a transfer.
+    InvokeTransferError,
+    /// The amount sent is less than the registration fee.
+    InsufficientFee,
+    /// Only accounts can receive refunds.
+    OnlyAccount,
+    /// The staked amount is zero or less than the minimum stake.
+    StakeTooLow,
+    /// The state contract returned no value or a value of an unexpected shape.
+    MalformedStateResponse,
+    /// The result hook entrypoint is empty or invalid, or the hook is one of
+    /// the protocol contracts.
+    InvalidResultHook,
+    /// The contract is in maintenance mode and only accepts admin calls.
+    MaintenanceMode,
+    /// CCD were sent to an entrypoint that does not accept any.
+    UnexpectedAmount,
+    /// The paused flag can be cached for at most `MAX_PAUSED_CACHE_SECONDS`.
+    PausedCacheTooLong,
+    /// Players can only act for themselves, as the account that started the
+    /// transaction.
+    OnlyInvoker,
+    /// The state contract is not shut down.
+    NotShutDown,
+    /// Newly added players can not start in the `NotAdded` state.
+    InvalidDefaultPlayerState,
 }
 
 type ContractResult<A> = Result<A, CustomContractError>;
@@ -151,24 +929,45 @@ impl<T> From<CallContractError<T>> for CustomContractError {
     fn from(_cce: CallContractError<T>) -> Self { Self::InvokeContractError }
 }
 
+/// Mapping errors related to transfer invocations to CustomContractError.
+impl From<TransferError> for CustomContractError {
+    fn from(_te: TransferError) -> Self { Self::InvokeTransferError }
+}
+
 impl StateImplementation {
     /// Creates the new state of the `implementation` contract.
     /// The ProtocolAddressesState is uninitialized.
     /// The ProtocolAddressesState has to be set with the `initialize`
     /// function after the `proxy` contract is deployed.
-    fn new(admin: Address) -> Self {
+    /// Adding players is free and fees go to the instantiater until the
+    /// admin sets a registration fee.
+    fn new(admin: AccountAddress) -> Self {
         // Setup state.
         StateImplementation {
-            admin,
-            protocol_addresses: ProtocolAddressesImplementation::UnInitialized,
+            admin:                  Address::Account(admin),
+            protocol_addresses:     ProtocolAddressesImplementation::UnInitialized,
+            registration_fee:       Amount::zero(),
+            fee_recipient:          admin,
+            recovery_mode:          false,
+            queue_deposits:         BTreeMap::new(),
+            events_enabled:         true,
+            min_stake:              Amount::zero(),
+            result_hook:            None,
+            result_hook_entrypoint: String::new(),
+            reward_per_win:         Amount::zero(),
+            reward_pool:            Amount::zero(),
+            maintenance:            false,
+            tournament_pool:        Amount::zero(),
+            paused_cache_seconds:   0,
+            paused_cache:           None,
         }
     }
 
-    /// Check if an player is added in versus
+    /// Check if an player is added in a game
     fn is_added<S>(
         &self,
         state_address: &ContractAddress,
-        player: &Address,
+        player: &GamePlayerParams,
         host: &impl HasHost<StateImplementation, StateApiType = S>,
     ) -> ContractResult<bool> {
         let is_added = host.invoke_contract_read_only(
@@ -193,13 +992,13 @@ fn contract_init<S: HasStateApi>(
     logger: &mut impl HasLogger,
 ) -> InitResult<StateImplementation> {
     // Get the instantiater of this contract instance.
-    let invoker = Address::Account(ctx.init_origin());
+    let invoker = ctx.init_origin();
     // Construct the initial contract state.
     let state = StateImplementation::new(invoker);
 
     // Log a new admin event.
     logger.log(&VersusEvent::NewAdmin(NewAdminEvent {
-        new_admin: invoker,
+        new_admin: Address::Account(invoker),
     }))?;
 
     Ok(state)
@@ -249,6 +1048,37 @@ fn only_proxy(proxy_address: ContractAddress, sender: Address) -> ContractResult
     Ok(())
 }
 
+/// Helper function to ensure that a player acts for themselves, i.e. that the
+/// player is the account that started the transaction.
+fn only_invoker(ctx: &impl HasReceiveContext, player: Address) -> ContractResult<()> {
+    ensure_eq!(player, Address::Account(ctx.invoker()), CustomContractError::OnlyInvoker);
+
+    Ok(())
+}
+
+/// Helper function to ensure that a call comes from the proxy, or from the
+/// admin while recovery mode is enabled. This keeps players updatable if the
+/// proxy is no longer usable.
+fn only_proxy_or_recovery_admin(
+    state: &StateImplementation,
+    proxy_address: ContractAddress,
+    sender: Address,
+) -> ContractResult<()> {
+    if state.recovery_mode && sender == state.admin {
+        return Ok(());
+    }
+
+    only_proxy(proxy_address, sender)
+}
+
+/// Helper function to ensure that the contract is not in maintenance mode.
+/// The admin is exempt, so that recovery mode keeps working.
+fn when_not_in_maintenance(state: &StateImplementation, sender: Address) -> ContractResult<()> {
+    ensure!(!state.maintenance || sender == state.admin, CustomContractError::MaintenanceMode);
+
+    Ok(())
+}
+
 // Getter and setter functions
 
 /// Function to view state of the implementation contract.
@@ -258,13 +1088,71 @@ fn only_proxy(proxy_address: ContractAddress, sender: Address) -> ContractResult
     return_value = "StateImplementation",
     error = "CustomContractError"
 )]
-fn contract_implementation_view<'a, 'b, S: HasStateApi>(
-    _ctx: &'b impl HasReceiveContext,
+fn contract_implementation_view<'a, S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
     host: &'a impl HasHost<StateImplementation, StateApiType = S>,
 ) -> ContractResult<&'a StateImplementation> {
     Ok(host.state())
 }
 
+/// Function to get the proxy and state addresses the implementation contract
+/// was initialized with.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getProtocolAddresses",
+    return_value = "ProtocolAddressesImplementation",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_protocol_addresses<'a, S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &'a impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<&'a ProtocolAddressesImplementation> {
+    Ok(&host.state().protocol_addresses)
+}
+
+/// Function to get the version of the implementation contract.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getVersion",
+    return_value = "u32",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_version<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<u32> {
+    Ok(IMPLEMENTATION_VERSION)
+}
+
+/// Function to list the names of all entrypoints of the implementation
+/// contract, so that tooling can discover them.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getEntrypoints",
+    return_value = "Vec<String>",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_entrypoints<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<Vec<String>> {
+    Ok(ENTRYPOINTS.iter().map(|name| String::from(*name)).collect())
+}
+
+/// Function to check whether the sender is the admin of the implementation.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "amIAdmin",
+    return_value = "bool",
+    error = "CustomContractError"
+)]
+fn contract_implementation_am_i_admin<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<bool> {
+    Ok(ctx.sender() == host.state().admin)
+}
+
 /// Helper function to get protocol addresses from the implementation contract.
 fn get_protocol_addresses_from_implementation<S>(
     host: &impl HasHost<StateImplementation, StateApiType = S>,
@@ -280,48 +1168,93 @@ fn get_protocol_addresses_from_implementation<S>(
     }
 }
 
-/// Helper function to ensure contract is not paused.
+/// Helper function to ensure contract is not paused. If `paused_cache_seconds`
+/// is set, the paused flag of the state contract is cached for that long for
+/// calls forwarded by the proxy, so that repeated calls skip the invoke.
+/// Pausing or unpausing through this contract invalidates the cache. A pause
+/// set on the state contract by `pauseAll` of the proxy is only seen once the
+/// cache expires, but the proxy itself rejects forwarded calls while it is
+/// paused. Direct calls, e.g. during a recovery, always query the state
+/// contract, as nothing else stops them while the state contract is paused.
 fn when_not_paused<S>(
     state_address: &ContractAddress,
+    ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<StateImplementation, StateApiType = S>,
 ) -> ContractResult<()> {
-    let paused = host.invoke_contract_read_only(
-        state_address,
-        &Parameter(&[]),
-        EntrypointName::new_unchecked("getPaused"),
-        Amount::zero(),
-    )?;
+    // The slot time is only needed while the cache is enabled for the caller.
+    let (proxy_address, _state_address) = get_protocol_addresses_from_implementation(host)?;
+    let cache_seconds = host.state().paused_cache_seconds;
+    let now = (cache_seconds > 0 && ctx.sender() == Address::Contract(proxy_address))
+        .then(|| ctx.metadata().slot_time());
+    let cached = host
+        .state()
+        .paused_cache
+        .filter(|cache| now.is_some_and(|now| now < cache.expires_at));
+    let paused = match cached {
+        Some(cache) => cache.paused,
+        None => {
+            let paused = host.invoke_contract_read_only(
+                state_address,
+                &Parameter(&[]),
+                EntrypointName::new_unchecked("getPaused"),
+                Amount::zero(),
+            )?;
+
+            // It is expected that this contract is initialized with the versus
+            // state contract (a V1 contract). In that case, the paused variable
+            // can be queried from the state contract without error. A failed
+            // call is reported as `InvokeContractError`, an unexpected response
+            // as `MalformedStateResponse`.
+            let paused: bool = paused
+                .ok_or(CustomContractError::MalformedStateResponse)?
+                .get()
+                .map_err(|_| CustomContractError::MalformedStateResponse)?;
 
-    // It is expected that this contract is initialized with the w_ccd_state
-    // contract (a V1 contract). In that case, the paused variable can be
-    // queried from the state contract without error.
-    let paused: bool = paused
-        .ok_or(CustomContractError::StateInvokeError)?
-        .get()?;
+            if let Some(now) = now {
+                host.state_mut().paused_cache = Some(PausedCache {
+                    paused,
+                    expires_at: now
+                        .checked_add(Duration::from_seconds(cache_seconds))
+                        .unwrap_or(now),
+                });
+            }
+            paused
+        }
+    };
     // Check that contract is not paused.
     ensure!(!paused, CustomContractError::ContractPaused);
     Ok(())
 }
 
-/// Update player state.
+/// Update player state. The entrypoint is payable only to reject CCD forwarded
+/// by the proxy fallback with `UnexpectedAmount`.
 #[receive(
     contract = "Versus-Implementation",
     name = "updatePlayerState",
     parameter = "UpdatePlayerStateParams",
     error = "CustomContractError",
-    mutable
+    mutable,
+    payable
 )]
 fn contract_implementation_update_player_state<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
-    host: &mut impl HasHost<StateImplementation, StateApiType = S>
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+    amount: Amount,
 ) -> ContractResult<()> {
+    // Check that no CCD were sent along.
+    ensure!(amount == Amount::zero(), CustomContractError::UnexpectedAmount);
+
     let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
 
-    // Can be only called through the fallback function on the proxy.
-    only_proxy(proxy_address, ctx.sender())?;
+    // Can be only called through the fallback function on the proxy, or by the
+    // admin in recovery mode.
+    only_proxy_or_recovery_admin(host.state(), proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
 
     // Check that contract is not paused.
-    when_not_paused(&state_address, host)?;
+    when_not_paused(&state_address, ctx, host)?;
 
     // Parse the parameter.
     let input: UpdatePlayerStateParams = ctx.parameter_cursor().get()?;
@@ -329,6 +1262,7 @@ fn contract_implementation_update_player_state<S: HasStateApi>(
     host.invoke_contract(
         &state_address,
         &UpdatePlayerStateParams {
+            game_id: input.game_id,
             player: input.player,
             state: input.state,
         },
@@ -336,6 +1270,17 @@ fn contract_implementation_update_player_state<S: HasStateApi>(
         Amount::zero(),
     )?;
 
+    // A player that is not active is removed from the queue of the game, so
+    // their deposit is refunded.
+    let queued = host
+        .state()
+        .queue_deposits
+        .get(&input.game_id)
+        .is_some_and(|(account, _)| input.player == Address::Account(*account));
+    if input.state != PlayerState::Active && queued {
+        refund_queue_deposit(host, input.game_id)?;
+    }
+
     // Log the update operator event.
     // host.invoke_contract(
     //     &proxy_address,
@@ -353,36 +1298,81 @@ fn contract_implementation_update_player_state<S: HasStateApi>(
     Ok(())
 }
 
-/// Update battle result.
+/// Update battle result. The entrypoint is payable only to reject CCD
+/// forwarded by the proxy fallback with `UnexpectedAmount`.
 #[receive(
     contract = "Versus-Implementation",
     name = "updateBattleResult",
     parameter = "UpdateBattleResultParams",
     error = "CustomContractError",
-    mutable
+    enable_logger,
+    mutable,
+    payable
 )]
 fn contract_implementation_update_battle_result<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
-    host: &mut impl HasHost<StateImplementation, StateApiType = S>
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+    amount: Amount,
+    logger: &mut impl HasLogger,
 ) -> ContractResult<()> {
+    // Check that no CCD were sent along.
+    ensure!(amount == Amount::zero(), CustomContractError::UnexpectedAmount);
+
     let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
 
-    // Can be only called through the fallback function on the proxy.
-    only_proxy(proxy_address, ctx.sender())?;
+    // Can be only called through the fallback function on the proxy, or by the
+    // admin in recovery mode.
+    only_proxy_or_recovery_admin(host.state(), proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
 
     // Check that contract is not paused.
-    when_not_paused(&state_address, host)?;
+    when_not_paused(&state_address, ctx, host)?;
 
     // Parse the parameter.
     let input: UpdateBattleResultParams = ctx.parameter_cursor().get()?;
 
-    host.invoke_contract(
+    let (_state_modified, snapshot) = host.invoke_contract(
         &state_address,
         &input,
         EntrypointName::new_unchecked("updateBattleResult"),
         Amount::zero(),
     )?;
 
+    let snapshot: ResultSnapshot = snapshot.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    // Log the stats before and after the result, so that indexers do not have
+    // to replay the state.
+    if host.state().events_enabled {
+        logger.log(&VersusEvent::ResultApplied(ResultAppliedEvent {
+            player:      input.player,
+            result:      input.result,
+            wins_before: snapshot.wins_before,
+            wins_after:  snapshot.wins_after,
+            elo_before:  snapshot.elo_before,
+            elo_after:   snapshot.elo_after,
+        }))?;
+    }
+
+    // Notify the result hook. The result is already recorded, so a failing
+    // hook is only logged.
+    if let Some(hook) = host.state().result_hook {
+        let entrypoint = host.state().result_hook_entrypoint.clone();
+        let notified = match EntrypointName::new(&entrypoint) {
+            Ok(entrypoint) => {
+                host.invoke_contract(&hook, &input, entrypoint, Amount::zero()).is_ok()
+            }
+            Err(_) => false,
+        };
+        if !notified && host.state().events_enabled {
+            logger.log(&VersusEvent::ResultHookFailed(ResultHookFailedEvent {
+                hook,
+                player: input.player,
+            }))?;
+        }
+    }
+
     // Log the update operator event.
     // host.invoke_contract(
     //     &proxy_address,
@@ -400,15 +1390,15 @@ fn contract_implementation_update_battle_result<S: HasStateApi>(
     Ok(())
 }
 
-/// Add new player.
+/// Record the result of a match between two players.
 #[receive(
     contract = "Versus-Implementation",
-    name = "addPlayer",
-    parameter = "Address",
+    name = "recordMatch",
+    parameter = "RecordMatchParams",
     error = "CustomContractError",
     mutable
 )]
-fn contract_implementation_add_player<S: HasStateApi>(
+fn contract_implementation_record_match<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<StateImplementation, StateApiType = S>
 ) -> ContractResult<()> {
@@ -417,224 +1407,4312 @@ fn contract_implementation_add_player<S: HasStateApi>(
     // Can be only called through the fallback function on the proxy.
     only_proxy(proxy_address, ctx.sender())?;
 
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
     // Check that contract is not paused.
-    when_not_paused(&state_address, host)?;
+    when_not_paused(&state_address, ctx, host)?;
 
     // Parse the parameter.
-    let input: Address = ctx.parameter_cursor().get()?;
-
-    ensure!(
-        host.state().is_added(&state_address, &input, host)?,
-        CustomContractError::AlreadyAdded
-    );
+    let input: RecordMatchParams = ctx.parameter_cursor().get()?;
 
     host.invoke_contract(
         &state_address,
         &input,
-        EntrypointName::new_unchecked("addPlayer"),
+        EntrypointName::new_unchecked("recordMatch"),
         Amount::zero(),
     )?;
 
-    // Log the update operator event.
-    // host.invoke_contract(
-    //     &proxy_address,
-    //     &UpdateOperator(
-    //         UpdateOperatorEvent {
-    //             owner:    sender,
-    //             operator: param.operator,
-    //             update:   param.update,
-    //         },
-    //     ),
-    //     EntrypointName::new_unchecked("logEvent"),
-    //     Amount::zero(),
-    // )?;
-
     Ok(())
 }
 
-/// This functions allows the admin of the implementation to transfer the
-/// address to a new admin.
+/// Report the result of a match of the current round of a tournament. Only
+/// referees can report results.
 #[receive(
     contract = "Versus-Implementation",
-    name = "updateAdmin",
-    parameter = "Address",
+    name = "reportTournamentMatch",
+    parameter = "ReportTournamentMatchParams",
     error = "CustomContractError",
-    enable_logger,
     mutable
 )]
-fn contract_implementation_update_admin<S: HasStateApi>(
+fn contract_implementation_report_tournament_match<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<StateImplementation, StateApiType = S>,
-    logger: &mut impl HasLogger,
 ) -> ContractResult<()> {
-    // Check that only the old admin is authorized to update the admin address.
-    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
-    // Parse the parameter.
-    let new_admin = ctx.parameter_cursor().get()?;
-    // Update admin.
-    host.state_mut().admin = new_admin;
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
 
-    // Log a new admin event.
-    logger.log(&VersusEvent::NewAdmin(NewAdminEvent {
-        new_admin,
-    }))?;
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
+
+    // Parse the parameter.
+    let input: ReportTournamentMatchParams = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &input,
+        EntrypointName::new_unchecked("reportTournamentMatch"),
+        Amount::zero(),
+    )?;
 
     Ok(())
 }
 
-/// This function pauses the contract. Only the
-/// admin of the implementation can call this function.
+/// Advance a tournament to the next round once all results of the current
+/// round are reported, or complete it after the final. Only referees can
+/// advance a tournament.
 #[receive(
     contract = "Versus-Implementation",
-    name = "pause",
+    name = "advanceRound",
+    parameter = "TournamentId",
     error = "CustomContractError",
     mutable
 )]
-fn contract_pause<S: HasStateApi>(
+fn contract_implementation_advance_round<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<StateImplementation, StateApiType = S>,
 ) -> ContractResult<()> {
-    // Check that only the current admin can pause.
-    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
 
-    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
+
+    // Parse the parameter.
+    let tournament_id: TournamentId = ctx.parameter_cursor().get()?;
 
     host.invoke_contract(
         &state_address,
-        &SetPausedParams {
-            paused: true,
-        },
-        EntrypointName::new_unchecked("setPaused"),
+        &tournament_id,
+        EntrypointName::new_unchecked("advanceRound"),
         Amount::zero(),
     )?;
 
     Ok(())
 }
 
-/// Function to unpause the contract by the admin.
+/// Helper function to refund the deposit of the player waiting in the queue of
+/// a game.
+fn refund_queue_deposit<S>(
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+    game_id: GameId,
+) -> ContractResult<()> {
+    if let Some((account, deposit)) = host.state_mut().queue_deposits.remove(&game_id) {
+        host.invoke_transfer(&account, deposit)?;
+    }
+
+    Ok(())
+}
+
+/// Add an active player to the matchmaking queue of a game. If another player
+/// is already waiting in the game, both are paired in a new match and its id
+/// is returned. The amount sent is held as deposit while the player waits and
+/// is refunded when they leave the queue or get paired. Players can only join
+/// themselves.
 #[receive(
     contract = "Versus-Implementation",
-    name = "unpause",
+    name = "joinQueue",
+    parameter = "GamePlayerParams",
+    return_value = "Option<MatchId>",
+    error = "CustomContractError",
+    mutable,
+    payable
+)]
+fn contract_implementation_join_queue<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+    amount: Amount,
+) -> ContractResult<Option<MatchId>> {
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
+
+    // Parse the parameter.
+    let input: GamePlayerParams = ctx.parameter_cursor().get()?;
+
+    // Check that the player joins themselves.
+    only_invoker(ctx, input.player)?;
+
+    let (_state_modified, match_id) = host.invoke_contract(
+        &state_address,
+        &input,
+        EntrypointName::new_unchecked("joinQueue"),
+        Amount::zero(),
+    )?;
+
+    let match_id: Option<MatchId> = match_id.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    if amount > Amount::zero() {
+        // Deposits can only be refunded to accounts.
+        let account = match input.player {
+            Address::Account(account) => account,
+            Address::Contract(_) => bail!(CustomContractError::OnlyAccount),
+        };
+        if match_id.is_some() {
+            host.invoke_transfer(&account, amount)?;
+        } else {
+            host.state_mut().queue_deposits.insert(input.game_id, (account, amount));
+        }
+    }
+
+    // The waiting player was paired, so their deposit is released.
+    if match_id.is_some() {
+        refund_queue_deposit(host, input.game_id)?;
+    }
+
+    Ok(match_id)
+}
+
+/// Remove the waiting player from the matchmaking queue of a game and refund
+/// their deposit. Players can only remove themselves.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "leaveQueue",
+    parameter = "GamePlayerParams",
     error = "CustomContractError",
     mutable
 )]
-fn contract_un_pause<S: HasStateApi>(
+fn contract_implementation_leave_queue<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>
+) -> ContractResult<()> {
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
+
+    // Parse the parameter.
+    let input: GamePlayerParams = ctx.parameter_cursor().get()?;
+
+    // Check that the player removes themselves.
+    only_invoker(ctx, input.player)?;
+
+    // The state contract rejects if the player is not waiting in the queue.
+    host.invoke_contract(
+        &state_address,
+        &input,
+        EntrypointName::new_unchecked("leaveQueue"),
+        Amount::zero(),
+    )?;
+
+    refund_queue_deposit(host, input.game_id)?;
+
+    Ok(())
+}
+
+/// Add an active player to the pairing queue, where they wait until they are
+/// paired with another player of the same game by `pairPlayers`. Players can
+/// only join themselves.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "joinPairingQueue",
+    parameter = "GamePlayerParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_join_pairing_queue<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<StateImplementation, StateApiType = S>,
 ) -> ContractResult<()> {
-    // Check that only the current admin can un_pause.
-    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
 
-    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
+
+    // Parse the parameter.
+    let input: GamePlayerParams = ctx.parameter_cursor().get()?;
+
+    // Check that the player joins themselves.
+    only_invoker(ctx, input.player)?;
 
     host.invoke_contract(
         &state_address,
-        &SetPausedParams {
-            paused: false,
-        },
-        EntrypointName::new_unchecked("setPaused"),
+        &input,
+        EntrypointName::new_unchecked("joinPairingQueue"),
         Amount::zero(),
     )?;
 
     Ok(())
 }
 
-/// Get the player data
+/// Remove a waiting player from the pairing queue of a game. Players can only
+/// remove themselves.
 #[receive(
     contract = "Versus-Implementation",
-    name = "getPlayerData",
-    parameter = "Address",
-    return_value = "(PlayerState, BattleResult)",
+    name = "leavePairingQueue",
+    parameter = "GamePlayerParams",
     error = "CustomContractError",
     mutable
 )]
-fn contract_implementation_get_player_data<S: HasStateApi>(
+fn contract_implementation_leave_pairing_queue<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<StateImplementation, StateApiType = S>,
-) -> ContractResult<(PlayerState, BattleResult)> {
+) -> ContractResult<()> {
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
+
     // Parse the parameter.
-    let param: Address = ctx.parameter_cursor().get()?;
-    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+    let input: GamePlayerParams = ctx.parameter_cursor().get()?;
 
-    let player_data = host.invoke_contract_read_only(
+    // Check that the player removes themselves.
+    only_invoker(ctx, input.player)?;
+
+    // The state contract rejects if the player is not waiting in the queue.
+    host.invoke_contract(
         &state_address,
-        &param,
-        EntrypointName::new_unchecked("getPlayerData"),
+        &input,
+        EntrypointName::new_unchecked("leavePairingQueue"),
         Amount::zero(),
     )?;
 
-    let (player_state, player_result) = player_data.ok_or(CustomContractError::StateInvokeError)?.get()?;
+    Ok(())
+}
 
-    Ok((player_state, player_result))
+/// Pair the players waiting in the pairing queue in new matches and return
+/// their ids. See `pairPlayers` on the state contract for how players are
+/// paired.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "pairPlayers",
+    return_value = "Vec<MatchId>",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_pair_players<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<Vec<MatchId>> {
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
+
+    let (_state_modified, match_ids) = host.invoke_contract(
+        &state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("pairPlayers"),
+        Amount::zero(),
+    )?;
+
+    let match_ids = match_ids.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(match_ids)
+}
+
+/// Stake on a pending match. Players can only stake for themselves, and only on
+/// matches between accounts, so that the pot can always be paid out. The
+/// amount sent has to be at least the minimum stake. The stakes of both players
+/// are paid to the winner when the match is completed and refunded when it is
+/// cancelled.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "stakeMatch",
+    parameter = "StakeMatchParams",
+    error = "CustomContractError",
+    mutable,
+    payable
+)]
+fn contract_implementation_stake_match<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+    amount: Amount,
+) -> ContractResult<()> {
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
+
+    // Parse the parameter.
+    let input: StakeMatchParams = ctx.parameter_cursor().get()?;
+
+    // Check that the stake is not dust.
+    ensure!(
+        amount > Amount::zero() && amount >= host.state().min_stake,
+        CustomContractError::StakeTooLow
+    );
+    // Check that players only stake for themselves.
+    only_invoker(ctx, input.player)?;
+
+    host.invoke_contract(
+        &state_address,
+        &StateStakeMatchParams {
+            match_id: input.match_id,
+            player: input.player,
+            amount,
+        },
+        EntrypointName::new_unchecked("stakeMatch"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Helper function to pay the reward per win from the reward pool to the
+/// winner of a match. The reward is skipped and a `RewardSkipped` event is
+/// logged if the pool can not cover it or the winner is not an account.
+fn pay_win_reward<S>(
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+    logger: &mut impl HasLogger,
+    winner: Address,
+) -> ContractResult<()> {
+    let reward = host.state().reward_per_win;
+    if reward == Amount::zero() {
+        return Ok(());
+    }
+
+    let pool = host.state().reward_pool;
+    match winner {
+        Address::Account(account) if pool >= reward => {
+            host.state_mut().reward_pool = pool - reward;
+            host.invoke_transfer(&account, reward)?;
+        }
+        _ => {
+            if host.state().events_enabled {
+                logger.log(&VersusEvent::RewardSkipped(RewardSkippedEvent {
+                    winner,
+                    reward,
+                    pool,
+                }))?;
+            }
+        }
+    }
+
+    Ok(())
 }
 
-// #[concordium_cfg_test]
-// mod tests {
-//     use super::*;
-//     use test_infrastructure::*;
+/// Complete a pending match, pay the stakes to the winner and reward the
+/// winner from the reward pool. The state contract only accepts the result
+/// when the transaction was started by a referee, and only lets the winner be
+/// rewarded once per pair of players within its reward cooldown.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "completeMatch",
+    parameter = "CompleteMatchParams",
+    error = "CustomContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_implementation_complete_match<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
 
-//     type ContractResult<A> = Result<A, Error>;
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
 
-//     #[concordium_test]
-//     /// Test that initializing the contract succeeds with some state.
-//     fn test_init() {
-//         let ctx = TestInitContext::empty();
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
 
-//         let mut state_builder = TestStateBuilder::new();
+    // Parse the parameter.
+    let input: CompleteMatchParams = ctx.parameter_cursor().get()?;
 
-//         let state_result = init(&ctx, &mut state_builder);
-//         state_result.expect_report("Contract initialization results in error");
-//     }
+    let (_state_modified, completed) = host.invoke_contract(
+        &state_address,
+        &input,
+        EntrypointName::new_unchecked("completeMatch"),
+        Amount::zero(),
+    )?;
 
-//     #[concordium_test]
-//     /// Test that invoking the `receive` endpoint with the `false` parameter
-//     /// succeeds in updating the contract.
-//     fn test_throw_no_error() {
-//         let ctx = TestInitContext::empty();
+    let completed: CompletedMatch =
+        completed.ok_or(CustomContractError::StateInvokeError)?.get()?;
 
-//         let mut state_builder = TestStateBuilder::new();
+    if completed.pot > Amount::zero() {
+        let winner = match input.winner {
+            Address::Account(winner) => winner,
+            Address::Contract(_) => bail!(CustomContractError::OnlyAccount),
+        };
+        host.invoke_transfer(&winner, completed.pot)?;
+    }
 
-//         // Initializing state
-//         let initial_state = init(&ctx, &mut state_builder).expect("Initialization should pass");
+    if completed.rewardable {
+        pay_win_reward(host, logger, input.winner)?;
+    }
 
-//         let mut ctx = TestReceiveContext::empty();
+    Ok(())
+}
 
-//         let throw_error = false;
-//         let parameter_bytes = to_bytes(&throw_error);
-//         ctx.set_parameter(&parameter_bytes);
+/// Add the amount sent to the reward pool that pays the winners of matches.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "fundRewards",
+    error = "CustomContractError",
+    mutable,
+    payable
+)]
+fn contract_implementation_fund_rewards<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+    amount: Amount,
+) -> ContractResult<()> {
+    let (proxy_address, _state_address) = get_protocol_addresses_from_implementation(host)?;
 
-//         let mut host = TestHost::new(initial_state, state_builder);
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
 
-//         // Call the contract function.
-//         let result: ContractResult<()> = receive(&ctx, &mut host);
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
 
-//         // Check the result.
-//         claim!(result.is_ok(), "Results in rejection");
-//     }
+    host.state_mut().reward_pool += amount;
 
-//     #[concordium_test]
-//     /// Test that invoking the `receive` endpoint with the `true` parameter
-//     /// results in the `YourError` being thrown.
-//     fn test_throw_error() {
-//         let ctx = TestInitContext::empty();
+    Ok(())
+}
 
-//         let mut state_builder = TestStateBuilder::new();
+/// Dispute the result of a completed match. Players can only dispute for
+/// themselves. The state contract rejects disputes by players not in the
+/// match and disputes after the dispute window.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "disputeMatch",
+    parameter = "DisputeMatchParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_dispute_match<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
 
-//         // Initializing state
-//         let initial_state = init(&ctx, &mut state_builder).expect("Initialization should pass");
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
 
-//         let mut ctx = TestReceiveContext::empty();
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
 
-//         let throw_error = true;
-//         let parameter_bytes = to_bytes(&throw_error);
-//         ctx.set_parameter(&parameter_bytes);
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
 
-//         let mut host = TestHost::new(initial_state, state_builder);
+    // Parse the parameter.
+    let input: DisputeMatchParams = ctx.parameter_cursor().get()?;
 
-//         // Call the contract function.
-//         let error: ContractResult<()> = receive(&ctx, &mut host);
+    // Check that the player disputes for themselves.
+    only_invoker(ctx, input.player)?;
 
-//         // Check the result.
-//         claim_eq!(error, Err(Error::YourError), "Function should throw an error.");
-//     }
-// }
+    host.invoke_contract(
+        &state_address,
+        &input,
+        EntrypointName::new_unchecked("disputeMatch"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Apply the recorded match results whose finality delay has passed. The sweep
+/// is triggered through the proxy, by anyone unless `referee_only` is set on
+/// the state contract. Then the transaction has to be started by a referee.
+/// Returns the number of applied results.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "finalizeResults",
+    return_value = "u32",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_finalize_results<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<u32> {
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
+
+    let (_state_modified, applied) = host.invoke_contract(
+        &state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("finalizeResults"),
+        Amount::zero(),
+    )?;
+
+    let applied = applied.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(applied)
+}
+
+/// Start a new season if the current one has ended. Anyone can trigger the
+/// rollover through the proxy, which is required before results of the new
+/// season are accepted when the rollover lock is set. Returns the number of the
+/// current season.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "rollOverSeason",
+    return_value = "u32",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_roll_over_season<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<u32> {
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
+
+    let (_state_modified, season) = host.invoke_contract(
+        &state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("rollOverSeason"),
+        Amount::zero(),
+    )?;
+
+    let season = season.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(season)
+}
+
+/// Decay the ratings of inactive players, one page of players at a time.
+/// Returns the key of the last visited player to continue after, or `None`
+/// once all players were visited.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "decayRatings",
+    parameter = "PlayerCursorParams",
+    return_value = "Option<(GameId, Address)>",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_decay_ratings<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<Option<(GameId, Address)>> {
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
+
+    // Parse the parameter.
+    let params: PlayerCursorParams = ctx.parameter_cursor().get()?;
+
+    let (_state_modified, next) = host.invoke_contract(
+        &state_address,
+        &params,
+        EntrypointName::new_unchecked("decayRatings"),
+        Amount::zero(),
+    )?;
+
+    let next = next.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(next)
+}
+
+/// Helper function to get the number of the current season from the state
+/// contract.
+fn current_season<S>(
+    state_address: &ContractAddress,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<u32> {
+    let season = host.invoke_contract_read_only(
+        state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("getCurrentSeason"),
+        Amount::zero(),
+    )?;
+
+    let season = season.ok_or(CustomContractError::StateInvokeError)?.get()?;
+    Ok(season)
+}
+
+/// Reset the stats of a single player in a game. Only the admin of the
+/// implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "resetPlayer",
+    parameter = "GamePlayerParams",
+    error = "CustomContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_implementation_reset_player<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only the current admin can reset a player.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let input: GamePlayerParams = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &input,
+        EntrypointName::new_unchecked("resetPlayer"),
+        Amount::zero(),
+    )?;
+
+    // Log a stats reset event.
+    if host.state().events_enabled {
+        let season = current_season(&state_address, host)?;
+        logger.log(&VersusEvent::StatsReset(StatsResetEvent {
+            player: Some(input.player),
+            season,
+        }))?;
+    }
+
+    Ok(())
+}
+
+/// Reset the stats of all players, one page of players at a time. The stats
+/// reset event is logged once the last page was reset. Only the admin of the
+/// implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "resetAllPlayers",
+    parameter = "PlayerCursorParams",
+    return_value = "ResetProgress",
+    error = "CustomContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_implementation_reset_all_players<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<ResetProgress> {
+    // Check that only the current admin can reset all players.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let params: PlayerCursorParams = ctx.parameter_cursor().get()?;
+
+    let (_state_modified, progress) = host.invoke_contract(
+        &state_address,
+        &params,
+        EntrypointName::new_unchecked("resetAllPlayers"),
+        Amount::zero(),
+    )?;
+
+    let progress: ResetProgress = progress.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    // Log a stats reset event.
+    if progress.done && host.state().events_enabled {
+        let season = current_season(&state_address, host)?;
+        logger.log(&VersusEvent::StatsReset(StatsResetEvent {
+            player: None,
+            season,
+        }))?;
+    }
+
+    Ok(progress)
+}
+
+/// Function to create a new game with its own players and an optional name and
+/// return its id. Only the admin of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "createGame",
+    parameter = "Option<String>",
+    return_value = "GameId",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_create_game<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<GameId> {
+    // Check that only the current admin can create a game.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    // Parse the parameter.
+    let name: Option<String> = ctx.parameter_cursor().get()?;
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let (_state_modified, game_id) = host.invoke_contract(
+        &state_address,
+        &name,
+        EntrypointName::new_unchecked("createGame"),
+        Amount::zero(),
+    )?;
+
+    let game_id = game_id.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(game_id)
+}
+
+/// Function to create a single-elimination tournament of players of a game and
+/// return its id. The number of players has to be a power of two and
+/// the payout split has to add up to 10 000 basis points. Only the admin of
+/// the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "createTournament",
+    parameter = "CreateTournamentParams",
+    return_value = "TournamentId",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_create_tournament<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<TournamentId> {
+    // Check that only the current admin can create a tournament.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    // Parse the parameter.
+    let params: CreateTournamentParams = ctx.parameter_cursor().get()?;
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let (_state_modified, tournament_id) = host.invoke_contract(
+        &state_address,
+        &params,
+        EntrypointName::new_unchecked("createTournament"),
+        Amount::zero(),
+    )?;
+
+    let tournament_id = tournament_id.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(tournament_id)
+}
+
+/// Function to pay the pool of a completed tournament to its top finishers
+/// according to its payout split. The state contract computes the prizes from
+/// the stakes on that tournament only. CCD can not be transferred to contracts,
+/// so the prizes of contract finishers stay in the tournament pool. Only the
+/// admin of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "distributePrizes",
+    parameter = "TournamentId",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_distribute_prizes<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can distribute prizes.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    // Parse the parameter.
+    let tournament_id: TournamentId = ctx.parameter_cursor().get()?;
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let (_state_modified, finishers) = host.invoke_contract(
+        &state_address,
+        &tournament_id,
+        EntrypointName::new_unchecked("distributePrizes"),
+        Amount::zero(),
+    )?;
+
+    let finishers: Vec<(Address, Amount)> =
+        finishers.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    for (finisher, prize) in finishers {
+        // Skip contract finishers instead of failing the whole distribution.
+        let finisher = match finisher {
+            Address::Account(finisher) if prize > Amount::zero() => finisher,
+            _ => continue,
+        };
+        host.state_mut().tournament_pool -= prize;
+        host.invoke_transfer(&finisher, prize)?;
+    }
+
+    Ok(())
+}
+
+/// Add new player to a game. The amount sent has to cover the registration fee
+/// and is transferred to the fee recipient.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "addPlayer",
+    parameter = "GamePlayerParams",
+    error = "CustomContractError",
+    mutable,
+    payable
+)]
+fn contract_implementation_add_player<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+    amount: Amount,
+) -> ContractResult<()> {
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
+
+    // Parse the parameter.
+    let input: GamePlayerParams = ctx.parameter_cursor().get()?;
+
+    ensure!(
+        !host.state().is_added(&state_address, &input, host)?,
+        CustomContractError::AlreadyAdded
+    );
+
+    // Check that the registration fee is paid.
+    ensure!(
+        amount >= host.state().registration_fee,
+        CustomContractError::InsufficientFee
+    );
+
+    host.invoke_contract(
+        &state_address,
+        &input,
+        EntrypointName::new_unchecked("addPlayer"),
+        Amount::zero(),
+    )?;
+
+    // Forward the collected fee.
+    if amount > Amount::zero() {
+        host.invoke_transfer(&host.state().fee_recipient, amount)?;
+    }
+
+    // Log the update operator event.
+    // host.invoke_contract(
+    //     &proxy_address,
+    //     &UpdateOperator(
+    //         UpdateOperatorEvent {
+    //             owner:    sender,
+    //             operator: param.operator,
+    //             update:   param.update,
+    //         },
+    //     ),
+    //     EntrypointName::new_unchecked("logEvent"),
+    //     Amount::zero(),
+    // )?;
+
+    Ok(())
+}
+
+/// Add a player to a game if they are not added yet and stake on a tournament
+/// they play in, in one transaction. The stake is added to the prize pool of
+/// that tournament. A new player pays the
+/// registration fee from the amount sent, which is transferred to the fee
+/// recipient, and stakes the rest. A player that is already added stakes the
+/// whole amount. If either step fails, the whole transaction is rejected.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "registerAndStake",
+    parameter = "RegisterAndStakeParams",
+    error = "CustomContractError",
+    mutable,
+    payable
+)]
+fn contract_implementation_register_and_stake<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+    amount: Amount,
+) -> ContractResult<()> {
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not in maintenance mode.
+    when_not_in_maintenance(host.state(), ctx.sender())?;
+
+    // Check that contract is not paused.
+    when_not_paused(&state_address, ctx, host)?;
+
+    // Parse the parameter.
+    let input: RegisterAndStakeParams = ctx.parameter_cursor().get()?;
+    let game_player = GamePlayerParams {
+        game_id: input.game_id,
+        player:  input.player,
+    };
+
+    // Stakes can only be paid out to accounts.
+    ensure!(matches!(input.player, Address::Account(_)), CustomContractError::OnlyAccount);
+
+    let is_added = host.state().is_added(&state_address, &game_player, host)?;
+    let fee = if is_added {
+        Amount::zero()
+    } else {
+        host.state().registration_fee
+    };
+    ensure!(amount >= fee, CustomContractError::InsufficientFee);
+    let stake = amount - fee;
+
+    // Check that the stake is not dust.
+    ensure!(
+        stake > Amount::zero() && stake >= host.state().min_stake,
+        CustomContractError::StakeTooLow
+    );
+
+    if !is_added {
+        host.invoke_contract(
+            &state_address,
+            &game_player,
+            EntrypointName::new_unchecked("addPlayer"),
+            Amount::zero(),
+        )?;
+
+        // Forward the collected fee.
+        if fee > Amount::zero() {
+            host.invoke_transfer(&host.state().fee_recipient, fee)?;
+        }
+    }
+
+    host.invoke_contract(
+        &state_address,
+        &StakeTournamentParams {
+            tournament_id: input.tournament_id,
+            player:        input.player,
+            amount:        stake,
+        },
+        EntrypointName::new_unchecked("stakeTournament"),
+        Amount::zero(),
+    )?;
+    host.state_mut().tournament_pool += stake;
+
+    Ok(())
+}
+
+/// This functions allows the admin of the implementation to transfer the
+/// address to a new admin.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "updateAdmin",
+    parameter = "Address",
+    error = "CustomContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_implementation_update_admin<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only the old admin is authorized to update the admin address.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let new_admin = ctx.parameter_cursor().get()?;
+    // Update admin.
+    host.state_mut().admin = new_admin;
+
+    // Log a new admin event.
+    if host.state().events_enabled {
+        logger.log(&VersusEvent::NewAdmin(NewAdminEvent {
+            new_admin,
+        }))?;
+    }
+
+    Ok(())
+}
+
+/// Function to set the registration fee and its recipient. Only the admin of
+/// the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setRegistrationFee",
+    parameter = "SetRegistrationFeeParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_registration_fee<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the registration fee.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let params: SetRegistrationFeeParams = ctx.parameter_cursor().get()?;
+
+    host.state_mut().registration_fee = params.registration_fee;
+    host.state_mut().fee_recipient = params.fee_recipient;
+
+    Ok(())
+}
+
+/// Function to set the minimum amount a player can stake on a match. Only the
+/// admin of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setMinStake",
+    parameter = "Amount",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_min_stake<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the minimum stake.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let min_stake: Amount = ctx.parameter_cursor().get()?;
+
+    host.state_mut().min_stake = min_stake;
+
+    Ok(())
+}
+
+/// Function to set the amount paid from the reward pool to the winner of a
+/// match. Only the admin of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setRewardPerWin",
+    parameter = "Amount",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_reward_per_win<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the reward per win.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let reward_per_win: Amount = ctx.parameter_cursor().get()?;
+
+    host.state_mut().reward_per_win = reward_per_win;
+
+    Ok(())
+}
+
+/// Function to get the amount funded for win rewards and not paid out yet. The
+/// reward pool is tracked separately from the contract balance, which also
+/// holds deposits and stakes.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getRewardPoolBalance",
+    return_value = "Amount",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_reward_pool_balance<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<Amount> {
+    Ok(host.state().reward_pool)
+}
+
+/// Function to set the contract that is notified after every recorded battle
+/// result. The hook can not be one of the protocol contracts and needs a
+/// non-empty entrypoint. Only the admin of the implementation can call this
+/// function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setResultHook",
+    parameter = "ResultHook",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_result_hook<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the result hook.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let params: ResultHook = ctx.parameter_cursor().get()?;
+
+    if let Some(hook) = params.hook {
+        let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+        ensure!(
+            !params.entrypoint.is_empty() && EntrypointName::new(&params.entrypoint).is_ok(),
+            CustomContractError::InvalidResultHook
+        );
+        // Notifying a protocol contract could loop back into this contract.
+        ensure!(
+            hook != ctx.self_address() && hook != proxy_address && hook != state_address,
+            CustomContractError::InvalidResultHook
+        );
+    }
+
+    let state = host.state_mut();
+    state.result_hook = params.hook;
+    state.result_hook_entrypoint = params.entrypoint;
+
+    Ok(())
+}
+
+/// Function to get the contract that is notified after every recorded battle
+/// result.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getResultHook",
+    return_value = "ResultHook",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_result_hook<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<ResultHook> {
+    let state = host.state();
+    Ok(ResultHook {
+        hook:       state.result_hook,
+        entrypoint: state.result_hook_entrypoint.clone(),
+    })
+}
+
+/// Function to cancel a pending match and refund the stakes of both players.
+/// Only the admin of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "cancelMatch",
+    parameter = "MatchId",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_cancel_match<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can cancel matches.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let match_id: MatchId = ctx.parameter_cursor().get()?;
+
+    let (_state_modified, game) = host.invoke_contract(
+        &state_address,
+        &match_id,
+        EntrypointName::new_unchecked("cancelMatch"),
+        Amount::zero(),
+    )?;
+
+    let game: Match = game.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    // Only accounts can stake, so the refunds go to accounts.
+    for (player, stake) in [(game.player_a, game.stake_a), (game.player_b, game.stake_b)] {
+        if let (Address::Account(player), true) = (player, stake > Amount::zero()) {
+            host.invoke_transfer(&player, stake)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Function to resolve a disputed match by either upholding or overturning
+/// its result. Only the admin of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "resolveDispute",
+    parameter = "ResolveDisputeParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_resolve_dispute<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can resolve disputes.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let input: ResolveDisputeParams = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &input,
+        EntrypointName::new_unchecked("resolveDispute"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to set the season length in seconds. Seasons roll over
+/// automatically once a result is recorded after the end of a season, unless
+/// the rollover lock is set. A length of zero disables the rollover. Only the
+/// admin of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setSeasonLength",
+    parameter = "u64",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_season_length<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the season length.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let season_length_seconds: u64 = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &season_length_seconds,
+        EntrypointName::new_unchecked("setSeasonLength"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to set whether results are rejected once the current season has
+/// ended, until the rollover is applied with `rollOverSeason`. Only the admin
+/// of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setRolloverLock",
+    parameter = "bool",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_rollover_lock<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the rollover lock.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let rollover_lock: bool = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &rollover_lock,
+        EntrypointName::new_unchecked("setRolloverLock"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to set the number of completed matches a player needs to appear
+/// on the leaderboard. Zero lists all players. Only the admin of the
+/// implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setLeaderboardMinMatches",
+    parameter = "u32",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_leaderboard_min_matches<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the leaderboard minimum.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let leaderboard_min_matches: u32 = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &leaderboard_min_matches,
+        EntrypointName::new_unchecked("setLeaderboardMinMatches"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to set the K-factors of the Elo rating update. Only the admin of
+/// the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setEloParams",
+    parameter = "EloParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_elo_params<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the Elo parameters.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let params: EloParams = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &params,
+        EntrypointName::new_unchecked("setEloParams"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to set the rating decay of inactive players. Only the admin of the
+/// implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setDecayParams",
+    parameter = "DecayParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_decay_params<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the rating decay.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let params: DecayParams = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &params,
+        EntrypointName::new_unchecked("setDecayParams"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to set the seconds after being added before results can be
+/// recorded for a player. Only the admin of the implementation can call this
+/// function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setBattleGracePeriod",
+    parameter = "u64",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_battle_grace_period<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the grace period.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let battle_grace_seconds: u64 = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &battle_grace_seconds,
+        EntrypointName::new_unchecked("setBattleGracePeriod"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to set the maximum number of battle results that can be submitted
+/// for a player within a window. Only the admin of the implementation can call
+/// this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setResultRateLimit",
+    parameter = "RateLimit",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_result_rate_limit<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the rate limit.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let rate_limit: RateLimit = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &rate_limit,
+        EntrypointName::new_unchecked("setResultRateLimit"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to set the delay in seconds before a recorded match result counts.
+/// Only the admin of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setFinalityDelay",
+    parameter = "u64",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_finality_delay<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the finality delay.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let finality_delay_seconds: u64 = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &finality_delay_seconds,
+        EntrypointName::new_unchecked("setFinalityDelay"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to add a referee that may submit battle results when referees are
+/// required. Only the admin of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "addReferee",
+    parameter = "Address",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_add_referee<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can add referees.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let referee: Address = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &referee,
+        EntrypointName::new_unchecked("addReferee"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to remove a referee. Only the admin of the implementation can
+/// call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "removeReferee",
+    parameter = "Address",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_remove_referee<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can remove referees.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let referee: Address = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &referee,
+        EntrypointName::new_unchecked("removeReferee"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to set whether battle results can only be submitted by referees.
+/// Only the admin of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setRefereeOnly",
+    parameter = "bool",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_referee_only<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can require referees.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let referee_only: bool = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &referee_only,
+        EntrypointName::new_unchecked("setRefereeOnly"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to set whether only accounts can be added as players. Only the
+/// admin of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setAccountsOnly",
+    parameter = "bool",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_accounts_only<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can restrict players to accounts.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let accounts_only: bool = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &accounts_only,
+        EntrypointName::new_unchecked("setAccountsOnly"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to shut the state contract down permanently in case of a
+/// catastrophic incident. Afterwards all mutations of the state contract
+/// reject while queries keep working. Only the admin of the implementation
+/// can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "emergencyShutdown",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_emergency_shutdown<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can shut the contract down.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    host.invoke_contract(
+        &state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("emergencyShutdown"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Refund the stake of a player on a match that was still pending when the
+/// state contract was shut down. Players can only refund their own stakes.
+/// This works while the contract is paused or in maintenance mode, since it is
+/// the only way to get the stakes back.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "refundStake",
+    parameter = "RefundStakeParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_refund_stake<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Can be only called through the fallback function on the proxy.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Parse the parameter.
+    let input: RefundStakeParams = ctx.parameter_cursor().get()?;
+
+    // Check that the player refunds their own stake.
+    only_invoker(ctx, input.player)?;
+
+    // The state contract rejects unless it is shut down.
+    let (_state_modified, stake) = host.invoke_contract(
+        &state_address,
+        &input,
+        EntrypointName::new_unchecked("refundStake"),
+        Amount::zero(),
+    )?;
+
+    let stake: Amount = stake.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    if stake > Amount::zero() {
+        host.invoke_transfer(&ctx.invoker(), stake)?;
+    }
+
+    Ok(())
+}
+
+/// Function to withdraw the reward pool and the tournament pool to an account
+/// after the state contract was shut down, so that the funds can be returned
+/// to the players off-chain. Only the admin of the implementation can call
+/// this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "withdrawPools",
+    parameter = "AccountAddress",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_withdraw_pools<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can withdraw the pools.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    // Parse the parameter.
+    let recipient: AccountAddress = ctx.parameter_cursor().get()?;
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let state = host.invoke_contract_read_only(
+        &state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("getExtendedState"),
+        Amount::zero(),
+    )?;
+    let state: ExtendedState = state.ok_or(CustomContractError::StateInvokeError)?.get()?;
+    ensure!(state.shutdown, CustomContractError::NotShutDown);
+
+    let implementation = host.state_mut();
+    let pools = implementation.reward_pool + implementation.tournament_pool;
+    implementation.reward_pool = Amount::zero();
+    implementation.tournament_pool = Amount::zero();
+
+    if pools > Amount::zero() {
+        host.invoke_transfer(&recipient, pools)?;
+    }
+
+    Ok(())
+}
+
+/// Function to check the wiring of the protocol after a deployment. Reads the
+/// extended state of the state contract, checks that it points to the proxy
+/// and this implementation, and reads back the player data of the
+/// implementation itself as a throwaway player that no real player can use.
+/// The self test only reads, so it leaves the state contract untouched and
+/// does not depend on its settings. A failing step does not abort the self
+/// test but is reported as failed. Only the admin of the implementation can
+/// call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "selfTest",
+    return_value = "SelfTestReport",
+    error = "CustomContractError"
+)]
+fn contract_implementation_self_test<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<SelfTestReport> {
+    // Check that only the current admin can run the self test.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let state = host.invoke_contract_read_only(
+        &state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("getExtendedState"),
+        Amount::zero(),
+    );
+    let state: Option<ExtendedState> = match state {
+        Ok(Some(mut state)) => state.get().ok(),
+        _ => None,
+    };
+    let reachable = state.is_some();
+    let wired = state.is_some_and(|state| {
+        state.proxy_address == proxy_address
+            && state.implementation_address == ctx.self_address()
+    });
+
+    // No real player can use the address of the implementation.
+    let player = GamePlayerParams {
+        game_id: DEFAULT_GAME_ID,
+        player:  Address::Contract(ctx.self_address()),
+    };
+    let player_data = host.invoke_contract_read_only(
+        &state_address,
+        &player,
+        EntrypointName::new_unchecked("getPlayerDataOrDefault"),
+        Amount::zero(),
+    );
+    let read_back = match player_data {
+        Ok(Some(mut player_data)) => {
+            let player_data: ParseResult<PlayerData> = player_data.get();
+            player_data.is_ok_and(|player_data| player_data.state == PlayerState::NotAdded)
+        }
+        _ => false,
+    };
+
+    Ok(SelfTestReport {
+        reachable,
+        wired,
+        read_back,
+    })
+}
+
+/// Function to move the data of a player in a game to a new address, e.g. when
+/// a user migrates accounts. Only the admin of the implementation can call this
+/// function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "transferProfile",
+    parameter = "TransferProfileParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_transfer_profile<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can transfer profiles.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let input: TransferProfileParams = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &input,
+        EntrypointName::new_unchecked("transferProfile"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to enable or disable event logging, e.g. to save energy during
+/// load spikes. Events are enabled by default. Only the admin of the
+/// implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setEventsEnabled",
+    parameter = "bool",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_events_enabled<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can toggle event logging.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let events_enabled: bool = ctx.parameter_cursor().get()?;
+
+    host.state_mut().events_enabled = events_enabled;
+
+    Ok(())
+}
+
+/// Function to enable or disable recovery mode. While enabled, the admin can
+/// call `updatePlayerState` and `updateBattleResult` directly instead of
+/// through the proxy. Only the admin of the implementation can call this
+/// function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setRecoveryMode",
+    parameter = "bool",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_recovery_mode<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can toggle recovery mode.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let recovery_mode: bool = ctx.parameter_cursor().get()?;
+
+    host.state_mut().recovery_mode = recovery_mode;
+
+    Ok(())
+}
+
+/// Function to enable or disable maintenance mode. While enabled, all
+/// entrypoints called through the proxy reject, while the admin entrypoints
+/// remain usable. Unlike pausing the state contract, this does not stop the
+/// admin from updating players. Only the admin of the implementation can call
+/// this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setMaintenance",
+    parameter = "bool",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_maintenance<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can toggle maintenance mode.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let maintenance: bool = ctx.parameter_cursor().get()?;
+
+    host.state_mut().maintenance = maintenance;
+
+    Ok(())
+}
+
+/// Function to set the number of seconds the paused flag of the state contract
+/// is cached for, at most `MAX_PAUSED_CACHE_SECONDS`. Zero disables the cache.
+/// Setting it invalidates the cache. Only the admin of the implementation can
+/// call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setPausedCacheSeconds",
+    parameter = "u64",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_paused_cache_seconds<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the cache duration.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let paused_cache_seconds: u64 = ctx.parameter_cursor().get()?;
+    ensure!(
+        paused_cache_seconds <= MAX_PAUSED_CACHE_SECONDS,
+        CustomContractError::PausedCacheTooLong
+    );
+
+    let state = host.state_mut();
+    state.paused_cache_seconds = paused_cache_seconds;
+    state.paused_cache = None;
+
+    Ok(())
+}
+
+/// Function to erase all data stored for a player in every game. Only the
+/// admin of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "forceDeletePlayer",
+    parameter = "Address",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_force_delete_player<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can delete players.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let player: Address = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &player,
+        EntrypointName::new_unchecked("forceDeletePlayer"),
+        Amount::zero(),
+    )?;
+
+    // A deleted player is removed from the queues, so their deposits are
+    // refunded.
+    let games: Vec<GameId> = host
+        .state()
+        .queue_deposits
+        .iter()
+        .filter(|(_, (account, _))| player == Address::Account(*account))
+        .map(|(game_id, _)| *game_id)
+        .collect();
+    for game_id in games {
+        refund_queue_deposit(host, game_id)?;
+    }
+
+    Ok(())
+}
+
+/// Function to set the state of newly added players. Only the admin of the
+/// implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "setDefaultPlayerState",
+    parameter = "PlayerState",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_implementation_set_default_player_state<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can set the default player state.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let default_player_state: PlayerState = ctx.parameter_cursor().get()?;
+    ensure!(
+        default_player_state != PlayerState::NotAdded,
+        CustomContractError::InvalidDefaultPlayerState
+    );
+
+    host.invoke_contract(
+        &state_address,
+        &default_player_state,
+        EntrypointName::new_unchecked("setDefaultPlayerState"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// This function pauses the contract. Only the
+/// admin of the implementation can call this function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "pause",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_pause<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can pause.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    host.invoke_contract(
+        &state_address,
+        &SetPausedParams {
+            paused: true,
+        },
+        EntrypointName::new_unchecked("setPaused"),
+        Amount::zero(),
+    )?;
+    host.state_mut().paused_cache = None;
+
+    Ok(())
+}
+
+/// This function pauses the contract and records the reason for it, which is
+/// cleared on unpause. Only the admin of the implementation can call this
+/// function.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "pauseWithReason",
+    parameter = "String",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_pause_with_reason<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can pause.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    // Parse the parameter.
+    let reason: String = ctx.parameter_cursor().get()?;
+
+    host.invoke_contract(
+        &state_address,
+        &reason,
+        EntrypointName::new_unchecked("pauseWithReason"),
+        Amount::zero(),
+    )?;
+    host.state_mut().paused_cache = None;
+
+    Ok(())
+}
+
+/// Function to unpause the contract by the admin.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "unpause",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_un_pause<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the current admin can un_pause.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    host.invoke_contract(
+        &state_address,
+        &SetPausedParams {
+            paused: false,
+        },
+        EntrypointName::new_unchecked("setPaused"),
+        Amount::zero(),
+    )?;
+    host.state_mut().paused_cache = None;
+
+    Ok(())
+}
+
+/// Get the player data in a game
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getPlayerData",
+    parameter = "GamePlayerParams",
+    return_value = "(PlayerState, BattleResult)",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_player_data<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<(PlayerState, BattleResult)> {
+    // Parse the parameter.
+    let param: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let player_data = host.invoke_contract_read_only(
+        &state_address,
+        &param,
+        EntrypointName::new_unchecked("getPlayerData"),
+        Amount::zero(),
+    )?;
+
+    let (player_state, player_result) = player_data.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok((player_state, player_result))
+}
+
+/// Get the complete data of a player in a game, including the counters and
+/// the Elo rating.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getPlayerFull",
+    parameter = "GamePlayerParams",
+    return_value = "PlayerData",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_player_full<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<PlayerData> {
+    // Parse the parameter.
+    let param: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let player_data = host.invoke_contract_read_only(
+        &state_address,
+        &param,
+        EntrypointName::new_unchecked("getPlayerFull"),
+        Amount::zero(),
+    )?;
+
+    let player_data = player_data.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(player_data)
+}
+
+/// Get the most recent state transitions of a player in a game, oldest first.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getPlayerStateHistory",
+    parameter = "GamePlayerParams",
+    return_value = "Vec<(PlayerState, Timestamp)>",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_player_state_history<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<Vec<(PlayerState, Timestamp)>> {
+    // Parse the parameter.
+    let param: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let history = host.invoke_contract_read_only(
+        &state_address,
+        &param,
+        EntrypointName::new_unchecked("getPlayerStateHistory"),
+        Amount::zero(),
+    )?;
+
+    let history = history.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(history)
+}
+
+/// Get the current and the best win streak of a player in a game.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getWinStreak",
+    parameter = "GamePlayerParams",
+    return_value = "WinStreak",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_win_streak<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<WinStreak> {
+    // Parse the parameter.
+    let param: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let win_streak = host.invoke_contract_read_only(
+        &state_address,
+        &param,
+        EntrypointName::new_unchecked("getWinStreak"),
+        Amount::zero(),
+    )?;
+
+    let win_streak = win_streak.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(win_streak)
+}
+
+/// Get the data of a player in a game, or the data of a `NotAdded` player if
+/// the player is not added.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getPlayerDataOrDefault",
+    parameter = "GamePlayerParams",
+    return_value = "PlayerData",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_player_data_or_default<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<PlayerData> {
+    // Parse the parameter.
+    let param: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let player_data = host.invoke_contract_read_only(
+        &state_address,
+        &param,
+        EntrypointName::new_unchecked("getPlayerDataOrDefault"),
+        Amount::zero(),
+    )?;
+
+    let player_data = player_data.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(player_data)
+}
+
+/// Get the time the contract was paused at, or `None` if it is not paused.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getPausedSince",
+    return_value = "Option<Timestamp>",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_paused_since<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<Option<Timestamp>> {
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let paused_since = host.invoke_contract_read_only(
+        &state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("getPausedSince"),
+        Amount::zero(),
+    )?;
+
+    let paused_since = paused_since.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(paused_since)
+}
+
+/// Get the number of players waiting in the pairing queue.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getQueueLength",
+    return_value = "u32",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_queue_length<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<u32> {
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let length = host.invoke_contract_read_only(
+        &state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("getQueueLength"),
+        Amount::zero(),
+    )?;
+
+    let length = length.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(length)
+}
+
+/// Get a page of the games with their metadata and number of players, in the
+/// order they were created in.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getGames",
+    parameter = "PaginationParams",
+    return_value = "Vec<(GameId, GameInfo)>",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_games<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<Vec<(GameId, GameInfo)>> {
+    // Parse the parameter.
+    let param: PaginationParams = ctx.parameter_cursor().get()?;
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let games = host.invoke_contract_read_only(
+        &state_address,
+        &param,
+        EntrypointName::new_unchecked("getGames"),
+        Amount::zero(),
+    )?;
+
+    let games = games.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(games)
+}
+
+/// Get a tournament with all its rounds played so far.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getTournament",
+    parameter = "TournamentId",
+    return_value = "Tournament",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_tournament<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<Tournament> {
+    // Parse the parameter.
+    let tournament_id: TournamentId = ctx.parameter_cursor().get()?;
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let tournament = host.invoke_contract_read_only(
+        &state_address,
+        &tournament_id,
+        EntrypointName::new_unchecked("getTournament"),
+        Amount::zero(),
+    )?;
+
+    let tournament = tournament.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(tournament)
+}
+
+/// Get a page of the players waiting in the pairing queue, in the order they
+/// joined it. Follow the `next` cursor until it is `None` to get every
+/// waiting player.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getQueue",
+    parameter = "GetQueueParams",
+    return_value = "QueuePage",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_queue<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<QueuePage> {
+    // Parse the parameter.
+    let param: GetQueueParams = ctx.parameter_cursor().get()?;
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let queue = host.invoke_contract_read_only(
+        &state_address,
+        &param,
+        EntrypointName::new_unchecked("getQueue"),
+        Amount::zero(),
+    )?;
+
+    let queue = queue.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(queue)
+}
+
+/// Get the number of players in the `Active` state.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getActivePlayerCount",
+    return_value = "u32",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_active_player_count<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<u32> {
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let count = host.invoke_contract_read_only(
+        &state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("getActivePlayerCount"),
+        Amount::zero(),
+    )?;
+
+    let count = count.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(count)
+}
+
+/// Get the leaderboard rank of a player in a game. See `getPlayerRank` on the
+/// state contract for the energy cost of this query.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getPlayerRank",
+    parameter = "GamePlayerParams",
+    return_value = "u32",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_player_rank<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<u32> {
+    // Parse the parameter.
+    let param: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let rank = host.invoke_contract_read_only(
+        &state_address,
+        &param,
+        EntrypointName::new_unchecked("getPlayerRank"),
+        Amount::zero(),
+    )?;
+
+    let rank = rank.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(rank)
+}
+
+/// Get the data, number of matches, rank, current streak and Elo rating of a
+/// player in a game at once. See `getPlayerSummary` on the state contract for
+/// the energy cost of this query.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getPlayerSummary",
+    parameter = "GamePlayerParams",
+    return_value = "PlayerSummary",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_player_summary<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<PlayerSummary> {
+    // Parse the parameter.
+    let param: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let summary = host.invoke_contract_read_only(
+        &state_address,
+        &param,
+        EntrypointName::new_unchecked("getPlayerSummary"),
+        Amount::zero(),
+    )?;
+
+    let summary = summary.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(summary)
+}
+
+/// Get the number of players added in a season.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getPlayersAddedInSeason",
+    parameter = "u32",
+    return_value = "u32",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_players_added_in_season<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<u32> {
+    // Parse the parameter.
+    let season: u32 = ctx.parameter_cursor().get()?;
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let count = host.invoke_contract_read_only(
+        &state_address,
+        &season,
+        EntrypointName::new_unchecked("getPlayersAddedInSeason"),
+        Amount::zero(),
+    )?;
+
+    let count = count.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(count)
+}
+
+/// Get the number, total duration and average duration of all recorded
+/// matches.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getMatchDurationStats",
+    return_value = "MatchDurationStats",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_match_duration_stats<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<MatchDurationStats> {
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let stats = host.invoke_contract_read_only(
+        &state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("getMatchDurationStats"),
+        Amount::zero(),
+    )?;
+
+    let stats = stats.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(stats)
+}
+
+/// Get the state and the counters of the state contract together with the
+/// maintenance mode of the implementation, so that a single query gives the
+/// full operational picture.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getExtendedState",
+    return_value = "OperationalState",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_extended_state<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<OperationalState> {
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let state = host.invoke_contract_read_only(
+        &state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("getExtendedState"),
+        Amount::zero(),
+    )?;
+
+    let state = state.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(OperationalState {
+        state,
+        maintenance: host.state().maintenance,
+    })
+}
+
+/// Get the players of a game with the highest Elo ratings in descending
+/// order. See `getTopByElo` on the state contract for the ordering of ties.
+#[receive(
+    contract = "Versus-Implementation",
+    name = "getTopByElo",
+    parameter = "TopByEloParams",
+    return_value = "Vec<(Address, i32)>",
+    error = "CustomContractError"
+)]
+fn contract_implementation_get_top_by_elo<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateImplementation, StateApiType = S>,
+) -> ContractResult<Vec<(Address, i32)>> {
+    // Parse the parameter.
+    let param: TopByEloParams = ctx.parameter_cursor().get()?;
+    let (_proxy_address, state_address) = get_protocol_addresses_from_implementation(host)?;
+
+    let top = host.invoke_contract_read_only(
+        &state_address,
+        &param,
+        EntrypointName::new_unchecked("getTopByElo"),
+        Amount::zero(),
+    )?;
+
+    let top = top.ok_or(CustomContractError::StateInvokeError)?.get()?;
+
+    Ok(top)
+}
+
+#[concordium_cfg_test]
+mod tests {
+    use super::*;
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+    use test_infrastructure::*;
+
+    const ADMIN_ACCOUNT: AccountAddress = AccountAddress([0u8; 32]);
+    const ADMIN_ADDRESS: Address = Address::Account(ADMIN_ACCOUNT);
+    const PROXY: ContractAddress = ContractAddress {
+        index:    1,
+        subindex: 0,
+    };
+    const IMPLEMENTATION: ContractAddress = ContractAddress {
+        index:    2,
+        subindex: 0,
+    };
+    const STATE: ContractAddress = ContractAddress {
+        index:    3,
+        subindex: 0,
+    };
+    const PLAYER_ACCOUNT: AccountAddress = AccountAddress([1u8; 32]);
+    const PLAYER: Address = Address::Account(PLAYER_ACCOUNT);
+    const GAME_PLAYER: GamePlayerParams = GamePlayerParams {
+        game_id: DEFAULT_GAME_ID,
+        player:  PLAYER,
+    };
+    const REGISTER_AND_STAKE: RegisterAndStakeParams = RegisterAndStakeParams {
+        game_id:       DEFAULT_GAME_ID,
+        player:        PLAYER,
+        tournament_id: 5,
+    };
+
+    /// Parameters received by a mocked state entrypoint.
+    type Invokes<P> = Rc<RefCell<Vec<P>>>;
+
+    /// Creates a host with an initialized implementation contract and mocks
+    /// for the `getPaused` and `isAdded` entrypoints of the state contract.
+    fn initialized_host(paused: bool, is_added: bool) -> TestHost<StateImplementation> {
+        let mut state = StateImplementation::new(ADMIN_ACCOUNT);
+        state.protocol_addresses = ProtocolAddressesImplementation::Initialized {
+            proxy_address: PROXY,
+            state_address: STATE,
+        };
+        let mut host = TestHost::new(state, TestStateBuilder::new());
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getPaused".into()),
+            MockFn::returning_ok(paused),
+        );
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("isAdded".into()),
+            MockFn::returning_ok(is_added),
+        );
+        host
+    }
+
+    /// Mocks a mutable state entrypoint and records the parameters it is
+    /// invoked with.
+    fn mock_state_entrypoint<P: Deserial + 'static>(
+        host: &mut TestHost<StateImplementation>,
+        entrypoint: &str,
+    ) -> Invokes<P> {
+        mock_state_entrypoint_returning(host, entrypoint, ())
+    }
+
+    /// Like `mock_state_entrypoint`, but the mocked entrypoint returns
+    /// `return_value`.
+    fn mock_state_entrypoint_returning<P: Deserial + 'static, R: Serial + Clone + 'static>(
+        host: &mut TestHost<StateImplementation>,
+        entrypoint: &str,
+        return_value: R,
+    ) -> Invokes<P> {
+        let invokes: Invokes<P> = Rc::new(RefCell::new(Vec::new()));
+        let recorded = invokes.clone();
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked(entrypoint.into()),
+            MockFn::new_v1(move |parameter, _amount, _balance, _state| {
+                recorded.borrow_mut().push(from_bytes(parameter.as_ref()).unwrap());
+                Ok((false, return_value.clone()))
+            }),
+        );
+        invokes
+    }
+
+    /// The stats of a player winning their first battle.
+    const FIRST_WIN: ResultSnapshot = ResultSnapshot {
+        wins_before: 0,
+        wins_after:  1,
+        elo_before:  1200,
+        elo_after:   1200,
+    };
+
+    #[concordium_test]
+    /// Test that adding a new player forwards the player to the state contract.
+    fn test_add_player() {
+        let mut host = initialized_host(false, false);
+        let invokes = mock_state_entrypoint::<GamePlayerParams>(&mut host, "addPlayer");
+
+        let parameter_bytes = to_bytes(&GAME_PLAYER);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+
+        let result = contract_implementation_add_player(&ctx, &mut host, Amount::zero());
+        claim!(result.is_ok(), "Adding a player should pass");
+        claim_eq!(*invokes.borrow(), vec![GAME_PLAYER]);
+    }
+
+    #[concordium_test]
+    /// Test that a player can not be added twice.
+    fn test_add_player_already_added() {
+        let mut host = initialized_host(false, true);
+        let invokes = mock_state_entrypoint::<GamePlayerParams>(&mut host, "addPlayer");
+
+        let parameter_bytes = to_bytes(&GAME_PLAYER);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+
+        let result = contract_implementation_add_player(&ctx, &mut host, Amount::zero());
+        claim_eq!(result, Err(CustomContractError::AlreadyAdded));
+        claim!(invokes.borrow().is_empty(), "State should not be invoked");
+    }
+
+    #[concordium_test]
+    /// Test that no player can be added while the contract is paused.
+    fn test_add_player_paused() {
+        let mut host = initialized_host(true, false);
+        let invokes = mock_state_entrypoint::<GamePlayerParams>(&mut host, "addPlayer");
+
+        let parameter_bytes = to_bytes(&GAME_PLAYER);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+
+        let result = contract_implementation_add_player(&ctx, &mut host, Amount::zero());
+        claim_eq!(result, Err(CustomContractError::ContractPaused));
+        claim!(invokes.borrow().is_empty(), "State should not be invoked");
+    }
+
+    #[concordium_test]
+    /// Test that a new player is added, pays the registration fee and stakes
+    /// the rest, and that nothing is kept if staking fails.
+    fn test_register_and_stake_new_player() {
+        let mut host = initialized_host(false, false);
+        host.state_mut().registration_fee = Amount::from_ccd(1);
+        host.set_self_balance(Amount::from_ccd(10));
+        let added = mock_state_entrypoint::<GamePlayerParams>(&mut host, "addPlayer");
+        let staked = mock_state_entrypoint::<StakeTournamentParams>(&mut host, "stakeTournament");
+
+        let parameter_bytes = to_bytes(&REGISTER_AND_STAKE);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+
+        let result =
+            contract_implementation_register_and_stake(&ctx, &mut host, Amount::from_ccd(1));
+        claim_eq!(result, Err(CustomContractError::StakeTooLow));
+
+        let result =
+            contract_implementation_register_and_stake(&ctx, &mut host, Amount::from_ccd(3));
+        claim_eq!(result, Ok(()));
+        claim_eq!(*added.borrow(), vec![GAME_PLAYER]);
+        claim_eq!(staked.borrow().len(), 1);
+        claim_eq!(staked.borrow()[0].tournament_id, 5);
+        claim_eq!(staked.borrow()[0].amount, Amount::from_ccd(2));
+        claim!(host.transfer_occurred(&ADMIN_ACCOUNT, Amount::from_ccd(1)), "Fee should be paid");
+        claim_eq!(host.state().tournament_pool, Amount::from_ccd(2));
+
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("stakeTournament".into()),
+            MockFn::returning_err::<()>(CallContractError::Trap),
+        );
+        let transfers = host.get_transfers().len();
+        let result = host.with_rollback(|host| {
+            contract_implementation_register_and_stake(&ctx, host, Amount::from_ccd(3))
+        });
+        claim_eq!(result, Err(CustomContractError::InvokeContractError));
+        claim_eq!(host.get_transfers().len(), transfers, "The fee should not be paid");
+        claim_eq!(host.state().tournament_pool, Amount::from_ccd(2));
+    }
+
+    #[concordium_test]
+    /// Test that a player that is already added stakes the whole amount
+    /// without being added again.
+    fn test_register_and_stake_existing_player() {
+        let mut host = initialized_host(false, true);
+        host.state_mut().registration_fee = Amount::from_ccd(1);
+        let added = mock_state_entrypoint::<GamePlayerParams>(&mut host, "addPlayer");
+        let staked = mock_state_entrypoint::<StakeTournamentParams>(&mut host, "stakeTournament");
+
+        let parameter_bytes = to_bytes(&REGISTER_AND_STAKE);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+
+        let result =
+            contract_implementation_register_and_stake(&ctx, &mut host, Amount::from_ccd(3));
+        claim_eq!(result, Ok(()));
+        claim!(added.borrow().is_empty(), "The player should not be added again");
+        claim_eq!(staked.borrow()[0].amount, Amount::from_ccd(3));
+        claim!(host.get_transfers().is_empty(), "No fee should be paid");
+        claim_eq!(host.state().tournament_pool, Amount::from_ccd(3));
+    }
+
+    #[concordium_test]
+    /// Test that players can only be added through the proxy.
+    fn test_add_player_only_proxy() {
+        let mut host = initialized_host(false, false);
+
+        let parameter_bytes = to_bytes(&GAME_PLAYER);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result = contract_implementation_add_player(&ctx, &mut host, Amount::zero());
+        claim_eq!(result, Err(CustomContractError::OnlyProxy));
+    }
+
+    #[concordium_test]
+    /// Test that player state and battle result updates forwarded with CCD are
+    /// rejected before reaching the state contract.
+    fn test_updates_reject_amount() {
+        let mut host = initialized_host(false, true);
+        let mut logger = TestLogger::init();
+        let state_invokes =
+            mock_state_entrypoint::<UpdatePlayerStateParams>(&mut host, "updatePlayerState");
+        let result_invokes =
+            mock_state_entrypoint_returning::<UpdateBattleResultParams, _>(
+                &mut host,
+                "updateBattleResult",
+                FIRST_WIN,
+            );
+        let amount = Amount::from_micro_ccd(1);
+
+        let parameter_bytes = to_bytes(&UpdatePlayerStateParams {
+            game_id: DEFAULT_GAME_ID,
+            player:  PLAYER,
+            state:   PlayerState::Suspended,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_implementation_update_player_state(&ctx, &mut host, amount),
+            Err(CustomContractError::UnexpectedAmount)
+        );
+
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            game_id:    DEFAULT_GAME_ID,
+            player:     PLAYER,
+            result:     BattleResult::Win,
+            client_ref: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_implementation_update_battle_result(&ctx, &mut host, amount, &mut logger),
+            Err(CustomContractError::UnexpectedAmount)
+        );
+
+        claim!(state_invokes.borrow().is_empty(), "The state should not be invoked");
+        claim!(result_invokes.borrow().is_empty(), "The state should not be invoked");
+    }
+
+    #[concordium_test]
+    /// Test that player state and battle result updates are forwarded to the
+    /// state contract unaltered.
+    fn test_updates_are_forwarded() {
+        let mut host = initialized_host(false, true);
+        let mut logger = TestLogger::init();
+        let state_invokes =
+            mock_state_entrypoint::<UpdatePlayerStateParams>(&mut host, "updatePlayerState");
+        let result_invokes =
+            mock_state_entrypoint_returning::<UpdateBattleResultParams, _>(
+                &mut host,
+                "updateBattleResult",
+                FIRST_WIN,
+            );
+
+        let parameter_bytes = to_bytes(&UpdatePlayerStateParams {
+            game_id: DEFAULT_GAME_ID,
+            player:  PLAYER,
+            state:   PlayerState::Suspended,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_implementation_update_player_state(&ctx, &mut host, Amount::zero());
+        claim!(result.is_ok(), "Updating the player state should pass");
+
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            game_id:    DEFAULT_GAME_ID,
+            player:     PLAYER,
+            result:     BattleResult::Win,
+            client_ref: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_implementation_update_battle_result(
+            &ctx,
+            &mut host,
+            Amount::zero(),
+            &mut logger,
+        );
+        claim!(result.is_ok(), "Updating the battle result should pass");
+        claim_eq!(
+            logger.logs,
+            vec![to_bytes(&VersusEvent::ResultApplied(ResultAppliedEvent {
+                player:      PLAYER,
+                result:      BattleResult::Win,
+                wins_before: 0,
+                wins_after:  1,
+                elo_before:  1200,
+                elo_after:   1200,
+            }))]
+        );
+
+        let state_invokes = state_invokes.borrow();
+        claim_eq!(state_invokes.len(), 1);
+        claim_eq!(state_invokes[0].player, PLAYER);
+        claim_eq!(state_invokes[0].state, PlayerState::Suspended);
+        let result_invokes = result_invokes.borrow();
+        claim_eq!(result_invokes.len(), 1);
+        claim_eq!(result_invokes[0].player, PLAYER);
+        claim_eq!(result_invokes[0].result, BattleResult::Win);
+    }
+
+    /// Invokes `addPlayer` through the proxy with a registration fee of 10 CCD
+    /// set.
+    fn add_player_with_fee(
+        host: &mut TestHost<StateImplementation>,
+        amount: Amount,
+    ) -> ContractResult<()> {
+        host.state_mut().registration_fee = Amount::from_ccd(10);
+        host.set_self_balance(amount);
+
+        let parameter_bytes = to_bytes(&GAME_PLAYER);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+
+        contract_implementation_add_player(&ctx, host, amount)
+    }
+
+    #[concordium_test]
+    /// Test that paying the exact registration fee adds the player and
+    /// forwards the fee.
+    fn test_add_player_exact_fee() {
+        let mut host = initialized_host(false, false);
+        let invokes = mock_state_entrypoint::<GamePlayerParams>(&mut host, "addPlayer");
+
+        let result = add_player_with_fee(&mut host, Amount::from_ccd(10));
+        claim!(result.is_ok(), "Adding a player should pass");
+        claim_eq!(*invokes.borrow(), vec![GAME_PLAYER]);
+        claim!(host.transfer_occurred(&ADMIN_ACCOUNT, Amount::from_ccd(10)), "Fee not forwarded");
+    }
+
+    #[concordium_test]
+    /// Test that paying more than the registration fee is accepted.
+    fn test_add_player_excess_fee() {
+        let mut host = initialized_host(false, false);
+        let invokes = mock_state_entrypoint::<GamePlayerParams>(&mut host, "addPlayer");
+
+        let result = add_player_with_fee(&mut host, Amount::from_ccd(15));
+        claim!(result.is_ok(), "Adding a player should pass");
+        claim_eq!(*invokes.borrow(), vec![GAME_PLAYER]);
+        claim!(host.transfer_occurred(&ADMIN_ACCOUNT, Amount::from_ccd(15)), "Fee not forwarded");
+    }
+
+    #[concordium_test]
+    /// Test that paying less than the registration fee is rejected.
+    fn test_add_player_insufficient_fee() {
+        let mut host = initialized_host(false, false);
+        let invokes = mock_state_entrypoint::<GamePlayerParams>(&mut host, "addPlayer");
+
+        let result = add_player_with_fee(&mut host, Amount::from_ccd(5));
+        claim_eq!(result, Err(CustomContractError::InsufficientFee));
+        claim!(invokes.borrow().is_empty(), "State should not be invoked");
+        claim!(host.get_transfers().is_empty(), "No fee should be forwarded");
+    }
+
+    #[concordium_test]
+    /// Test that the admin can only update players directly while recovery
+    /// mode is enabled.
+    fn test_recovery_mode() {
+        let mut host = initialized_host(false, true);
+        let mut logger = TestLogger::init();
+        let invokes =
+            mock_state_entrypoint_returning::<UpdateBattleResultParams, _>(
+                &mut host,
+                "updateBattleResult",
+                FIRST_WIN,
+            );
+
+        let set_recovery_mode = |host: &mut TestHost<StateImplementation>, enabled: bool| {
+            let parameter_bytes = to_bytes(&enabled);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(ADMIN_ADDRESS);
+            ctx.set_parameter(&parameter_bytes);
+            contract_implementation_set_recovery_mode(&ctx, host)
+                .expect_report("Admin should be able to toggle recovery mode");
+        };
+
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            game_id:    DEFAULT_GAME_ID,
+            player:     PLAYER,
+            result:     BattleResult::Win,
+            client_ref: None,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_parameter(&parameter_bytes);
+
+        let result = contract_implementation_update_battle_result(
+            &ctx,
+            &mut host,
+            Amount::zero(),
+            &mut logger,
+        );
+        claim_eq!(result, Err(CustomContractError::OnlyProxy));
+
+        set_recovery_mode(&mut host, true);
+        let result = contract_implementation_update_battle_result(
+            &ctx,
+            &mut host,
+            Amount::zero(),
+            &mut logger,
+        );
+        claim!(result.is_ok(), "Admin should update directly in recovery mode");
+        claim_eq!(invokes.borrow().len(), 1);
+
+        set_recovery_mode(&mut host, false);
+        let result = contract_implementation_update_battle_result(
+            &ctx,
+            &mut host,
+            Amount::zero(),
+            &mut logger,
+        );
+        claim_eq!(result, Err(CustomContractError::OnlyProxy));
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(PLAYER);
+        ctx.set_parameter(&parameter_bytes);
+        host.state_mut().recovery_mode = true;
+        let result = contract_implementation_update_battle_result(
+            &ctx,
+            &mut host,
+            Amount::zero(),
+            &mut logger,
+        );
+        claim_eq!(result, Err(CustomContractError::OnlyProxy));
+    }
+
+    #[concordium_test]
+    /// Test that joining the queue is forwarded to the state contract and the
+    /// id of a created match is returned, and that no one else can add the
+    /// player.
+    fn test_join_queue() {
+        let mut host = initialized_host(false, true);
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("joinQueue".into()),
+            MockFn::returning_ok(Some(7u64)),
+        );
+
+        let parameter_bytes = to_bytes(&GAME_PLAYER);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_invoker(ADMIN_ACCOUNT);
+
+        let result = contract_implementation_join_queue(&ctx, &mut host, Amount::zero());
+        claim_eq!(result, Err(CustomContractError::OnlyInvoker));
+
+        ctx.set_invoker(PLAYER_ACCOUNT);
+        let result = contract_implementation_join_queue(&ctx, &mut host, Amount::zero());
+        claim_eq!(result, Ok(Some(7)));
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        let result = contract_implementation_join_queue(&ctx, &mut host, Amount::zero());
+        claim_eq!(result, Err(CustomContractError::OnlyProxy));
+    }
+
+    #[concordium_test]
+    /// Test that the deposit of a waiting player is refunded when they leave
+    /// the queue, and that no one else can remove the player.
+    fn test_leave_queue_refunds_deposit() {
+        let mut host = initialized_host(false, true);
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("joinQueue".into()),
+            MockFn::returning_ok(None::<MatchId>),
+        );
+        let invokes = mock_state_entrypoint::<GamePlayerParams>(&mut host, "leaveQueue");
+        let deposit = Amount::from_micro_ccd(500);
+        host.set_self_balance(deposit);
+
+        let parameter_bytes = to_bytes(&GAME_PLAYER);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_invoker(PLAYER_ACCOUNT);
+
+        let result = contract_implementation_join_queue(&ctx, &mut host, deposit);
+        claim_eq!(result, Ok(None));
+        let deposits = &host.state().queue_deposits;
+        claim_eq!(deposits.get(&DEFAULT_GAME_ID), Some(&(PLAYER_ACCOUNT, deposit)));
+
+        ctx.set_invoker(ADMIN_ACCOUNT);
+        let result = contract_implementation_leave_queue(&ctx, &mut host);
+        claim_eq!(result, Err(CustomContractError::OnlyInvoker));
+        claim!(invokes.borrow().is_empty(), "State should not be invoked");
+
+        ctx.set_invoker(PLAYER_ACCOUNT);
+        let result = contract_implementation_leave_queue(&ctx, &mut host);
+        claim!(result.is_ok(), "Leaving the queue should pass");
+        claim_eq!(*invokes.borrow(), vec![GAME_PLAYER]);
+        claim!(host.state().queue_deposits.is_empty(), "Deposit should be released");
+        claim!(host.transfer_occurred(&PLAYER_ACCOUNT, deposit), "Deposit should be refunded");
+    }
+
+    #[concordium_test]
+    /// Test that the deposit of a queued player is refunded when they are
+    /// suspended.
+    fn test_suspend_refunds_deposit() {
+        let mut host = initialized_host(false, true);
+        mock_state_entrypoint::<UpdatePlayerStateParams>(&mut host, "updatePlayerState");
+        let deposit = Amount::from_micro_ccd(500);
+        host.set_self_balance(deposit);
+        host.state_mut().queue_deposits.insert(DEFAULT_GAME_ID, (PLAYER_ACCOUNT, deposit));
+
+        let parameter_bytes = to_bytes(&UpdatePlayerStateParams {
+            game_id: DEFAULT_GAME_ID,
+            player:  PLAYER,
+            state:   PlayerState::Suspended,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+
+        let result = contract_implementation_update_player_state(&ctx, &mut host, Amount::zero());
+        claim!(result.is_ok(), "Suspending the player should pass");
+        claim!(host.state().queue_deposits.is_empty(), "Deposit should be released");
+        claim!(host.transfer_occurred(&PLAYER_ACCOUNT, deposit), "Deposit should be refunded");
+    }
+
+    #[concordium_test]
+    /// Test that leaving the queue rejects when the state contract rejects
+    /// the player as not queued.
+    fn test_leave_queue_not_queued() {
+        let mut host = initialized_host(false, true);
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("leaveQueue".into()),
+            MockFn::returning_err::<()>(CallContractError::Trap),
+        );
+
+        let parameter_bytes = to_bytes(&GAME_PLAYER);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_invoker(PLAYER_ACCOUNT);
+
+        let result = contract_implementation_leave_queue(&ctx, &mut host);
+        claim_eq!(result, Err(CustomContractError::InvokeContractError));
+        claim!(host.get_transfers().is_empty(), "Nothing should be refunded");
+    }
+
+    #[concordium_test]
+    /// Test that players can only join and leave the pairing queue themselves.
+    fn test_pairing_queue_only_invoker() {
+        let mut host = initialized_host(false, true);
+        let joined = mock_state_entrypoint::<GamePlayerParams>(&mut host, "joinPairingQueue");
+        let left = mock_state_entrypoint::<GamePlayerParams>(&mut host, "leavePairingQueue");
+
+        let parameter_bytes = to_bytes(&GAME_PLAYER);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_invoker(ADMIN_ACCOUNT);
+        claim_eq!(
+            contract_implementation_join_pairing_queue(&ctx, &mut host),
+            Err(CustomContractError::OnlyInvoker)
+        );
+        claim_eq!(
+            contract_implementation_leave_pairing_queue(&ctx, &mut host),
+            Err(CustomContractError::OnlyInvoker)
+        );
+        claim!(joined.borrow().is_empty(), "State should not be invoked");
+        claim!(left.borrow().is_empty(), "State should not be invoked");
+
+        ctx.set_invoker(PLAYER_ACCOUNT);
+        claim_eq!(contract_implementation_join_pairing_queue(&ctx, &mut host), Ok(()));
+        claim_eq!(contract_implementation_leave_pairing_queue(&ctx, &mut host), Ok(()));
+        claim_eq!(*joined.borrow(), vec![GAME_PLAYER]);
+        claim_eq!(*left.borrow(), vec![GAME_PLAYER]);
+    }
+
+    #[concordium_test]
+    /// Test that the new admin event is only logged while events are enabled.
+    fn test_events_enabled() {
+        let mut host = initialized_host(false, false);
+        let mut logger = TestLogger::init();
+        let new_admin = Address::Contract(PROXY);
+
+        let parameter_bytes = to_bytes(&ADMIN_ADDRESS);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_implementation_update_admin(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Updating the admin should pass");
+        claim_eq!(
+            logger.logs,
+            vec![to_bytes(&VersusEvent::NewAdmin(NewAdminEvent {
+                new_admin: ADMIN_ADDRESS,
+            }))]
+        );
+
+        let parameter_bytes = to_bytes(&false);
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_implementation_set_events_enabled(&ctx, &mut host);
+        claim!(result.is_ok(), "Disabling events should pass");
+
+        let parameter_bytes = to_bytes(&new_admin);
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_implementation_update_admin(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Updating the admin should pass");
+        claim_eq!(host.state().admin, new_admin);
+        claim_eq!(logger.logs.len(), 1, "No event should be logged while disabled");
+    }
+
+    #[concordium_test]
+    /// Test that players can only stake for themselves and that stakes below
+    /// the minimum stake and zero stakes are rejected.
+    fn test_stake_match_min_stake() {
+        let mut host = initialized_host(false, true);
+        let invokes = mock_state_entrypoint::<StateStakeMatchParams>(&mut host, "stakeMatch");
+        host.state_mut().min_stake = Amount::from_micro_ccd(100);
+
+        let parameter_bytes = to_bytes(&StakeMatchParams {
+            match_id: 0,
+            player:   PLAYER,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_invoker(ADMIN_ACCOUNT);
+        claim_eq!(
+            contract_implementation_stake_match(&ctx, &mut host, Amount::from_micro_ccd(100)),
+            Err(CustomContractError::OnlyInvoker)
+        );
+        claim!(invokes.borrow().is_empty(), "State should not be invoked");
+        ctx.set_invoker(PLAYER_ACCOUNT);
+
+        for (stake, expected) in [
+            (0, Err(CustomContractError::StakeTooLow)),
+            (99, Err(CustomContractError::StakeTooLow)),
+            (100, Ok(())),
+            (101, Ok(())),
+        ] {
+            let stake = Amount::from_micro_ccd(stake);
+            let result = contract_implementation_stake_match(&ctx, &mut host, stake);
+            claim_eq!(result, expected);
+        }
+
+        let staked: Vec<u64> =
+            invokes.borrow().iter().map(|params| params.amount.micro_ccd).collect();
+        claim_eq!(staked, vec![100, 101]);
+
+        // Zero stakes are rejected even without a minimum stake.
+        host.state_mut().min_stake = Amount::zero();
+        let result = contract_implementation_stake_match(&ctx, &mut host, Amount::zero());
+        claim_eq!(result, Err(CustomContractError::StakeTooLow));
+    }
+
+    #[concordium_test]
+    /// Test that completing a match pays the stakes to the winner.
+    fn test_complete_match_pays_winner() {
+        let mut host = initialized_host(false, true);
+        let pot = Amount::from_micro_ccd(300);
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("completeMatch".into()),
+            MockFn::returning_ok(CompletedMatch {
+                pot,
+                rewardable: true,
+            }),
+        );
+        host.set_self_balance(pot);
+
+        let parameter_bytes = to_bytes(&CompleteMatchParams {
+            match_id: 0,
+            winner:   PLAYER,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut logger = TestLogger::init();
+
+        let result = contract_implementation_complete_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Completing the match should pass");
+        claim!(host.transfer_occurred(&PLAYER_ACCOUNT, pot), "Winner should be paid");
+    }
+
+    #[concordium_test]
+    /// Test that the winner of a match is rewarded from a funded reward pool
+    /// unless the state contract reports the pair as recently rewarded, and
+    /// that the reward is skipped once the pool is empty.
+    fn test_win_reward() {
+        let mut host = initialized_host(false, true);
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("completeMatch".into()),
+            MockFn::returning_ok(CompletedMatch {
+                pot:        Amount::zero(),
+                rewardable: true,
+            }),
+        );
+        let reward = Amount::from_micro_ccd(100);
+        host.state_mut().reward_per_win = reward;
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        let funding = Amount::from_micro_ccd(150);
+        let result = contract_implementation_fund_rewards(&ctx, &mut host, funding);
+        claim!(result.is_ok(), "Funding the reward pool should pass");
+        host.set_self_balance(funding);
+
+        let parameter_bytes = to_bytes(&CompleteMatchParams {
+            match_id: 0,
+            winner:   PLAYER,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let mut logger = TestLogger::init();
+
+        let result = contract_implementation_complete_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Completing the match should pass");
+        claim!(host.transfer_occurred(&PLAYER_ACCOUNT, reward), "Winner should be rewarded");
+        claim_eq!(host.state().reward_pool, Amount::from_micro_ccd(50));
+        claim!(logger.logs.is_empty(), "No event should be logged");
+
+        // The pair was rewarded recently.
+        host.state_mut().reward_pool = Amount::from_micro_ccd(150);
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("completeMatch".into()),
+            MockFn::returning_ok(CompletedMatch {
+                pot:        Amount::zero(),
+                rewardable: false,
+            }),
+        );
+        let result = contract_implementation_complete_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Completing the match should pass without a reward");
+        claim_eq!(host.get_transfers_to(PLAYER_ACCOUNT).len(), 1);
+        claim_eq!(host.state().reward_pool, Amount::from_micro_ccd(150));
+        claim!(logger.logs.is_empty(), "No event should be logged");
+        host.state_mut().reward_pool = Amount::from_micro_ccd(50);
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("completeMatch".into()),
+            MockFn::returning_ok(CompletedMatch {
+                pot:        Amount::zero(),
+                rewardable: true,
+            }),
+        );
+
+        // The remaining pool can not cover the reward.
+        let result = contract_implementation_complete_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Completing the match should pass with an empty pool");
+        claim_eq!(host.get_transfers_to(PLAYER_ACCOUNT).len(), 1);
+        claim_eq!(host.state().reward_pool, Amount::from_micro_ccd(50));
+        claim_eq!(
+            logger.logs,
+            vec![to_bytes(&VersusEvent::RewardSkipped(RewardSkippedEvent {
+                winner: PLAYER,
+                reward,
+                pool:   Amount::from_micro_ccd(50),
+            }))]
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the reward pool balance increases on funding and decreases on
+    /// payout, independently of stakes held by the contract.
+    fn test_get_reward_pool_balance() {
+        let mut host = initialized_host(false, true);
+        let pot = Amount::from_micro_ccd(300);
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("completeMatch".into()),
+            MockFn::returning_ok(CompletedMatch {
+                pot,
+                rewardable: true,
+            }),
+        );
+        host.state_mut().reward_per_win = Amount::from_micro_ccd(40);
+        let read_ctx = TestReceiveContext::empty();
+        let balance = contract_implementation_get_reward_pool_balance(&read_ctx, &host);
+        claim_eq!(balance, Ok(Amount::zero()));
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        for funding in [60, 40] {
+            let funding = Amount::from_micro_ccd(funding);
+            let result = contract_implementation_fund_rewards(&ctx, &mut host, funding);
+            claim!(result.is_ok(), "Funding the reward pool should pass");
+        }
+        let balance = contract_implementation_get_reward_pool_balance(&read_ctx, &host);
+        claim_eq!(balance, Ok(Amount::from_micro_ccd(100)));
+
+        host.set_self_balance(pot + Amount::from_micro_ccd(100));
+        let parameter_bytes = to_bytes(&CompleteMatchParams {
+            match_id: 0,
+            winner:   PLAYER,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let mut logger = TestLogger::init();
+        let result = contract_implementation_complete_match(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Completing the match should pass");
+        let balance = contract_implementation_get_reward_pool_balance(&read_ctx, &host);
+        claim_eq!(balance, Ok(Amount::from_micro_ccd(60)), "The pot should not touch the pool");
+    }
+
+    #[concordium_test]
+    /// Test that only the admin can create games and that the id allocated by
+    /// the state contract is returned.
+    fn test_create_game() {
+        let mut host = initialized_host(false, true);
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("createGame".into()),
+            MockFn::returning_ok(1 as GameId),
+        );
+
+        let parameter_bytes = to_bytes(&Some(String::from("Chess")));
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(PLAYER);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_implementation_create_game(&ctx, &mut host),
+            Err(CustomContractError::OnlyAdmin)
+        );
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        claim_eq!(contract_implementation_create_game(&ctx, &mut host), Ok(1));
+    }
+
+    #[concordium_test]
+    /// Test that only the admin can create tournaments and that the players are
+    /// forwarded to the state contract.
+    fn test_create_tournament() {
+        let mut host = initialized_host(false, true);
+        let params = CreateTournamentParams {
+            game_id:      DEFAULT_GAME_ID,
+            players:      vec![PLAYER, ADMIN_ADDRESS],
+            payout_split: vec![10_000],
+        };
+        let expected = to_bytes(&params);
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("createTournament".into()),
+            MockFn::new_v1(move |parameter, _amount, _balance, _state| {
+                claim_eq!(parameter.as_ref(), &expected[..]);
+                Ok((true, 3 as TournamentId))
+            }),
+        );
+
+        let parameter_bytes = to_bytes(&params);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(PLAYER);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_implementation_create_tournament(&ctx, &mut host),
+            Err(CustomContractError::OnlyAdmin)
+        );
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        claim_eq!(contract_implementation_create_tournament(&ctx, &mut host), Ok(3));
+    }
+
+    #[concordium_test]
+    /// Test that the prizes of a tournament computed by the state contract are
+    /// paid to its top finishers, that contract finishers are skipped and that
+    /// only the paid prizes leave the pool of all tournaments.
+    fn test_distribute_prizes() {
+        let mut host = initialized_host(false, true);
+        let finishers = [1u8, 2, 3].map(|byte| AccountAddress([byte; 32]));
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("distributePrizes".into()),
+            MockFn::returning_ok(vec![
+                (Address::Account(finishers[0]), Amount::from_micro_ccd(700_003)),
+                (Address::Contract(PROXY), Amount::from_micro_ccd(100_000)),
+                (Address::Account(finishers[1]), Amount::from_micro_ccd(200_001)),
+                (Address::Account(finishers[2]), Amount::zero()),
+            ]),
+        );
+        // The pool also holds the stakes on other tournaments.
+        host.state_mut().tournament_pool = Amount::from_micro_ccd(3_000_000);
+        host.set_self_balance(Amount::from_micro_ccd(3_000_000));
+
+        let parameter_bytes = to_bytes(&0u64);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(PLAYER);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_implementation_distribute_prizes(&ctx, &mut host),
+            Err(CustomContractError::OnlyAdmin)
+        );
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        claim_eq!(contract_implementation_distribute_prizes(&ctx, &mut host), Ok(()));
+        claim_eq!(host.get_transfers(), [
+            (finishers[0], Amount::from_micro_ccd(700_003)),
+            (finishers[1], Amount::from_micro_ccd(200_001)),
+        ]);
+        // The prize of the contract finisher stays in the pool.
+        claim_eq!(host.state().tournament_pool, Amount::from_micro_ccd(2_099_996));
+    }
+
+    #[concordium_test]
+    /// Test that players can only refund their own stakes and that the refund
+    /// returned by the state contract is transferred back to them.
+    fn test_refund_stake() {
+        let mut host = initialized_host(true, true);
+        let stake = Amount::from_micro_ccd(500);
+        let invokes = mock_state_entrypoint_returning::<RefundStakeParams, _>(
+            &mut host,
+            "refundStake",
+            stake,
+        );
+        host.set_self_balance(stake);
+
+        let parameter_bytes = to_bytes(&RefundStakeParams {
+            match_id: 4,
+            player:   PLAYER,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_invoker(ADMIN_ACCOUNT);
+        claim_eq!(
+            contract_implementation_refund_stake(&ctx, &mut host),
+            Err(CustomContractError::OnlyInvoker)
+        );
+        claim!(invokes.borrow().is_empty(), "State should not be invoked");
+
+        // Refunds work while the contract is paused.
+        ctx.set_invoker(PLAYER_ACCOUNT);
+        claim_eq!(contract_implementation_refund_stake(&ctx, &mut host), Ok(()));
+        claim_eq!(invokes.borrow().len(), 1);
+        claim_eq!(host.get_transfers(), [(PLAYER_ACCOUNT, stake)]);
+    }
+
+    #[concordium_test]
+    /// Test that the admin can only withdraw the pools after the state contract
+    /// was shut down.
+    fn test_withdraw_pools() {
+        let mut host = initialized_host(false, false);
+        let mut extended_state = ExtendedState {
+            proxy_address:          PROXY,
+            implementation_address: IMPLEMENTATION,
+            paused:                 false,
+            pause_reason:           None,
+            shutdown:               false,
+            player_count:           0,
+            active_player_count:    0,
+            current_season:         0,
+            total_wins:             0,
+            total_losses:           0,
+        };
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getExtendedState".into()),
+            MockFn::returning_ok(extended_state.clone()),
+        );
+        host.state_mut().reward_pool = Amount::from_micro_ccd(300);
+        host.state_mut().tournament_pool = Amount::from_micro_ccd(700);
+        host.set_self_balance(Amount::from_micro_ccd(1_000));
+
+        let parameter_bytes = to_bytes(&ADMIN_ACCOUNT);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(PLAYER);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_implementation_withdraw_pools(&ctx, &mut host),
+            Err(CustomContractError::OnlyAdmin)
+        );
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        claim_eq!(
+            contract_implementation_withdraw_pools(&ctx, &mut host),
+            Err(CustomContractError::NotShutDown)
+        );
+
+        extended_state.shutdown = true;
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getExtendedState".into()),
+            MockFn::returning_ok(extended_state),
+        );
+        claim_eq!(contract_implementation_withdraw_pools(&ctx, &mut host), Ok(()));
+        claim_eq!(host.get_transfers(), [(ADMIN_ACCOUNT, Amount::from_micro_ccd(1_000))]);
+        claim_eq!(host.state().reward_pool, Amount::zero());
+        claim_eq!(host.state().tournament_pool, Amount::zero());
+    }
+
+    #[concordium_test]
+    /// Test that a dispute is only forwarded if the disputing player is the
+    /// account that started the transaction.
+    fn test_dispute_match_only_invoker() {
+        let mut host = initialized_host(false, true);
+        let invokes = mock_state_entrypoint::<DisputeMatchParams>(&mut host, "disputeMatch");
+
+        let parameter_bytes = to_bytes(&DisputeMatchParams {
+            match_id: 4,
+            player:   PLAYER,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_invoker(ADMIN_ACCOUNT);
+        claim_eq!(
+            contract_implementation_dispute_match(&ctx, &mut host),
+            Err(CustomContractError::OnlyInvoker)
+        );
+        claim!(invokes.borrow().is_empty(), "State should not be invoked");
+
+        ctx.set_invoker(PLAYER_ACCOUNT);
+        claim_eq!(contract_implementation_dispute_match(&ctx, &mut host), Ok(()));
+        claim_eq!(invokes.borrow().len(), 1);
+    }
+
+    #[concordium_test]
+    /// Test that only the admin can resolve disputes.
+    fn test_resolve_dispute_only_admin() {
+        let mut host = initialized_host(false, true);
+        let invokes = mock_state_entrypoint::<ResolveDisputeParams>(&mut host, "resolveDispute");
+
+        let parameter_bytes = to_bytes(&ResolveDisputeParams {
+            match_id: 4,
+            overturn: true,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_implementation_resolve_dispute(&ctx, &mut host);
+        claim_eq!(result, Err(CustomContractError::OnlyAdmin));
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        let result = contract_implementation_resolve_dispute(&ctx, &mut host);
+        claim!(result.is_ok(), "Resolving the dispute should pass");
+        claim_eq!(invokes.borrow().len(), 1);
+    }
+
+    #[concordium_test]
+    /// Test that a missing or malformed `getPaused` response is reported
+    /// differently from a failed call.
+    fn test_when_not_paused_malformed_response() {
+        let mut host = initialized_host(false, false);
+        let mut check_response = |mock: MockFn<StateImplementation>| {
+            host.setup_mock_entrypoint(
+                STATE,
+                OwnedEntrypointName::new_unchecked("getPaused".into()),
+                mock,
+            );
+            when_not_paused(&STATE, &TestReceiveContext::empty(), &mut host)
+        };
+
+        claim_eq!(
+            check_response(MockFn::new_v0::<(), _>(|_, _, _, _| Ok(false))),
+            Err(CustomContractError::MalformedStateResponse)
+        );
+        claim_eq!(
+            check_response(MockFn::returning_ok(2u8)),
+            Err(CustomContractError::MalformedStateResponse)
+        );
+        claim_eq!(
+            check_response(MockFn::returning_err::<()>(CallContractError::Trap)),
+            Err(CustomContractError::InvokeContractError)
+        );
+        claim_eq!(check_response(MockFn::returning_ok(false)), Ok(()));
+    }
+
+    #[concordium_test]
+    /// Test that the player data queries only need an immutable host and that
+    /// `getPlayerFull` returns the complete player data.
+    fn test_get_player_full() {
+        let mut host = initialized_host(false, true);
+        let player_data = PlayerData {
+            version:        4,
+            state:          PlayerState::Active,
+            last_result:    BattleResult::Win,
+            wins:           3,
+            losses:         1,
+            elo:            1042,
+            state_history:  StateHistory {
+                entries: [None; 8],
+                next:    0,
+            },
+            current_streak: 2,
+            best_streak:    4,
+            last_updated:   Timestamp::from_timestamp_millis(1_000),
+            registered_at:  Timestamp::from_timestamp_millis(500),
+            rate_window:    RateWindow {
+                submitted: vec![Timestamp::from_timestamp_millis(1_000)],
+            },
+            season:         0,
+        };
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getPlayerFull".into()),
+            MockFn::returning_ok(player_data.clone()),
+        );
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getPlayerData".into()),
+            MockFn::returning_ok((PlayerState::Active, BattleResult::Win)),
+        );
+        let host = &host;
+
+        let parameter_bytes = to_bytes(&GAME_PLAYER);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_parameter(&parameter_bytes);
+
+        claim_eq!(contract_implementation_get_player_full(&ctx, host), Ok(player_data));
+        claim_eq!(
+            contract_implementation_get_player_data(&ctx, host),
+            Ok((PlayerState::Active, BattleResult::Win))
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the self test passes every step against a correctly wired
+    /// state contract without writing to it, and reports failing steps
+    /// otherwise.
+    fn test_self_test() {
+        let mut host = initialized_host(false, false);
+        let mut extended_state = ExtendedState {
+            proxy_address:          PROXY,
+            implementation_address: IMPLEMENTATION,
+            paused:                 false,
+            pause_reason:           None,
+            shutdown:               false,
+            player_count:           0,
+            active_player_count:    0,
+            current_season:         0,
+            total_wins:             0,
+            total_losses:           0,
+        };
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getExtendedState".into()),
+            MockFn::returning_ok(extended_state.clone()),
+        );
+        let mut player_data = PlayerData {
+            version:        4,
+            state:          PlayerState::NotAdded,
+            last_result:    BattleResult::NoResult,
+            wins:           0,
+            losses:         0,
+            elo:            1200,
+            state_history:  StateHistory {
+                entries: [None; 8],
+                next:    0,
+            },
+            current_streak: 0,
+            best_streak:    0,
+            last_updated:   Timestamp::from_timestamp_millis(0),
+            registered_at:  Timestamp::from_timestamp_millis(0),
+            rate_window:    RateWindow {
+                submitted: Vec::new(),
+            },
+            season:         0,
+        };
+        let read = mock_state_entrypoint_returning::<GamePlayerParams, _>(
+            &mut host,
+            "getPlayerDataOrDefault",
+            player_data.clone(),
+        );
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Account(ADMIN_ACCOUNT));
+        ctx.set_self_address(IMPLEMENTATION);
+
+        // The host is borrowed immutably, so the self test can not write.
+        claim_eq!(
+            contract_implementation_self_test(&ctx, &host),
+            Ok(SelfTestReport {
+                reachable: true,
+                wired:     true,
+                read_back: true,
+            })
+        );
+        claim_eq!(*read.borrow(), vec![GamePlayerParams {
+            game_id: DEFAULT_GAME_ID,
+            player:  Address::Contract(IMPLEMENTATION),
+        }]);
+
+        extended_state.implementation_address = ContractAddress::new(99, 0);
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getExtendedState".into()),
+            MockFn::returning_ok(extended_state),
+        );
+        player_data.state = PlayerState::Active;
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getPlayerDataOrDefault".into()),
+            MockFn::returning_ok(player_data),
+        );
+        claim_eq!(
+            contract_implementation_self_test(&ctx, &host),
+            Ok(SelfTestReport {
+                reachable: true,
+                wired:     false,
+                read_back: false,
+            })
+        );
+
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getExtendedState".into()),
+            MockFn::returning_err::<()>(CallContractError::Trap),
+        );
+        claim_eq!(
+            contract_implementation_self_test(&ctx, &host),
+            Ok(SelfTestReport {
+                reachable: false,
+                wired:     false,
+                read_back: false,
+            })
+        );
+
+        ctx.set_sender(Address::Account(PLAYER_ACCOUNT));
+        claim_eq!(
+            contract_implementation_self_test(&ctx, &host),
+            Err(CustomContractError::OnlyAdmin)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that only the admin is reported as admin.
+    fn test_am_i_admin() {
+        let host = initialized_host(false, false);
+        let mut ctx = TestReceiveContext::empty();
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        claim_eq!(contract_implementation_am_i_admin(&ctx, &host), Ok(true));
+        ctx.set_sender(PLAYER);
+        claim_eq!(contract_implementation_am_i_admin(&ctx, &host), Ok(false));
+    }
+
+    #[concordium_test]
+    /// Test that the result hook is notified after a battle result is recorded
+    /// and that a failing hook does not revert the result.
+    fn test_result_hook() {
+        let mut host = initialized_host(false, true);
+        let mut logger = TestLogger::init();
+        mock_state_entrypoint_returning::<UpdateBattleResultParams, _>(
+            &mut host,
+            "updateBattleResult",
+            FIRST_WIN,
+        );
+        let hook = ContractAddress {
+            index:    10,
+            subindex: 0,
+        };
+
+        let parameter_bytes = to_bytes(&ResultHook {
+            hook:       Some(hook),
+            entrypoint: "onResult".into(),
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(PLAYER);
+        ctx.set_self_address(IMPLEMENTATION);
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_implementation_set_result_hook(&ctx, &mut host);
+        claim_eq!(result, Err(CustomContractError::OnlyAdmin));
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        let result = contract_implementation_set_result_hook(&ctx, &mut host);
+        claim!(result.is_ok(), "Admin should be able to set the result hook");
+
+        let hook_invokes: Invokes<UpdateBattleResultParams> = Rc::new(RefCell::new(Vec::new()));
+        let recorded = hook_invokes.clone();
+        host.setup_mock_entrypoint(
+            hook,
+            OwnedEntrypointName::new_unchecked("onResult".into()),
+            MockFn::new_v1(move |parameter, _amount, _balance, _state| {
+                recorded.borrow_mut().push(from_bytes(parameter.as_ref()).unwrap());
+                Ok((false, ()))
+            }),
+        );
+
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            game_id:    DEFAULT_GAME_ID,
+            player:     PLAYER,
+            result:     BattleResult::Loss,
+            client_ref: None,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_implementation_update_battle_result(
+            &ctx,
+            &mut host,
+            Amount::zero(),
+            &mut logger,
+        );
+        claim!(result.is_ok(), "Updating the battle result should pass");
+        let hook_invokes = hook_invokes.borrow();
+        claim_eq!(hook_invokes.len(), 1);
+        claim_eq!(hook_invokes[0].player, PLAYER);
+        claim_eq!(hook_invokes[0].result, BattleResult::Loss);
+        claim_eq!(logger.logs.len(), 1, "Only the applied result should be logged");
+
+        host.setup_mock_entrypoint(
+            hook,
+            OwnedEntrypointName::new_unchecked("onResult".into()),
+            MockFn::returning_err::<()>(CallContractError::Trap),
+        );
+        let result = contract_implementation_update_battle_result(
+            &ctx,
+            &mut host,
+            Amount::zero(),
+            &mut logger,
+        );
+        claim!(result.is_ok(), "A failing hook should not revert the result");
+        claim_eq!(
+            logger.logs[2..],
+            vec![to_bytes(&VersusEvent::ResultHookFailed(ResultHookFailedEvent {
+                hook,
+                player: PLAYER,
+            }))]
+        );
+    }
+
+    #[concordium_test]
+    /// Test that a valid result hook can be read back and that hooks without
+    /// an entrypoint or pointing at a protocol contract are rejected.
+    fn test_get_result_hook() {
+        let mut host = initialized_host(false, true);
+        let hook = ContractAddress {
+            index:    10,
+            subindex: 0,
+        };
+        let set_result_hook = |host: &mut TestHost<StateImplementation>, hook, entrypoint: &str| {
+            let parameter_bytes = to_bytes(&ResultHook {
+                hook,
+                entrypoint: entrypoint.into(),
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(ADMIN_ADDRESS);
+            ctx.set_self_address(IMPLEMENTATION);
+            ctx.set_parameter(&parameter_bytes);
+            contract_implementation_set_result_hook(&ctx, host)
+        };
+
+        let result = set_result_hook(&mut host, Some(hook), "onResult");
+        claim!(result.is_ok(), "Admin should be able to set a valid result hook");
+        let ctx = TestReceiveContext::empty();
+        let result_hook = contract_implementation_get_result_hook(&ctx, &host);
+        claim_eq!(
+            result_hook,
+            Ok(ResultHook {
+                hook:       Some(hook),
+                entrypoint: "onResult".into(),
+            })
+        );
+
+        for invalid_hook in [IMPLEMENTATION, PROXY, STATE] {
+            let result = set_result_hook(&mut host, Some(invalid_hook), "onResult");
+            claim_eq!(result, Err(CustomContractError::InvalidResultHook));
+        }
+        let result = set_result_hook(&mut host, Some(hook), "");
+        claim_eq!(result, Err(CustomContractError::InvalidResultHook));
+        claim_eq!(host.state().result_hook, Some(hook), "The hook should be unchanged");
+
+        let result = set_result_hook(&mut host, None, "");
+        claim!(result.is_ok(), "Admin should be able to remove the result hook");
+        claim_eq!(host.state().result_hook, None);
+    }
+
+    #[concordium_test]
+    /// Test that the protocol addresses are returned once initialized.
+    fn test_get_protocol_addresses() {
+        let ctx = TestReceiveContext::empty();
+        let host = TestHost::new(StateImplementation::new(ADMIN_ACCOUNT), TestStateBuilder::new());
+        claim_eq!(
+            contract_implementation_get_protocol_addresses(&ctx, &host),
+            Ok(&ProtocolAddressesImplementation::UnInitialized)
+        );
+
+        let host = initialized_host(false, false);
+        claim_eq!(
+            contract_implementation_get_protocol_addresses(&ctx, &host),
+            Ok(&ProtocolAddressesImplementation::Initialized {
+                proxy_address: PROXY,
+                state_address: STATE,
+            })
+        );
+    }
+
+    #[concordium_test]
+    /// Test that single and global stats resets log the reset player and the
+    /// current season.
+    fn test_stats_reset_event() {
+        let mut host = initialized_host(false, true);
+        let mut logger = TestLogger::init();
+        let reset_invokes = mock_state_entrypoint::<GamePlayerParams>(&mut host, "resetPlayer");
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getCurrentSeason".into()),
+            MockFn::returning_ok(3u32),
+        );
+
+        let parameter_bytes = to_bytes(&GAME_PLAYER);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_implementation_reset_player(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Admin should be able to reset a player");
+        claim_eq!(*reset_invokes.borrow(), vec![GAME_PLAYER]);
+
+        let progress = Rc::new(Cell::new(ResetProgress {
+            next: Some((DEFAULT_GAME_ID, PLAYER)),
+            done: false,
+        }));
+        let returned = progress.clone();
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("resetAllPlayers".into()),
+            MockFn::new_v1(move |_parameter, _amount, _balance, _state| {
+                Ok((true, returned.get()))
+            }),
+        );
+        let parameter_bytes = to_bytes(&PlayerCursorParams {
+            after: None,
+            limit: 2,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_implementation_reset_all_players(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Admin should be able to reset all players");
+        claim_eq!(logger.logs.len(), 1, "No event should be logged before the reset is done");
+
+        progress.set(ResetProgress {
+            next: Some((DEFAULT_GAME_ID, PLAYER)),
+            done: true,
+        });
+        let result = contract_implementation_reset_all_players(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Admin should be able to reset all players");
+        claim_eq!(
+            logger.logs,
+            vec![
+                to_bytes(&VersusEvent::StatsReset(StatsResetEvent {
+                    player: Some(PLAYER),
+                    season: 3,
+                })),
+                to_bytes(&VersusEvent::StatsReset(StatsResetEvent {
+                    player: None,
+                    season: 3,
+                })),
+            ]
+        );
+    }
+
+    #[concordium_test]
+    /// Test that calls through the proxy reject in maintenance mode, while the
+    /// admin entrypoints remain usable.
+    fn test_maintenance_mode() {
+        let mut host = initialized_host(false, true);
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("joinQueue".into()),
+            MockFn::returning_ok(None::<MatchId>),
+        );
+        let set_maintenance = |host: &mut TestHost<StateImplementation>, sender, enabled: bool| {
+            let parameter_bytes = to_bytes(&enabled);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(sender);
+            ctx.set_parameter(&parameter_bytes);
+            contract_implementation_set_maintenance(&ctx, host)
+        };
+        claim_eq!(set_maintenance(&mut host, PLAYER, true), Err(CustomContractError::OnlyAdmin));
+        claim_eq!(set_maintenance(&mut host, ADMIN_ADDRESS, true), Ok(()));
+
+        let parameter_bytes = to_bytes(&GAME_PLAYER);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_invoker(PLAYER_ACCOUNT);
+        let result = contract_implementation_join_queue(&ctx, &mut host, Amount::zero());
+        claim_eq!(result, Err(CustomContractError::MaintenanceMode));
+
+        let parameter_bytes = to_bytes(&Amount::from_ccd(1));
+        let mut admin_ctx = TestReceiveContext::empty();
+        admin_ctx.set_sender(ADMIN_ADDRESS);
+        admin_ctx.set_parameter(&parameter_bytes);
+        let result = contract_implementation_set_min_stake(&admin_ctx, &mut host);
+        claim!(result.is_ok(), "Admin calls should pass in maintenance mode");
+        claim_eq!(host.state().min_stake, Amount::from_ccd(1));
+
+        claim_eq!(set_maintenance(&mut host, ADMIN_ADDRESS, false), Ok(()));
+        let result = contract_implementation_join_queue(&ctx, &mut host, Amount::zero());
+        claim_eq!(result, Ok(None));
+    }
+
+    #[concordium_test]
+    /// Test that the listed entrypoints are valid entrypoint names and match
+    /// the entrypoints declared in this file.
+    fn test_get_entrypoints() {
+        let ctx = TestReceiveContext::empty();
+        let host = initialized_host(false, true);
+        let entrypoints =
+            contract_implementation_get_entrypoints(&ctx, &host).expect_report("Query should pass");
+        for name in &entrypoints {
+            claim!(EntrypointName::new(name).is_ok(), "Invalid entrypoint name {}", name);
+        }
+
+        let source = include_str!("lib.rs");
+        let declared: Vec<String> = source
+            .lines()
+            .zip(source.lines().skip(1))
+            .filter(|(line, _)| line.trim() == "contract = \"Versus-Implementation\",")
+            .filter_map(|(_, next)| next.trim().strip_prefix("name = \""))
+            .filter_map(|name| name.strip_suffix("\","))
+            .map(String::from)
+            .collect();
+        claim_eq!(entrypoints, declared);
+    }
+
+    #[concordium_test]
+    /// Test that every event serializes with its distinct tag, and that no
+    /// tag falls into the range a CIS-2 library on the same contract logs
+    /// its events with.
+    fn test_event_tags() {
+        let events = [
+            VersusEvent::NewAdmin(NewAdminEvent {
+                new_admin: ADMIN_ADDRESS,
+            }),
+            VersusEvent::ResultHookFailed(ResultHookFailedEvent {
+                hook:   STATE,
+                player: PLAYER,
+            }),
+            VersusEvent::StatsReset(StatsResetEvent {
+                player: None,
+                season: 0,
+            }),
+            VersusEvent::RewardSkipped(RewardSkippedEvent {
+                winner: PLAYER,
+                reward: Amount::zero(),
+                pool:   Amount::zero(),
+            }),
+            VersusEvent::ResultApplied(ResultAppliedEvent {
+                player:      PLAYER,
+                result:      BattleResult::Win,
+                wins_before: 0,
+                wins_after:  1,
+                elo_before:  1200,
+                elo_after:   1200,
+            }),
+        ];
+
+        let tags: Vec<u8> = events.iter().map(|event| to_bytes(event)[0]).collect();
+        claim_eq!(tags, EVENT_TAGS);
+        // The CIS-2 library logs transfer, mint, burn, update operator and token
+        // metadata events with the tags from `u8::MAX` down to `u8::MAX - 4`.
+        claim!(
+            tags.iter().all(|tag| !(u8::MAX - 4..=u8::MAX).contains(tag)),
+            "Event tags should not be reserved by CIS-2"
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the extended state of the state contract is returned
+    /// together with the maintenance mode.
+    fn test_get_extended_state() {
+        let mut host = initialized_host(false, false);
+        host.state_mut().maintenance = true;
+        let extended_state = ExtendedState {
+            proxy_address:          PROXY,
+            implementation_address: IMPLEMENTATION,
+            paused:                 false,
+            pause_reason:           None,
+            shutdown:               false,
+            player_count:           4,
+            active_player_count:    3,
+            current_season:         1,
+            total_wins:             12,
+            total_losses:           9,
+        };
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getExtendedState".into()),
+            MockFn::returning_ok(extended_state.clone()),
+        );
+
+        let ctx = TestReceiveContext::empty();
+        claim_eq!(
+            contract_implementation_get_extended_state(&ctx, &host),
+            Ok(OperationalState {
+                state:       extended_state,
+                maintenance: true,
+            })
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the paused flag is cached while the cache is enabled, queried
+    /// again once the cache expired and that pausing or unpausing through this
+    /// contract invalidates the cache.
+    fn test_paused_cache() {
+        let mut host = initialized_host(false, false);
+        mock_state_entrypoint::<SetPausedParams>(&mut host, "setPaused");
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_self_address(IMPLEMENTATION);
+
+        let parameter_bytes = to_bytes(&(MAX_PAUSED_CACHE_SECONDS + 1));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_implementation_set_paused_cache_seconds(&ctx, &mut host),
+            Err(CustomContractError::PausedCacheTooLong)
+        );
+        let parameter_bytes = to_bytes(&10u64);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_implementation_set_paused_cache_seconds(&ctx, &mut host), Ok(()));
+
+        let set_state_paused = |host: &mut TestHost<StateImplementation>, paused: bool| {
+            host.setup_mock_entrypoint(
+                STATE,
+                OwnedEntrypointName::new_unchecked("getPaused".into()),
+                MockFn::returning_ok(paused),
+            );
+        };
+        let check_from = |host: &mut TestHost<StateImplementation>, sender: Address, now: u64| {
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(sender);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(now));
+            when_not_paused(&STATE, &ctx, host)
+        };
+        let check = |host: &mut TestHost<StateImplementation>, now: u64| {
+            check_from(host, Address::Contract(PROXY), now)
+        };
+
+        // The flag is cached on the first check and used until it expires.
+        claim_eq!(check(&mut host, 0), Ok(()));
+        set_state_paused(&mut host, true);
+        claim_eq!(check(&mut host, 9_999), Ok(()));
+        claim_eq!(check(&mut host, 10_000), Err(CustomContractError::ContractPaused));
+
+        // Unpausing through this contract invalidates the cached pause.
+        set_state_paused(&mut host, false);
+        claim_eq!(contract_un_pause(&ctx, &mut host), Ok(()));
+        claim_eq!(host.state().paused_cache, None);
+        claim_eq!(check(&mut host, 10_001), Ok(()));
+
+        // Pausing through this contract is seen by the next check.
+        set_state_paused(&mut host, true);
+        claim_eq!(contract_pause(&ctx, &mut host), Ok(()));
+        claim_eq!(check(&mut host, 10_002), Err(CustomContractError::ContractPaused));
+
+        // Direct calls see a pause set on the state contract while the cache
+        // still holds the unpaused flag, and do not touch the cache.
+        set_state_paused(&mut host, false);
+        claim_eq!(contract_un_pause(&ctx, &mut host), Ok(()));
+        claim_eq!(check(&mut host, 20_000), Ok(()));
+        set_state_paused(&mut host, true);
+        claim_eq!(check(&mut host, 20_001), Ok(()));
+        claim_eq!(check_from(&mut host, PLAYER, 20_001), Err(CustomContractError::ContractPaused));
+        claim_eq!(host.state().paused_cache.map(|cache| cache.paused), Some(false));
+    }
+}
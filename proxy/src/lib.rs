@@ -1,13 +1,117 @@
 //! # A Concordium V1 smart contract
-use concordium_std::*;
+use concordium_std::{
+    collections::{BTreeMap, BTreeSet},
+    *,
+};
 use core::fmt::Debug;
 
-/// Tag for the NewAdmin event. The CIS-2 library already uses the
-/// event tags from `u8::MAX` to `u8::MAX - 4`.
-pub const TOKEN_NEW_ADMIN_EVENT_TAG: u8 = u8::MAX - 5;
+// Versus events are tagged from 0 upwards, so that they stay clear of the tags
+// the CIS standards assign from `u8::MAX` downwards.
+
+/// Tag for the NewAdmin event.
+pub const NEW_ADMIN_EVENT_TAG: u8 = 0;
 
 /// Tag for the NewImplementation event.
-pub const TOKEN_NEW_IMPLEMENTATION_EVENT_TAG: u8 = u8::MAX - 6;
+pub const NEW_IMPLEMENTATION_EVENT_TAG: u8 = 1;
+
+/// Tag for the UpgradeCancelled event.
+pub const UPGRADE_CANCELLED_EVENT_TAG: u8 = 2;
+
+/// Tag for the Forward event.
+pub const FORWARD_EVENT_TAG: u8 = 3;
+
+/// Tag for the MetadataUpdated event.
+pub const METADATA_UPDATED_EVENT_TAG: u8 = 4;
+
+/// The lowest event tag reserved by the CIS-2 library, which uses the tags
+/// from `u8::MAX` to `u8::MAX - 4`.
+const CIS2_RESERVED_EVENT_TAG_FLOOR: u8 = u8::MAX - 4;
+
+/// All event tags logged by this contract.
+const EVENT_TAGS: &[u8] = &[
+    NEW_ADMIN_EVENT_TAG,
+    NEW_IMPLEMENTATION_EVENT_TAG,
+    UPGRADE_CANCELLED_EVENT_TAG,
+    FORWARD_EVENT_TAG,
+    METADATA_UPDATED_EVENT_TAG,
+];
+
+// Turn colliding event tags or tags in the CIS-2 range into a build error.
+const _: () = {
+    let mut i = 0;
+    while i < EVENT_TAGS.len() {
+        assert!(EVENT_TAGS[i] < CIS2_RESERVED_EVENT_TAG_FLOOR, "Event tag is reserved by CIS-2");
+        let mut j = i + 1;
+        while j < EVENT_TAGS.len() {
+            assert!(EVENT_TAGS[i] != EVENT_TAGS[j], "Event tags have to be distinct");
+            j += 1;
+        }
+        i += 1;
+    }
+};
+
+/// Implementation entrypoints that accept CCD. Every other entrypoint that is
+/// forwarded by the fallback has to be invoked with a zero amount.
+const PAYABLE_ENTRYPOINTS: &[&str] =
+    &["addPlayer", "fundRewards", "joinQueue", "registerAndStake", "stakeMatch"];
+
+/// Maximum number of entries in a batch, e.g. calls in a `multicall`, until
+/// the admin sets a different limit.
+const DEFAULT_MAX_BATCH_SIZE: u32 = 16;
+
+/// Maximum number of past admins kept in the admin history.
+const MAX_ADMIN_HISTORY: usize = 32;
+
+/// Maximum number of entries returned by a paginated query.
+const MAX_PAGE_SIZE: u32 = 50;
+
+/// Maximum length of the name of the contract in bytes.
+const MAX_NAME_LENGTH: usize = 64;
+
+/// Maximum length of the metadata URL in bytes.
+const MAX_METADATA_URL_LENGTH: usize = 256;
+
+/// Maximum size in bytes of a parameter forwarded by the fallback until the
+/// admin sets a different limit.
+const DEFAULT_MAX_PARAMETER_SIZE: u32 = 1024;
+
+/// Seconds between scheduling an implementation update and the earliest time
+/// it can be executed, until the admin sets a different delay.
+const DEFAULT_UPGRADE_DELAY_SECONDS: u64 = 24 * 60 * 60;
+
+/// Shortest upgrade delay the admin can set, in seconds.
+const MIN_UPGRADE_DELAY_SECONDS: u64 = 60 * 60;
+
+/// Implementation entrypoints that the fallback keeps forwarding while the
+/// contract is paused. These only read state, except `refundStake`, which
+/// returns stakes after a shutdown.
+const PAUSE_EXEMPT_ENTRYPOINTS: &[&str] = &[
+    "view",
+    "getActivePlayerCount",
+    "getEntrypoints",
+    "getExtendedState",
+    "getGames",
+    "getMatchDurationStats",
+    "getPaused",
+    "getPausedSince",
+    "getPlayerData",
+    "getPlayerDataOrDefault",
+    "getPlayerFull",
+    "getPlayerRank",
+    "getPlayerStateHistory",
+    "getPlayerSummary",
+    "getPlayersAddedInSeason",
+    "getProtocolAddresses",
+    "getQueue",
+    "getQueueLength",
+    "getResultHook",
+    "getRewardPoolBalance",
+    "getTopByElo",
+    "getTournament",
+    "getVersion",
+    "getWinStreak",
+    "refundStake",
+];
 
 // Types
 
@@ -25,17 +129,35 @@ enum VersusEvent {
     NewAdmin(NewAdminEvent),
     /// A new implementation event.
     NewImplementation(NewImplementationEvent),
+    /// The admin cancelled the scheduled implementation update.
+    UpgradeCancelled(ScheduledUpgrade),
+    /// The fallback forwarded a call to the implementation.
+    Forward(ForwardEvent),
+    /// The admin changed the metadata URL or hash.
+    MetadataUpdated(Metadata),
 }
 
 impl Serial for VersusEvent {
     fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
         match self {
             VersusEvent::NewAdmin(event) => {
-                out.write_u8(TOKEN_NEW_ADMIN_EVENT_TAG)?;
+                out.write_u8(NEW_ADMIN_EVENT_TAG)?;
                 event.serial(out)
             }
             VersusEvent::NewImplementation(event) => {
-                out.write_u8(TOKEN_NEW_IMPLEMENTATION_EVENT_TAG)?;
+                out.write_u8(NEW_IMPLEMENTATION_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::UpgradeCancelled(event) => {
+                out.write_u8(UPGRADE_CANCELLED_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::Forward(event) => {
+                out.write_u8(FORWARD_EVENT_TAG)?;
+                event.serial(out)
+            }
+            VersusEvent::MetadataUpdated(event) => {
+                out.write_u8(METADATA_UPDATED_EVENT_TAG)?;
                 event.serial(out)
             }
         }
@@ -43,14 +165,265 @@ impl Serial for VersusEvent {
 }
 
 /// The `proxy` contract state.
-#[derive(Serial, Deserial, Clone, SchemaType)]
+#[derive(Serial, Deserial, Clone, Debug, PartialEq, SchemaType)]
 struct StateProxy {
     /// The admin address can upgrade the implementation contract.
+    admin:                    Address,
+    /// Address of the versus implementation contract.
+    implementation_address:   ContractAddress,
+    /// Address of the versus state contract.
+    state_address:            ContractAddress,
+    /// Senders allowed to invoke an implementation entrypoint through the
+    /// fallback. Entrypoints without an entry are public.
+    access_control:           BTreeMap<String, BTreeSet<Address>>,
+    /// Past admins, oldest first. Only the most recent `MAX_ADMIN_HISTORY`
+    /// admins are kept.
+    admin_history:            Vec<Address>,
+    /// Human readable name of the deployment, at most `MAX_NAME_LENGTH`
+    /// bytes long.
+    name:                     String,
+    /// The fallback rejects all entrypoints that are not pause exempt while
+    /// this is set, independent of the paused flag of the state contract.
+    paused:                   bool,
+    /// Maximum size in bytes of a parameter forwarded by the fallback.
+    max_parameter_size:       u32,
+    /// Maximum number of entries in a batch, e.g. calls in a `multicall`.
+    max_batch_size:           u32,
+    /// Seconds a scheduled implementation update waits before it can be
+    /// executed.
+    upgrade_delay_seconds:    u64,
+    /// The implementation update waiting for its delay to pass.
+    scheduled_upgrade:        Option<ScheduledUpgrade>,
+    /// A shorter upgrade delay waiting for the current delay to pass.
+    scheduled_upgrade_delay:  Option<ScheduledUpgradeDelay>,
+    /// Implementation contracts approved by the admin, with the time their
+    /// approval becomes usable at. Updates can only be scheduled to an
+    /// approved implementation once its approval is usable.
+    approved_implementations: BTreeMap<ContractAddress, Timestamp>,
+    /// The fallback logs a forward event for every call while this is set.
+    /// Disabled by default, since logging costs extra energy on every call.
+    forward_events:           bool,
+    /// URL of the metadata off-chain clients fetch branding and configuration
+    /// from, at most `MAX_METADATA_URL_LENGTH` bytes long.
+    metadata_url:             Option<String>,
+    /// SHA-256 hash of the metadata document, so that clients can verify what
+    /// they fetched. Only set together with the metadata URL.
+    metadata_hash:            Option<[u8; 32]>,
+}
+
+/// The state of the `implementation` contract, as returned by its `view`
+/// function.
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq)]
+struct StateImplementation {
+    /// The admin address can pause/unpause the contract
     admin:                  Address,
-    /// Address of the w_ccd implementation contract.
+    /// Addresses of the protocol
+    protocol_addresses:     ProtocolAddressesImplementation,
+    /// The amount a player has to pay to be added.
+    registration_fee:       Amount,
+    /// The account receiving the registration fees.
+    fee_recipient:          AccountAddress,
+    /// The admin can update players directly while recovery mode is enabled.
+    recovery_mode:          bool,
+    /// The deposit held for the player waiting in the matchmaking queue of
+    /// each game.
+    queue_deposits:         BTreeMap<u32, (AccountAddress, Amount)>,
+    /// Events are only logged while enabled.
+    events_enabled:         bool,
+    /// The minimum amount a player can stake on a match.
+    min_stake:              Amount,
+    /// Contract notified after every recorded battle result.
+    result_hook:            Option<ContractAddress>,
+    /// Entrypoint of the result hook contract that is invoked with the
+    /// `UpdateBattleResultParams`.
+    result_hook_entrypoint: String,
+    /// The amount paid from the reward pool to the winner of a match.
+    reward_per_win:         Amount,
+    /// The amount funded for win rewards and not paid out yet.
+    reward_pool:            Amount,
+    /// Entrypoints called through the proxy reject while maintenance mode is
+    /// enabled. Admin entrypoints remain usable.
+    maintenance:            bool,
+    /// The amount staked on tournaments with `registerAndStake`.
+    tournament_pool:        Amount,
+    /// Seconds the paused flag of the state contract is cached for. Every
+    /// check invokes the state contract if zero.
+    paused_cache_seconds:   u64,
+    /// The cached paused flag of the state contract.
+    paused_cache:           Option<PausedCache>,
+}
+
+/// The paused flag of the state contract, as cached by the `implementation`
+/// contract.
+#[derive(Serialize, SchemaType, Clone, Copy, Debug, PartialEq)]
+struct PausedCache {
+    /// The paused flag of the state contract.
+    paused:     bool,
+    /// The cached flag is used until this time.
+    expires_at: Timestamp,
+}
+
+/// The basic state of the `state` contract, as returned by its `view`
+/// function.
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq)]
+struct ReturnBasicState {
+    /// Address of the versus proxy contract.
+    proxy_address:          ContractAddress,
+    /// Address of the versus implementation contract.
     implementation_address: ContractAddress,
-    /// Address of the w_ccd state contract.
-    state_address:          ContractAddress,
+    /// Contract is paused/unpaused.
+    paused:                 bool,
+    /// Why the contract was paused, cleared when it is unpaused.
+    pause_reason:           Option<String>,
+}
+
+/// The status of the `state` contract, as returned by its `getStatus`
+/// function.
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq)]
+struct StateStatus {
+    /// The protocol addresses are set.
+    initialized: bool,
+    /// Contract is paused/unpaused.
+    paused:      bool,
+    /// The contract was shut down and rejects all mutations.
+    shutdown:    bool,
+}
+
+/// The return type for the proxy contract function `getHealth`.
+#[derive(Serial, SchemaType, Debug, PartialEq)]
+struct HealthReport {
+    /// The admin of the proxy contract.
+    admin:                      Address,
+    /// The admin of the implementation contract.
+    implementation_admin:       Address,
+    /// The proxy rejects all entrypoints that are not pause exempt.
+    proxy_paused:               bool,
+    /// The implementation contract has its protocol addresses set.
+    implementation_initialized: bool,
+    /// The implementation contract only accepts admin calls.
+    maintenance:                bool,
+    /// The state contract has its protocol addresses set.
+    state_initialized:          bool,
+    /// The state contract is paused.
+    state_paused:               bool,
+    /// The state contract was shut down and rejects all mutations.
+    shutdown:                   bool,
+}
+
+/// The pause state of the protocol resolved from all its flags, as returned by
+/// `getEffectivePauseState`.
+#[derive(Serialize, SchemaType, Clone, Copy, Debug, PartialEq)]
+enum EffectivePauseState {
+    /// All entrypoints are available.
+    Operational,
+    /// The proxy or the state contract is paused.
+    Paused,
+    /// The implementation contract only accepts admin calls.
+    Maintenance,
+    /// The state contract was shut down and rejects all mutations.
+    ShutDown,
+}
+
+impl HealthReport {
+    /// Resolve the pause flags of all contracts into a single state. A shut
+    /// down takes precedence over maintenance mode, which takes precedence over
+    /// a paused proxy or state contract.
+    fn effective_pause_state(&self) -> EffectivePauseState {
+        if self.shutdown {
+            EffectivePauseState::ShutDown
+        } else if self.maintenance {
+            EffectivePauseState::Maintenance
+        } else if self.proxy_paused || self.state_paused {
+            EffectivePauseState::Paused
+        } else {
+            EffectivePauseState::Operational
+        }
+    }
+}
+
+/// The return type for the proxy contract function `getFullState`.
+#[derive(Serial, SchemaType, Debug, PartialEq)]
+struct FullState {
+    /// The state of the proxy contract.
+    proxy:          StateProxy,
+    /// The state of the implementation contract.
+    implementation: StateImplementation,
+    /// The basic state of the state contract.
+    state:          ReturnBasicState,
+}
+
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq)]
+enum ProtocolAddressesImplementation {
+    UnInitialized,
+    Initialized {
+        /// Address of the versus proxy contract.
+        proxy_address: ContractAddress,
+        /// Address of the versus state contract.
+        state_address: ContractAddress,
+    },
+}
+
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+enum PlayerState {
+    NotAdded,
+    Active,
+    Suspended
+}
+
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+enum BattleResult {
+    NoResult,
+    Win,
+    Loss
+}
+
+/// The data the state contract tracks for each player.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct PlayerData {
+    /// Serialization version of the player data.
+    version:        u8,
+    /// The player's state
+    state:          PlayerState,
+    /// Result of the last battle counted in `wins` or `losses`, `NoResult`
+    /// if there is none.
+    last_result:    BattleResult,
+    /// Number of battles the player has won.
+    wins:           u32,
+    /// Number of battles the player has lost.
+    losses:         u32,
+    /// The player's Elo rating.
+    elo:            i32,
+    /// The most recent state transitions of the player.
+    state_history:  StateHistory,
+    /// Number of consecutive wins if positive, of consecutive losses if
+    /// negative.
+    current_streak: i32,
+    /// Longest run of consecutive wins.
+    best_streak:    u32,
+    /// Time the player was added or their last battle result was recorded.
+    last_updated:   Timestamp,
+    /// Time the player was added.
+    registered_at:  Timestamp,
+    /// Battle results submitted for the player within the rate limit window.
+    rate_window:    RateWindow,
+    /// Season the stats were recorded in.
+    season:         u32,
+}
+
+/// The battle results submitted for a player within the rate limit window.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct RateWindow {
+    /// Times of the submitted results, oldest first.
+    submitted: Vec<Timestamp>,
+}
+
+/// Fixed-length ring buffer of the most recent state transitions of a player.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct StateHistory {
+    /// The transitions, `None` for slots that were not written yet.
+    entries: [Option<(PlayerState, Timestamp)>; 8],
+    /// Index of the slot written next.
+    next:    u8,
 }
 
 /// NewAdminEvent.
@@ -67,43 +440,191 @@ struct NewImplementationEvent {
     new_implementation: ContractAddress,
 }
 
+/// ForwardEvent.
+#[derive(Serial)]
+struct ForwardEvent {
+    /// Name of the implementation entrypoint the call was forwarded to.
+    entrypoint: OwnedEntrypointName,
+    /// Sender of the forwarded call.
+    sender:     Address,
+    /// Amount of CCD forwarded with the call.
+    amount:     Amount,
+}
+
+/// The metadata URL of the deployment with the hash of the document. It is
+/// the parameter of `setMetadataUrl`, the return value of `getMetadataUrl` and
+/// the content of the `MetadataUpdated` event.
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq)]
+struct Metadata {
+    /// The metadata URL, `None` if it is not set.
+    metadata_url:  Option<String>,
+    /// SHA-256 hash of the metadata document, `None` if it is not provided.
+    metadata_hash: Option<[u8; 32]>,
+}
+
 /// The parameter type for the state contract function `initialize`.
 #[derive(Serialize, SchemaType)]
 struct InitializeStateParams {
-    /// Address of the w_ccd proxy contract.
+    /// Address of the versus proxy contract.
     proxy_address:          ContractAddress,
-    /// Address of the w_ccd implementation contract.
+    /// Address of the versus implementation contract.
     implementation_address: ContractAddress,
 }
 
 /// The parameter type for the implementation contract function `initialize`.
 #[derive(Serialize, SchemaType)]
 struct InitializeImplementationParams {
-    /// Address of the w_ccd proxy contract.
+    /// Address of the versus proxy contract.
     proxy_address: ContractAddress,
-    /// Address of the w_ccd state contract.
+    /// Address of the versus state contract.
     state_address: ContractAddress,
 }
 
 /// The parameter type for the proxy contract function `init`.
 #[derive(Serialize, SchemaType)]
 struct InitProxyParams {
-    /// Address of the w_ccd implementation contract.
+    /// Address of the versus implementation contract.
     implementation_address: ContractAddress,
-    /// Address of the w_ccd state contract.
+    /// Address of the versus state contract.
     state_address:          ContractAddress,
+    /// Human readable name of the deployment.
+    name:                   String,
 }
 
 /// The parameter type for the state contract function
 /// `set_implementation_address`.
 #[derive(Serialize, SchemaType)]
 struct SetImplementationAddressParams {
-    /// Address of the w_ccd implementation contract.
+    /// Address of the versus implementation contract.
+    implementation_address: ContractAddress,
+}
+
+/// The parameter type for the proxy contract function `swapBackend`.
+#[derive(Serialize, SchemaType)]
+struct SwapBackendParams {
+    /// Address of the new versus implementation contract.
+    implementation_address: ContractAddress,
+    /// Address of the new versus state contract.
+    state_address:          ContractAddress,
+}
+
+/// The parameter type for the proxy contract function `approveImplementation`.
+#[derive(Serialize, SchemaType)]
+struct ApproveImplementationParams {
+    /// Address of the implementation contract.
+    implementation_address: ContractAddress,
+    /// Whether the implementation is approved or revoked.
+    approved:               bool,
+}
+
+/// An implementation update scheduled by `updateImplementation` or
+/// `swapBackend`.
+#[derive(Serialize, SchemaType, Clone, Copy, Debug, PartialEq)]
+struct ScheduledUpgrade {
+    /// Address of the new implementation contract.
+    implementation_address: ContractAddress,
+    /// Address of the new state contract if the update was scheduled by
+    /// `swapBackend`.
+    state_address:          Option<ContractAddress>,
+    /// Earliest time the update can be executed at.
+    ready_at:               Timestamp,
+}
+
+/// A decrease of the upgrade delay scheduled by `setUpgradeDelay`.
+#[derive(Serialize, SchemaType, Clone, Copy, Debug, PartialEq)]
+struct ScheduledUpgradeDelay {
+    /// The new upgrade delay in seconds.
+    upgrade_delay_seconds: u64,
+    /// Earliest time the new delay can be applied at.
+    ready_at:              Timestamp,
+}
+
+/// The parameter type for the implementation contract functions
+/// `getPlayerData` and `getPlayerFull`.
+#[derive(Serialize, SchemaType)]
+struct GamePlayerParams {
+    /// The game of the player.
+    game_id: u32,
+    /// The player.
+    player:  Address,
+}
+
+/// The parameter type for the state contract function `setPaused`.
+#[derive(Serialize, SchemaType)]
+struct SetPausedParams {
+    /// Contract is paused/unpaused.
+    paused: bool,
+}
+
+/// The parameter type for the proxy contract function `setEntrypointAccess`.
+#[derive(Serialize, SchemaType)]
+struct SetEntrypointAccessParams {
+    /// Name of the implementation entrypoint.
+    entrypoint:      String,
+    /// Senders allowed to invoke the entrypoint. An empty set makes the
+    /// entrypoint public.
+    allowed_senders: BTreeSet<Address>,
+}
+
+/// A single call of the proxy contract function `multicall`.
+#[derive(Serialize, SchemaType)]
+struct MulticallCall {
+    /// Name of the implementation entrypoint.
+    entrypoint: String,
+    /// The serialized parameter of the call.
+    parameter:  Vec<u8>,
+}
+
+/// The return type for the proxy contract function `getImplementationVersion`.
+#[derive(Serialize, SchemaType, Debug, PartialEq)]
+struct ImplementationVersion {
+    /// Address of the versus implementation contract.
     implementation_address: ContractAddress,
+    /// Version reported by the implementation contract.
+    version:                u32,
+}
+
+/// A protocol address the implementation contract holds a copy of.
+#[derive(Serialize, SchemaType, Clone, Copy, Debug, PartialEq)]
+enum ProtocolAddressField {
+    /// The address of the proxy contract.
+    Proxy,
+    /// The address of the state contract.
+    State,
+}
+
+/// The return type for the proxy contract function
+/// `getProxyImplementationMismatch`.
+#[derive(Serialize, SchemaType, Debug, PartialEq)]
+struct ProxyImplementationMismatch {
+    /// Whether the implementation disagrees with the proxy on any address.
+    mismatch: bool,
+    /// The addresses the implementation disagrees with the proxy on.
+    fields:   Vec<ProtocolAddressField>,
+}
+
+/// The return type for the proxy contract function `getAddresses`.
+#[derive(Serialize, SchemaType, Debug, PartialEq)]
+struct ProtocolAddresses {
+    /// Address of the versus proxy contract.
+    proxy:          ContractAddress,
+    /// Address of the versus implementation contract.
+    implementation: ContractAddress,
+    /// Address of the versus state contract.
+    state:          ContractAddress,
+}
+
+/// The parameter type for paginated queries.
+#[derive(Serialize, SchemaType)]
+struct PaginationParams {
+    /// Number of entries to skip.
+    offset: u32,
+    /// Maximum number of entries to return, capped at `MAX_PAGE_SIZE`.
+    limit:  u32,
 }
 
 /// The different errors the contract can produce.
-#[derive(Serialize, Debug, PartialEq, Eq, Reject, SchemaType)]
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy, Reject, SchemaType)]
 enum CustomContractError {
     /// Failed parsing the parameter.
     #[from(ParseError)]
@@ -130,10 +651,109 @@ enum CustomContractError {
     StateInvokeError,
     /// Only admin
     OnlyAdmin,
+    /// CCD was sent to an entrypoint that is not payable.
+    UnexpectedAmount,
+    /// Sender is not allowed to invoke the entrypoint.
+    SenderNotAllowed,
+    /// Too many calls in a batch.
+    BatchTooLarge,
+    /// Name is longer than `MAX_NAME_LENGTH` bytes.
+    NameTooLong,
+    /// The forwarded parameter is larger than the maximum parameter size.
+    ParameterTooLarge,
+    /// No implementation update or upgrade delay decrease is scheduled.
+    NoUpgradeScheduled,
+    /// The delay of the scheduled implementation update or upgrade delay
+    /// decrease has not passed yet.
+    UpgradeNotReady,
+    /// The implementation was not approved by the admin.
+    ImplementationNotApproved,
+    /// Metadata URL is longer than `MAX_METADATA_URL_LENGTH` bytes.
+    MetadataUrlTooLong,
+    /// A metadata hash was given without a metadata URL.
+    MetadataHashWithoutUrl,
+    /// The new implementation and state contracts do not reference each
+    /// other and this proxy.
+    BackendMismatch,
+    /// An implementation update is scheduled.
+    UpgradeScheduled,
+    /// The current implementation holds funds that a backend swap would
+    /// strand.
+    FundsHeld,
+    /// The upgrade delay is shorter than `MIN_UPGRADE_DELAY_SECONDS`.
+    UpgradeDelayTooShort,
+    /// The upgrade delay of the approval of the implementation has not passed
+    /// yet.
+    ApprovalNotReady,
 }
 
 type ContractResult<A> = Result<A, CustomContractError>;
 
+/// All errors in the order they are declared in, which is the order of their
+/// reject codes.
+const ERROR_VARIANTS: &[CustomContractError] = &[
+    CustomContractError::ParseParams,
+    CustomContractError::LogFull,
+    CustomContractError::LogMalformed,
+    CustomContractError::InvokeContractError,
+    CustomContractError::InvokeTransferError,
+    CustomContractError::ContractPaused,
+    CustomContractError::AlreadyInitialized,
+    CustomContractError::UnInitialized,
+    CustomContractError::OnlyImplementation,
+    CustomContractError::OnlyProxy,
+    CustomContractError::StateInvokeError,
+    CustomContractError::OnlyAdmin,
+    CustomContractError::UnexpectedAmount,
+    CustomContractError::SenderNotAllowed,
+    CustomContractError::BatchTooLarge,
+    CustomContractError::NameTooLong,
+    CustomContractError::ParameterTooLarge,
+    CustomContractError::NoUpgradeScheduled,
+    CustomContractError::UpgradeNotReady,
+    CustomContractError::ImplementationNotApproved,
+    CustomContractError::MetadataUrlTooLong,
+    CustomContractError::MetadataHashWithoutUrl,
+    CustomContractError::BackendMismatch,
+    CustomContractError::UpgradeScheduled,
+    CustomContractError::FundsHeld,
+    CustomContractError::UpgradeDelayTooShort,
+    CustomContractError::ApprovalNotReady,
+];
+
+/// Helper function to get the name of an error as declared.
+fn error_name(error: CustomContractError) -> &'static str {
+    match error {
+        CustomContractError::ParseParams => "ParseParams",
+        CustomContractError::LogFull => "LogFull",
+        CustomContractError::LogMalformed => "LogMalformed",
+        CustomContractError::InvokeContractError => "InvokeContractError",
+        CustomContractError::InvokeTransferError => "InvokeTransferError",
+        CustomContractError::ContractPaused => "ContractPaused",
+        CustomContractError::AlreadyInitialized => "AlreadyInitialized",
+        CustomContractError::UnInitialized => "UnInitialized",
+        CustomContractError::OnlyImplementation => "OnlyImplementation",
+        CustomContractError::OnlyProxy => "OnlyProxy",
+        CustomContractError::StateInvokeError => "StateInvokeError",
+        CustomContractError::OnlyAdmin => "OnlyAdmin",
+        CustomContractError::UnexpectedAmount => "UnexpectedAmount",
+        CustomContractError::SenderNotAllowed => "SenderNotAllowed",
+        CustomContractError::BatchTooLarge => "BatchTooLarge",
+        CustomContractError::NameTooLong => "NameTooLong",
+        CustomContractError::ParameterTooLarge => "ParameterTooLarge",
+        CustomContractError::NoUpgradeScheduled => "NoUpgradeScheduled",
+        CustomContractError::UpgradeNotReady => "UpgradeNotReady",
+        CustomContractError::ImplementationNotApproved => "ImplementationNotApproved",
+        CustomContractError::MetadataUrlTooLong => "MetadataUrlTooLong",
+        CustomContractError::MetadataHashWithoutUrl => "MetadataHashWithoutUrl",
+        CustomContractError::BackendMismatch => "BackendMismatch",
+        CustomContractError::UpgradeScheduled => "UpgradeScheduled",
+        CustomContractError::FundsHeld => "FundsHeld",
+        CustomContractError::UpgradeDelayTooShort => "UpgradeDelayTooShort",
+        CustomContractError::ApprovalNotReady => "ApprovalNotReady",
+    }
+}
+
 /// Mapping the logging errors to ContractError.
 impl From<LogError> for CustomContractError {
     fn from(le: LogError) -> Self {
@@ -185,14 +805,28 @@ fn contract_proxy_init<S: HasStateApi>(
 ) -> InitResult<StateProxy> {
     // Set state and implementation addresses.
     let params: InitProxyParams = ctx.parameter_cursor().get()?;
+    ensure!(params.name.len() <= MAX_NAME_LENGTH, CustomContractError::NameTooLong.into());
 
     // Get the instantiater of this contract instance.
     let invoker = Address::Account(ctx.init_origin());
     // Construct the initial proxy contract state.
     let state = StateProxy {
-        admin:                  invoker,
-        state_address:          params.state_address,
-        implementation_address: params.implementation_address,
+        admin:                    invoker,
+        state_address:            params.state_address,
+        implementation_address:   params.implementation_address,
+        access_control:           BTreeMap::new(),
+        admin_history:            Vec::new(),
+        name:                     params.name,
+        paused:                   false,
+        max_parameter_size:       DEFAULT_MAX_PARAMETER_SIZE,
+        max_batch_size:           DEFAULT_MAX_BATCH_SIZE,
+        upgrade_delay_seconds:    DEFAULT_UPGRADE_DELAY_SECONDS,
+        scheduled_upgrade:        None,
+        scheduled_upgrade_delay:  None,
+        approved_implementations: BTreeMap::new(),
+        forward_events:           false,
+        metadata_url:             None,
+        metadata_hash:            None,
     };
 
     Ok(state)
@@ -201,10 +835,8 @@ fn contract_proxy_init<S: HasStateApi>(
 /// Initializes the `implementation` and `state` contracts by using the
 /// addresses that the `proxy` contract was set up. This function will call the
 /// `initialize` functions on the `implementation` as well as the `state`
-/// contracts. This function logs a mint event with amount 0 to signal that a
-/// new CIS-2 token was deployed. This function logs an event including the
-/// metadata for this token. This function logs a new implementation event.
-/// This function logs a new admin event.
+/// contracts. This function logs a new implementation event and a new admin
+/// event.
 #[receive(
     contract = "Versus-Proxy",
     name = "initialize",
@@ -255,10 +887,12 @@ fn contract_proxy_initialize<S: HasStateApi>(
 }
 
 /// The fallback method, which redirects the invocations to the implementation.
+/// Logs a forward event if forward events are enabled.
 #[receive(
     contract = "Versus-Proxy",
     error = "CustomContractError",
     fallback,
+    enable_logger,
     mutable,
     payable
 )]
@@ -266,21 +900,112 @@ fn receive_fallback<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<StateProxy, StateApiType = S>,
     amount: Amount,
+    logger: &mut impl HasLogger,
 ) -> ReceiveResult<RawReturnValue> {
     let entrypoint = ctx.named_entrypoint();
-    let implementation = host.state().implementation_address;
 
-    let mut parameter_buffer = vec![0; ctx.parameter_cursor().size() as usize];
+    // Reject CCD sent to a non-payable entrypoint here instead of forwarding it
+    // and surfacing the implementation's reject.
+    ensure!(
+        amount == Amount::zero() || is_payable_entrypoint(entrypoint.as_entrypoint_name()),
+        CustomContractError::UnexpectedAmount.into()
+    );
+
+    // Check the parameter size before allocating the buffer for it.
+    let parameter_size = ctx.parameter_cursor().size();
+    ensure_parameter_size(host.state(), parameter_size)?;
+
+    let mut parameter_buffer = vec![0; parameter_size as usize];
     ctx.parameter_cursor().read_exact(&mut parameter_buffer)?;
 
+    let return_value = forward_to_implementation(
+        host,
+        logger,
+        ctx.sender(),
+        entrypoint.as_entrypoint_name(),
+        &parameter_buffer,
+        amount,
+    )?;
+
+    Ok(RawReturnValue(return_value))
+}
+
+/// Forwards several invokes to the implementation in one transaction. The
+/// calls are forwarded in order and are subject to the same checks as calls
+/// through the fallback, and log the same forward events. If any call is
+/// rejected, the whole transaction is rejected. Returns the raw return value
+/// of each call.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "multicall",
+    parameter = "Vec<MulticallCall>",
+    return_value = "Vec<Vec<u8>>",
+    error = "CustomContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_proxy_multicall<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ReceiveResult<Vec<Vec<u8>>> {
+    let calls: Vec<MulticallCall> =
+        ctx.parameter_cursor().get().map_err(CustomContractError::from)?;
+    ensure_batch_size(host.state(), calls.len())?;
+
+    let mut return_values = Vec::with_capacity(calls.len());
+    for call in calls {
+        let entrypoint = EntrypointName::new(&call.entrypoint)
+            .map_err(|_| CustomContractError::ParseParams)?;
+        return_values.push(forward_to_implementation(
+            host,
+            logger,
+            ctx.sender(),
+            entrypoint,
+            &call.parameter,
+            Amount::zero(),
+        )?);
+    }
+
+    Ok(return_values)
+}
+
+/// Helper function to forward an invoke unaltered to the implementation
+/// contract after checking the parameter size, the sender and the paused
+/// state. Logs a forward event if forward events are enabled. A reject of the
+/// implementation is passed on with its reason and return value.
+fn forward_to_implementation<S>(
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+    logger: &mut impl HasLogger,
+    sender: Address,
+    entrypoint: EntrypointName,
+    parameter: &[u8],
+    amount: Amount,
+) -> ReceiveResult<Vec<u8>> {
+    let implementation = host.state().implementation_address;
+
+    // Check that the parameter is not larger than allowed.
+    ensure_parameter_size(host.state(), parameter.len() as u32)?;
+
+    // Check that the sender is allowed to invoke the entrypoint.
+    only_allowed_sender(host.state(), entrypoint, sender)?;
+
+    // Check that contract is not paused, unless the entrypoint only reads state.
+    if !is_pause_exempt_entrypoint(entrypoint) {
+        when_not_paused(&host.state().state_address, host)?;
+    }
+
+    if host.state().forward_events {
+        logger.log(&VersusEvent::Forward(ForwardEvent {
+            entrypoint: entrypoint.into(),
+            sender,
+            amount,
+        }))?;
+    }
+
     // Forwarding the invoke unaltered to the implementation contract.
     let mut return_value = host
-        .invoke_contract_raw(
-            &implementation,
-            Parameter(&parameter_buffer[..]),
-            entrypoint.as_entrypoint_name(),
-            amount,
-        )
+        .invoke_contract_raw(&implementation, Parameter(parameter), entrypoint, amount)
         .map_err(|r| {
             if let CallContractError::LogicReject {
                 reason,
@@ -301,7 +1026,71 @@ fn receive_fallback<S: HasStateApi>(
 
     let mut rv_buffer = vec![0; return_value.size() as usize];
     return_value.read_exact(&mut rv_buffer)?;
-    Ok(RawReturnValue(rv_buffer))
+    Ok(rv_buffer)
+}
+
+/// Helper function to ensure that a forwarded parameter is not larger than
+/// `max_parameter_size`.
+fn ensure_parameter_size(state: &StateProxy, parameter_size: u32) -> ContractResult<()> {
+    ensure!(parameter_size <= state.max_parameter_size, CustomContractError::ParameterTooLarge);
+    Ok(())
+}
+
+/// Helper function to check if an implementation entrypoint accepts CCD.
+fn is_payable_entrypoint(entrypoint: EntrypointName) -> bool {
+    let entrypoint: &str = entrypoint.into();
+    PAYABLE_ENTRYPOINTS.contains(&entrypoint)
+}
+
+/// Helper function to check if an implementation entrypoint can be invoked
+/// while the contract is paused.
+fn is_pause_exempt_entrypoint(entrypoint: EntrypointName) -> bool {
+    let entrypoint: &str = entrypoint.into();
+    PAUSE_EXEMPT_ENTRYPOINTS.contains(&entrypoint)
+}
+
+/// Helper function to ensure that the sender is allowed to invoke an
+/// implementation entrypoint through the fallback.
+fn only_allowed_sender(
+    state: &StateProxy,
+    entrypoint: EntrypointName,
+    sender: Address,
+) -> ContractResult<()> {
+    let entrypoint: &str = entrypoint.into();
+    if let Some(allowed_senders) = state.access_control.get(entrypoint) {
+        ensure!(allowed_senders.contains(&sender), CustomContractError::SenderNotAllowed);
+    }
+
+    Ok(())
+}
+
+/// Helper function to ensure contract is not paused.
+fn when_not_paused<S>(
+    state_address: &ContractAddress,
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check the proxy flag first, it does not need an invoke.
+    ensure!(!host.state().paused, CustomContractError::ContractPaused);
+
+    let paused = host.invoke_contract_read_only(
+        state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("getPaused"),
+        Amount::zero(),
+    )?;
+
+    let paused: bool = paused
+        .ok_or(CustomContractError::StateInvokeError)?
+        .get()?;
+    // Check that contract is not paused.
+    ensure!(!paused, CustomContractError::ContractPaused);
+    Ok(())
+}
+
+/// Helper function to ensure a batch does not exceed the maximum batch size.
+fn ensure_batch_size(state: &StateProxy, batch_size: usize) -> ContractResult<()> {
+    ensure!(batch_size <= state.max_batch_size as usize, CustomContractError::BatchTooLarge);
+    Ok(())
 }
 
 // Simple helper functions to ensure that a call comes from the implementation
@@ -326,153 +1115,2417 @@ fn only_implementation(
     return_value = "StateProxy",
     error = "CustomContractError"
 )]
-fn contract_proxy_view<'a, 'b, S: HasStateApi>(
-    _ctx: &'b impl HasReceiveContext,
+fn contract_proxy_view<'a, S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
     host: &'a impl HasHost<StateProxy, StateApiType = S>,
 ) -> ContractResult<&'a StateProxy> {
     Ok(host.state())
 }
 
-/// This functions allows the admin of the proxy to transfer the address to a
-/// new admin.
+/// Function to get the CCD balance held by the proxy contract.
 #[receive(
     contract = "Versus-Proxy",
-    name = "updateAdmin",
-    parameter = "Address",
-    error = "CustomContractError",
-    enable_logger,
-    mutable
+    name = "getBalance",
+    return_value = "Amount",
+    error = "CustomContractError"
 )]
-fn contract_proxy_update_admin<S: HasStateApi>(
+fn contract_proxy_get_balance<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<Amount> {
+    Ok(host.self_balance())
+}
+
+/// Function to get the data of a player in a game from the implementation with
+/// a typed return value.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "getPlayerData",
+    parameter = "GamePlayerParams",
+    return_value = "(PlayerState, BattleResult)",
+    error = "CustomContractError"
+)]
+fn contract_proxy_get_player_data<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
-    host: &mut impl HasHost<StateProxy, StateApiType = S>,
-    logger: &mut impl HasLogger,
-) -> ContractResult<()> {
-    // Check that only the old admin is authorized to update the admin address.
-    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<(PlayerState, BattleResult)> {
     // Parse the parameter.
-    let new_admin = ctx.parameter_cursor().get()?;
-    // Update admin.
-    host.state_mut().admin = new_admin;
+    let param: GamePlayerParams = ctx.parameter_cursor().get()?;
 
-    // Log a new admin event.
-    logger.log(&VersusEvent::NewAdmin(NewAdminEvent {
-        new_admin,
-    }))?;
+    let player_data = host.invoke_contract_read_only(
+        &host.state().implementation_address,
+        &param,
+        EntrypointName::new_unchecked("getPlayerData"),
+        Amount::zero(),
+    )?;
 
-    Ok(())
+    let player_data = player_data.ok_or(CustomContractError::InvokeContractError)?.get()?;
+
+    Ok(player_data)
 }
 
-/// Function to update the protocol with a new implementation.
-/// Only the admin on the proxy can call this function.
+/// Function to get the complete data of a player in a game, including the
+/// counters and the Elo rating, from the implementation with a typed return
+/// value.
 #[receive(
     contract = "Versus-Proxy",
-    name = "updateImplementation",
-    parameter = "SetImplementationAddressParams",
-    error = "CustomContractError",
-    enable_logger,
-    mutable
+    name = "getPlayerFull",
+    parameter = "GamePlayerParams",
+    return_value = "PlayerData",
+    error = "CustomContractError"
 )]
-fn contract_proxy_update_implementation<S: HasStateApi>(
+fn contract_proxy_get_player_full<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
-    host: &mut impl HasHost<StateProxy, StateApiType = S>,
-    logger: &mut impl HasLogger,
-) -> ContractResult<()> {
-    // Check that only the proxy admin is authorized to update the implementation
-    // address.
-    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<PlayerData> {
     // Parse the parameter.
-    let params: SetImplementationAddressParams = ctx.parameter_cursor().get()?;
-    // Update implementation.
-    host.state_mut().implementation_address = params.implementation_address;
-
-    let state_address = host.state().state_address;
+    let param: GamePlayerParams = ctx.parameter_cursor().get()?;
 
-    // Update implementation address in the state contract.
-    host.invoke_contract(
-        &state_address,
-        &SetImplementationAddressParams {
-            implementation_address: params.implementation_address,
-        },
-        EntrypointName::new_unchecked("setImplementationAddress"),
+    let player_data = host.invoke_contract_read_only(
+        &host.state().implementation_address,
+        &param,
+        EntrypointName::new_unchecked("getPlayerFull"),
         Amount::zero(),
     )?;
 
-    // Log a new implementation event.
-    logger.log(&VersusEvent::NewImplementation(NewImplementationEvent {
-        new_implementation: params.implementation_address,
-    }))?;
+    let player_data = player_data.ok_or(CustomContractError::InvokeContractError)?.get()?;
 
-    Ok(())
+    Ok(player_data)
 }
 
-// #[concordium_cfg_test]
-// mod tests {
-//     use super::*;
-//     use test_infrastructure::*;
+/// Function to view the state of the implementation contract with a typed
+/// return value.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "viewImplementation",
+    return_value = "StateImplementation",
+    error = "CustomContractError"
+)]
+fn contract_proxy_view_implementation<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<StateImplementation> {
+    let state = host.invoke_contract_read_only(
+        &host.state().implementation_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("view"),
+        Amount::zero(),
+    )?;
 
-//     type ContractResult<A> = Result<A, Error>;
+    let state = state.ok_or(CustomContractError::InvokeContractError)?.get()?;
 
-//     #[concordium_test]
-//     /// Test that initializing the contract succeeds with some state.
-//     fn test_init() {
-//         let ctx = TestInitContext::empty();
+    Ok(state)
+}
 
-//         let mut state_builder = TestStateBuilder::new();
+/// Function to view the states of the proxy, implementation and state
+/// contracts at once.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "getFullState",
+    return_value = "FullState",
+    error = "CustomContractError"
+)]
+fn contract_proxy_get_full_state<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<FullState> {
+    let implementation = host.invoke_contract_read_only(
+        &host.state().implementation_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("view"),
+        Amount::zero(),
+    )?;
+    let implementation = implementation.ok_or(CustomContractError::InvokeContractError)?.get()?;
 
-//         let state_result = init(&ctx, &mut state_builder);
-//         state_result.expect_report("Contract initialization results in error");
-//     }
+    let state = host.invoke_contract_read_only(
+        &host.state().state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("view"),
+        Amount::zero(),
+    )?;
+    let state = state.ok_or(CustomContractError::InvokeContractError)?.get()?;
 
-//     #[concordium_test]
-//     /// Test that invoking the `receive` endpoint with the `false` parameter
-//     /// succeeds in updating the contract.
-//     fn test_throw_no_error() {
-//         let ctx = TestInitContext::empty();
+    Ok(FullState {
+        proxy: host.state().clone(),
+        implementation,
+        state,
+    })
+}
 
-//         let mut state_builder = TestStateBuilder::new();
+/// Function to summarize the operational status of the proxy, implementation
+/// and state contracts, so that monitoring can poll a single entrypoint.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "getHealth",
+    return_value = "HealthReport",
+    error = "CustomContractError"
+)]
+fn contract_proxy_get_health<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<HealthReport> {
+    health_report(host)
+}
 
-//         // Initializing state
-//         let initial_state = init(&ctx, &mut state_builder).expect("Initialization should pass");
+/// Function to get the single pause state of the protocol, resolved from the
+/// pause flags of the proxy, implementation and state contracts with the
+/// precedence ShutDown > Maintenance > Paused > Operational.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "getEffectivePauseState",
+    return_value = "EffectivePauseState",
+    error = "CustomContractError"
+)]
+fn contract_proxy_get_effective_pause_state<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<EffectivePauseState> {
+    Ok(health_report(host)?.effective_pause_state())
+}
 
-//         let mut ctx = TestReceiveContext::empty();
+/// Helper function to read the status of the implementation and state
+/// contracts into a health report.
+fn health_report<S: HasStateApi>(
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<HealthReport> {
+    let implementation = host.invoke_contract_read_only(
+        &host.state().implementation_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("view"),
+        Amount::zero(),
+    )?;
+    let implementation: StateImplementation =
+        implementation.ok_or(CustomContractError::InvokeContractError)?.get()?;
 
-//         let throw_error = false;
-//         let parameter_bytes = to_bytes(&throw_error);
-//         ctx.set_parameter(&parameter_bytes);
+    let status = host.invoke_contract_read_only(
+        &host.state().state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("getStatus"),
+        Amount::zero(),
+    )?;
+    let status: StateStatus = status.ok_or(CustomContractError::InvokeContractError)?.get()?;
+
+    Ok(HealthReport {
+        admin:                      host.state().admin,
+        implementation_admin:       implementation.admin,
+        proxy_paused:               host.state().paused,
+        implementation_initialized: matches!(
+            implementation.protocol_addresses,
+            ProtocolAddressesImplementation::Initialized { .. }
+        ),
+        maintenance:                implementation.maintenance,
+        state_initialized:          status.initialized,
+        state_paused:               status.paused,
+        shutdown:                   status.shutdown,
+    })
+}
 
-//         let mut host = TestHost::new(initial_state, state_builder);
+/// Function to get the address of the implementation contract together with
+/// the version it reports.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "getImplementationVersion",
+    return_value = "ImplementationVersion",
+    error = "CustomContractError"
+)]
+fn contract_proxy_get_implementation_version<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<ImplementationVersion> {
+    let implementation_address = host.state().implementation_address;
+    let version = host.invoke_contract_read_only(
+        &implementation_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("getVersion"),
+        Amount::zero(),
+    )?;
 
-//         // Call the contract function.
-//         let result: ContractResult<()> = receive(&ctx, &mut host);
+    let version = version.ok_or(CustomContractError::InvokeContractError)?.get()?;
 
-//         // Check the result.
-//         claim!(result.is_ok(), "Results in rejection");
-//     }
+    Ok(ImplementationVersion {
+        implementation_address,
+        version,
+    })
+}
+
+/// Function to get the addresses of all contracts of the protocol at once.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "getAddresses",
+    return_value = "ProtocolAddresses",
+    error = "CustomContractError"
+)]
+fn contract_proxy_get_addresses<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<ProtocolAddresses> {
+    Ok(ProtocolAddresses {
+        proxy:          ctx.self_address(),
+        implementation: host.state().implementation_address,
+        state:          host.state().state_address,
+    })
+}
+
+/// Function to check that the proxy and state addresses stored in the
+/// implementation contract match the ones of this proxy, e.g. after an
+/// upgrade. An uninitialized implementation mismatches on all addresses.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "getProxyImplementationMismatch",
+    return_value = "ProxyImplementationMismatch",
+    error = "CustomContractError"
+)]
+fn contract_proxy_get_proxy_implementation_mismatch<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<ProxyImplementationMismatch> {
+    let protocol_addresses = host.invoke_contract_read_only(
+        &host.state().implementation_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("getProtocolAddresses"),
+        Amount::zero(),
+    )?;
+    let protocol_addresses: ProtocolAddressesImplementation =
+        protocol_addresses.ok_or(CustomContractError::InvokeContractError)?.get()?;
+
+    let mut fields = Vec::new();
+    match protocol_addresses {
+        ProtocolAddressesImplementation::UnInitialized => {
+            fields.push(ProtocolAddressField::Proxy);
+            fields.push(ProtocolAddressField::State);
+        }
+        ProtocolAddressesImplementation::Initialized {
+            proxy_address,
+            state_address,
+        } => {
+            if proxy_address != ctx.self_address() {
+                fields.push(ProtocolAddressField::Proxy);
+            }
+            if state_address != host.state().state_address {
+                fields.push(ProtocolAddressField::State);
+            }
+        }
+    }
+
+    Ok(ProxyImplementationMismatch {
+        mismatch: !fields.is_empty(),
+        fields,
+    })
+}
+
+/// This functions allows the admin of the proxy to transfer the address to a
+/// new admin.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "updateAdmin",
+    parameter = "Address",
+    error = "CustomContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_proxy_update_admin<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only the old admin is authorized to update the admin address.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let new_admin = ctx.parameter_cursor().get()?;
+    // Update admin and record the old admin in the history.
+    let state = host.state_mut();
+    if state.admin_history.len() >= MAX_ADMIN_HISTORY {
+        state.admin_history.remove(0);
+    }
+    state.admin_history.push(state.admin);
+    state.admin = new_admin;
+
+    // Log a new admin event.
+    logger.log(&VersusEvent::NewAdmin(NewAdminEvent {
+        new_admin,
+    }))?;
+
+    Ok(())
+}
+
+/// Function to check whether the sender is the admin of the proxy.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "amIAdmin",
+    return_value = "bool",
+    error = "CustomContractError"
+)]
+fn contract_proxy_am_i_admin<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<bool> {
+    Ok(ctx.sender() == host.state().admin)
+}
+
+/// Function to get the past admins of the proxy, oldest first.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "getAdminHistory",
+    parameter = "PaginationParams",
+    return_value = "Vec<Address>",
+    error = "CustomContractError"
+)]
+fn contract_proxy_get_admin_history<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<Vec<Address>> {
+    // Parse the parameter.
+    let params: PaginationParams = ctx.parameter_cursor().get()?;
+
+    let admins = host
+        .state()
+        .admin_history
+        .iter()
+        .skip(params.offset as usize)
+        .take(params.limit.min(MAX_PAGE_SIZE) as usize)
+        .copied()
+        .collect();
+
+    Ok(admins)
+}
+
+/// Function to get the reject code and the name of every error of the proxy
+/// contract, so that clients can present readable errors.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "getErrorCodes",
+    return_value = "Vec<(i32, String)>",
+    error = "CustomContractError"
+)]
+fn contract_proxy_get_error_codes<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<Vec<(i32, String)>> {
+    let error_codes = ERROR_VARIANTS
+        .iter()
+        .map(|error| (Reject::from(*error).error_code.get(), error_name(*error).into()))
+        .collect();
+
+    Ok(error_codes)
+}
+
+/// Function to restrict which senders can invoke an implementation entrypoint
+/// through the fallback. An empty set of senders makes the entrypoint public
+/// again. Only the admin on the proxy can call this function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "setEntrypointAccess",
+    parameter = "SetEntrypointAccessParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_proxy_set_entrypoint_access<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the proxy admin is authorized to restrict entrypoints.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let params: SetEntrypointAccessParams = ctx.parameter_cursor().get()?;
+
+    let access_control = &mut host.state_mut().access_control;
+    if params.allowed_senders.is_empty() {
+        access_control.remove(&params.entrypoint);
+    } else {
+        access_control.insert(params.entrypoint, params.allowed_senders);
+    }
+
+    Ok(())
+}
+
+/// Function to set the name of the deployment. The name can be at most
+/// `MAX_NAME_LENGTH` bytes long. Only the admin on the proxy can call this
+/// function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "setName",
+    parameter = "String",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_proxy_set_name<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the proxy admin is authorized to set the name.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let name: String = ctx.parameter_cursor().get()?;
+    ensure!(name.len() <= MAX_NAME_LENGTH, CustomContractError::NameTooLong);
+
+    host.state_mut().name = name;
+    Ok(())
+}
+
+/// Function to set or clear the URL of the metadata off-chain clients fetch
+/// branding and configuration from, together with the optional hash of the
+/// document. The URL can be at most `MAX_METADATA_URL_LENGTH` bytes long and
+/// a hash requires a URL. A change is logged with a `MetadataUpdated` event.
+/// Only the admin on the proxy can call this function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "setMetadataUrl",
+    parameter = "Metadata",
+    error = "CustomContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_proxy_set_metadata_url<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only the proxy admin is authorized to set the metadata URL.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let metadata: Metadata = ctx.parameter_cursor().get()?;
+    ensure!(
+        metadata.metadata_url.as_ref().is_none_or(|url| url.len() <= MAX_METADATA_URL_LENGTH),
+        CustomContractError::MetadataUrlTooLong
+    );
+    ensure!(
+        metadata.metadata_url.is_some() || metadata.metadata_hash.is_none(),
+        CustomContractError::MetadataHashWithoutUrl
+    );
 
-//     #[concordium_test]
-//     /// Test that invoking the `receive` endpoint with the `true` parameter
-//     /// results in the `YourError` being thrown.
-//     fn test_throw_error() {
-//         let ctx = TestInitContext::empty();
+    let state = host.state_mut();
+    if state.metadata_url != metadata.metadata_url
+        || state.metadata_hash != metadata.metadata_hash
+    {
+        state.metadata_url = metadata.metadata_url.clone();
+        state.metadata_hash = metadata.metadata_hash;
+        logger.log(&VersusEvent::MetadataUpdated(metadata))?;
+    }
+    Ok(())
+}
 
-//         let mut state_builder = TestStateBuilder::new();
+/// Function to set the maximum size in bytes of a parameter forwarded by the
+/// fallback. Only the admin on the proxy can call this function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "setMaxParameterSize",
+    parameter = "u32",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_proxy_set_max_parameter_size<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the proxy admin is authorized to set the limit.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let max_parameter_size: u32 = ctx.parameter_cursor().get()?;
 
-//         // Initializing state
-//         let initial_state = init(&ctx, &mut state_builder).expect("Initialization should pass");
+    host.state_mut().max_parameter_size = max_parameter_size;
+    Ok(())
+}
 
-//         let mut ctx = TestReceiveContext::empty();
+/// Function to set the maximum number of entries in a batch, which applies to
+/// all batch entrypoints. Only the admin on the proxy can call this function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "setMaxBatchSize",
+    parameter = "u32",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_proxy_set_max_batch_size<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the proxy admin is authorized to set the limit.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let max_batch_size: u32 = ctx.parameter_cursor().get()?;
 
-//         let throw_error = true;
-//         let parameter_bytes = to_bytes(&throw_error);
-//         ctx.set_parameter(&parameter_bytes);
+    host.state_mut().max_batch_size = max_batch_size;
+    Ok(())
+}
 
-//         let mut host = TestHost::new(initial_state, state_builder);
+/// Function to enable or disable the forward events logged by the fallback.
+/// Only the admin on the proxy can call this function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "setForwardEvents",
+    parameter = "bool",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_proxy_set_forward_events<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the proxy admin is authorized to toggle the events.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let forward_events: bool = ctx.parameter_cursor().get()?;
 
-//         // Call the contract function.
-//         let error: ContractResult<()> = receive(&ctx, &mut host);
+    host.state_mut().forward_events = forward_events;
+    Ok(())
+}
 
-//         // Check the result.
-//         claim_eq!(error, Err(Error::YourError), "Function should throw an error.");
-//     }
-// }
+/// Function to set the seconds a scheduled implementation update waits before
+/// it can be executed, at least `MIN_UPGRADE_DELAY_SECONDS`. Only updates
+/// scheduled afterwards use the new delay. A longer delay applies at once and
+/// replaces a scheduled decrease. A shorter delay is scheduled and can be
+/// applied with `executeUpgradeDelayUpdate` once the current delay has passed.
+/// Only the admin on the proxy can call this function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "setUpgradeDelay",
+    parameter = "u64",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_proxy_set_upgrade_delay<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the proxy admin is authorized to set the delay.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let upgrade_delay_seconds: u64 = ctx.parameter_cursor().get()?;
+    ensure!(
+        upgrade_delay_seconds >= MIN_UPGRADE_DELAY_SECONDS,
+        CustomContractError::UpgradeDelayTooShort
+    );
+
+    let state = host.state_mut();
+    if upgrade_delay_seconds >= state.upgrade_delay_seconds {
+        state.upgrade_delay_seconds = upgrade_delay_seconds;
+        state.scheduled_upgrade_delay = None;
+    } else {
+        let delay = Duration::from_seconds(state.upgrade_delay_seconds);
+        let ready_at = ctx
+            .metadata()
+            .slot_time()
+            .checked_add(delay)
+            .unwrap_or_else(|| Timestamp::from_timestamp_millis(u64::MAX));
+        state.scheduled_upgrade_delay = Some(ScheduledUpgradeDelay {
+            upgrade_delay_seconds,
+            ready_at,
+        });
+    }
+    Ok(())
+}
+
+/// Function to apply the scheduled decrease of the upgrade delay once the
+/// delay it was scheduled with has passed. Anyone can call this function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "executeUpgradeDelayUpdate",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_proxy_execute_upgrade_delay_update<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    let scheduled_upgrade_delay =
+        host.state().scheduled_upgrade_delay.ok_or(CustomContractError::NoUpgradeScheduled)?;
+    ensure!(
+        ctx.metadata().slot_time() >= scheduled_upgrade_delay.ready_at,
+        CustomContractError::UpgradeNotReady
+    );
+
+    let state = host.state_mut();
+    state.upgrade_delay_seconds = scheduled_upgrade_delay.upgrade_delay_seconds;
+    state.scheduled_upgrade_delay = None;
+    Ok(())
+}
+
+/// Function to get the implementation update waiting for its delay to pass,
+/// `None` if no update is scheduled.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "getScheduledUpgrade",
+    return_value = "Option<ScheduledUpgrade>",
+    error = "CustomContractError"
+)]
+fn contract_proxy_get_scheduled_upgrade<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<Option<ScheduledUpgrade>> {
+    Ok(host.state().scheduled_upgrade)
+}
+
+/// Function to get the name of the deployment.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "getName",
+    return_value = "String",
+    error = "CustomContractError"
+)]
+fn contract_proxy_get_name<'a, S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &'a impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<&'a String> {
+    Ok(&host.state().name)
+}
+
+/// Function to get the URL of the metadata of the deployment together with the
+/// hash of the document.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "getMetadataUrl",
+    return_value = "Metadata",
+    error = "CustomContractError"
+)]
+fn contract_proxy_get_metadata_url<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<Metadata> {
+    Ok(Metadata {
+        metadata_url:  host.state().metadata_url.clone(),
+        metadata_hash: host.state().metadata_hash,
+    })
+}
+
+/// Helper function to set the paused flag of the proxy and the state contract
+/// together.
+fn set_paused_all<S>(
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+    paused: bool,
+) -> ContractResult<()> {
+    host.state_mut().paused = paused;
+
+    let state_address = host.state().state_address;
+    host.invoke_contract(
+        &state_address,
+        &SetPausedParams {
+            paused,
+        },
+        EntrypointName::new_unchecked("setPaused"),
+        Amount::zero(),
+    )?;
+
+    Ok(())
+}
+
+/// Function to pause the proxy and the state contract together. Only the
+/// admin on the proxy can call this function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "pauseAll",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_proxy_pause_all<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the proxy admin is authorized to pause.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    set_paused_all(host, true)
+}
+
+/// Function to unpause the proxy and the state contract together. Only the
+/// admin on the proxy can call this function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "unpauseAll",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_proxy_unpause_all<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the proxy admin is authorized to unpause.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    set_paused_all(host, false)
+}
+
+/// Helper function to ensure that an implementation was approved and that the
+/// upgrade delay of its approval has passed.
+fn ensure_approved(
+    state: &StateProxy,
+    implementation_address: ContractAddress,
+    now: Timestamp,
+) -> ContractResult<()> {
+    let usable_at = state
+        .approved_implementations
+        .get(&implementation_address)
+        .ok_or(CustomContractError::ImplementationNotApproved)?;
+    ensure!(now >= *usable_at, CustomContractError::ApprovalNotReady);
+    Ok(())
+}
+
+/// Function to approve or revoke an implementation contract that updates can
+/// be scheduled to. An approval becomes usable once the upgrade delay has
+/// passed, approving an already approved implementation keeps its time.
+/// Revoking applies at once and does not affect an already scheduled update,
+/// which can be cancelled with `cancelScheduledUpgrade`. Only the admin on the
+/// proxy can call this function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "approveImplementation",
+    parameter = "ApproveImplementationParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_proxy_approve_implementation<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the proxy admin is authorized to approve implementations.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let params: ApproveImplementationParams = ctx.parameter_cursor().get()?;
+
+    let delay = Duration::from_seconds(host.state().upgrade_delay_seconds);
+    let usable_at = ctx
+        .metadata()
+        .slot_time()
+        .checked_add(delay)
+        .unwrap_or_else(|| Timestamp::from_timestamp_millis(u64::MAX));
+    let approved_implementations = &mut host.state_mut().approved_implementations;
+    if params.approved {
+        approved_implementations.entry(params.implementation_address).or_insert(usable_at);
+    } else {
+        approved_implementations.remove(&params.implementation_address);
+    }
+    Ok(())
+}
+
+/// Function to schedule an update of the protocol to a new implementation.
+/// The update can be executed with `executeImplementationUpdate` once the
+/// upgrade delay has passed, which gives users time to exit before the
+/// upgrade. Scheduling again replaces the scheduled update. The new
+/// implementation has to be approved with `approveImplementation` first. Only
+/// the admin on the proxy can call this function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "updateImplementation",
+    parameter = "SetImplementationAddressParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_proxy_update_implementation<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the proxy admin is authorized to update the implementation
+    // address.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let params: SetImplementationAddressParams = ctx.parameter_cursor().get()?;
+    ensure_approved(host.state(), params.implementation_address, ctx.metadata().slot_time())?;
+
+    let delay = Duration::from_seconds(host.state().upgrade_delay_seconds);
+    let ready_at = ctx
+        .metadata()
+        .slot_time()
+        .checked_add(delay)
+        .unwrap_or_else(|| Timestamp::from_timestamp_millis(u64::MAX));
+    host.state_mut().scheduled_upgrade = Some(ScheduledUpgrade {
+        implementation_address: params.implementation_address,
+        state_address: None,
+        ready_at,
+    });
+
+    Ok(())
+}
+
+/// Function to execute the scheduled update of the protocol to a new
+/// implementation once its delay has passed. An update scheduled by
+/// `swapBackend` switches the state contract too, after checking again that
+/// the new pair references each other and that the current implementation
+/// holds no funds. Anyone can call this function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "executeImplementationUpdate",
+    error = "CustomContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_proxy_execute_implementation_update<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let scheduled_upgrade =
+        host.state().scheduled_upgrade.ok_or(CustomContractError::NoUpgradeScheduled)?;
+    ensure!(
+        ctx.metadata().slot_time() >= scheduled_upgrade.ready_at,
+        CustomContractError::UpgradeNotReady
+    );
+    let implementation_address = scheduled_upgrade.implementation_address;
+
+    if let Some(state_address) = scheduled_upgrade.state_address {
+        ensure_backend_pair(host, ctx.self_address(), implementation_address, state_address)?;
+        ensure_no_funds_held(host)?;
+
+        // Swap implementation and state, which already reference each other.
+        let proxy_state = host.state_mut();
+        proxy_state.scheduled_upgrade = None;
+        proxy_state.implementation_address = implementation_address;
+        proxy_state.state_address = state_address;
+    } else {
+        // Update implementation.
+        host.state_mut().scheduled_upgrade = None;
+        host.state_mut().implementation_address = implementation_address;
+
+        let state_address = host.state().state_address;
+
+        // Update implementation address in the state contract.
+        host.invoke_contract(
+            &state_address,
+            &SetImplementationAddressParams {
+                implementation_address,
+            },
+            EntrypointName::new_unchecked("setImplementationAddress"),
+            Amount::zero(),
+        )?;
+    }
+
+    // Log a new implementation event.
+    logger.log(&VersusEvent::NewImplementation(NewImplementationEvent {
+        new_implementation: implementation_address,
+    }))?;
+
+    Ok(())
+}
+
+/// Function to cancel the scheduled implementation update before it is
+/// executed. Only the admin on the proxy can call this function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "cancelScheduledUpgrade",
+    error = "CustomContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_proxy_cancel_scheduled_upgrade<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // Check that only the proxy admin is authorized to cancel the update.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+
+    let scheduled_upgrade =
+        host.state_mut().scheduled_upgrade.take().ok_or(CustomContractError::NoUpgradeScheduled)?;
+
+    // Log an upgrade cancelled event.
+    logger.log(&VersusEvent::UpgradeCancelled(scheduled_upgrade))?;
+
+    Ok(())
+}
+
+/// Helper function to ensure that a new implementation references this proxy
+/// and the new state, and that the new state references this proxy and the
+/// new implementation.
+fn ensure_backend_pair<S: HasStateApi>(
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+    proxy_address: ContractAddress,
+    implementation_address: ContractAddress,
+    state_address: ContractAddress,
+) -> ContractResult<()> {
+    let protocol_addresses = host.invoke_contract_read_only(
+        &implementation_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("getProtocolAddresses"),
+        Amount::zero(),
+    )?;
+    let protocol_addresses: ProtocolAddressesImplementation =
+        protocol_addresses.ok_or(CustomContractError::InvokeContractError)?.get()?;
+    ensure_eq!(
+        protocol_addresses,
+        ProtocolAddressesImplementation::Initialized {
+            proxy_address,
+            state_address,
+        },
+        CustomContractError::BackendMismatch
+    );
+
+    let state = host.invoke_contract_read_only(
+        &state_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("view"),
+        Amount::zero(),
+    )?;
+    let state: ReturnBasicState = state.ok_or(CustomContractError::InvokeContractError)?.get()?;
+    ensure!(
+        state.proxy_address == proxy_address
+            && state.implementation_address == implementation_address,
+        CustomContractError::BackendMismatch
+    );
+    Ok(())
+}
+
+/// Helper function to ensure that the current implementation holds no funds:
+/// no reward or tournament pool, no queue deposit and no stakes on pending
+/// matches of the current state. Swapping the backend would strand them.
+fn ensure_no_funds_held<S: HasStateApi>(
+    host: &impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    let implementation = host.invoke_contract_read_only(
+        &host.state().implementation_address,
+        &Parameter(&[]),
+        EntrypointName::new_unchecked("view"),
+        Amount::zero(),
+    )?;
+    let implementation: StateImplementation =
+        implementation.ok_or(CustomContractError::InvokeContractError)?.get()?;
+    ensure!(
+        implementation.reward_pool == Amount::zero()
+            && implementation.tournament_pool == Amount::zero()
+            && implementation.queue_deposits.is_empty(),
+        CustomContractError::FundsHeld
+    );
+
+    let pending_matches = host.invoke_contract_read_only(
+        &host.state().state_address,
+        &PaginationParams {
+            offset: 0,
+            limit:  1,
+        },
+        EntrypointName::new_unchecked("getPendingMatches"),
+        Amount::zero(),
+    )?;
+    // Only the number of returned matches is read, which precedes them.
+    let pending_matches: u32 =
+        pending_matches.ok_or(CustomContractError::InvokeContractError)?.get()?;
+    ensure_eq!(pending_matches, 0, CustomContractError::FundsHeld);
+    Ok(())
+}
+
+/// Function to schedule a switch of the protocol to a new pair of
+/// implementation and state contracts at once, for a blue/green deployment.
+/// The new pair has to be deployed and initialized with this proxy
+/// beforehand: the implementation has to reference this proxy and the new
+/// state, and the state has to reference this proxy and the new
+/// implementation. This is checked with read-only invokes, and the swap
+/// rejects with `BackendMismatch` otherwise. The swap is executed with
+/// `executeImplementationUpdate` once the upgrade delay has passed and only
+/// while the current implementation holds no funds. The new implementation
+/// has to be approved with `approveImplementation`, and no implementation
+/// update may be scheduled. Only the admin on the proxy can call this
+/// function.
+#[receive(
+    contract = "Versus-Proxy",
+    name = "swapBackend",
+    parameter = "SwapBackendParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_proxy_swap_backend<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<StateProxy, StateApiType = S>,
+) -> ContractResult<()> {
+    // Check that only the proxy admin is authorized to swap the backend.
+    ensure_eq!(ctx.sender(), host.state().admin, CustomContractError::OnlyAdmin);
+    // Parse the parameter.
+    let params: SwapBackendParams = ctx.parameter_cursor().get()?;
+    ensure_approved(host.state(), params.implementation_address, ctx.metadata().slot_time())?;
+    ensure!(host.state().scheduled_upgrade.is_none(), CustomContractError::UpgradeScheduled);
+    ensure_backend_pair(
+        host,
+        ctx.self_address(),
+        params.implementation_address,
+        params.state_address,
+    )?;
+
+    let delay = Duration::from_seconds(host.state().upgrade_delay_seconds);
+    let ready_at = ctx
+        .metadata()
+        .slot_time()
+        .checked_add(delay)
+        .unwrap_or_else(|| Timestamp::from_timestamp_millis(u64::MAX));
+    host.state_mut().scheduled_upgrade = Some(ScheduledUpgrade {
+        implementation_address: params.implementation_address,
+        state_address: Some(params.state_address),
+        ready_at,
+    });
+
+    Ok(())
+}
+
+#[concordium_cfg_test]
+mod tests {
+    use super::*;
+    use std::{cell::Cell, rc::Rc};
+    use test_infrastructure::*;
+
+    const ADMIN_ACCOUNT: AccountAddress = AccountAddress([0u8; 32]);
+    const ADMIN_ADDRESS: Address = Address::Account(ADMIN_ACCOUNT);
+    const IMPLEMENTATION: ContractAddress = ContractAddress {
+        index:    2,
+        subindex: 0,
+    };
+    const STATE: ContractAddress = ContractAddress {
+        index:    3,
+        subindex: 0,
+    };
+    const USER: Address = Address::Account(AccountAddress([1u8; 32]));
+
+    /// Creates a host with a proxy contract pointing at `IMPLEMENTATION` and
+    /// `STATE`.
+    fn initialized_host() -> TestHost<StateProxy> {
+        let state = StateProxy {
+            admin:                    ADMIN_ADDRESS,
+            implementation_address:   IMPLEMENTATION,
+            state_address:            STATE,
+            access_control:           BTreeMap::new(),
+            admin_history:            Vec::new(),
+            name:                     "Versus".into(),
+            paused:                   false,
+            max_parameter_size:       DEFAULT_MAX_PARAMETER_SIZE,
+            max_batch_size:           DEFAULT_MAX_BATCH_SIZE,
+            upgrade_delay_seconds:    DEFAULT_UPGRADE_DELAY_SECONDS,
+            scheduled_upgrade:        None,
+            scheduled_upgrade_delay:  None,
+            approved_implementations: BTreeMap::new(),
+            forward_events:           false,
+            metadata_url:             None,
+            metadata_hash:            None,
+        };
+        let mut host = TestHost::new(state, TestStateBuilder::new());
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getPaused".into()),
+            MockFn::returning_ok(false),
+        );
+        host
+    }
+
+    /// Creates the state of an implementation contract initialized with this
+    /// proxy and `STATE`, as returned by its `view` function.
+    fn implementation_state() -> StateImplementation {
+        StateImplementation {
+            admin:                  ADMIN_ADDRESS,
+            protocol_addresses:     ProtocolAddressesImplementation::Initialized {
+                proxy_address: ContractAddress {
+                    index:    1,
+                    subindex: 0,
+                },
+                state_address: STATE,
+            },
+            registration_fee:       Amount::from_ccd(1),
+            fee_recipient:          ADMIN_ACCOUNT,
+            recovery_mode:          false,
+            queue_deposits:         BTreeMap::new(),
+            events_enabled:         true,
+            min_stake:              Amount::zero(),
+            result_hook:            None,
+            result_hook_entrypoint: String::new(),
+            reward_per_win:         Amount::zero(),
+            reward_pool:            Amount::zero(),
+            maintenance:            false,
+            tournament_pool:        Amount::zero(),
+            paused_cache_seconds:   0,
+            paused_cache:           None,
+        }
+    }
+
+    /// Invokes the fallback with the `entrypoint` and an empty parameter.
+    fn forward(
+        host: &mut TestHost<StateProxy>,
+        sender: Address,
+        entrypoint: &str,
+        amount: Amount,
+    ) -> ReceiveResult<RawReturnValue> {
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(sender);
+        ctx.set_named_entrypoint(OwnedEntrypointName::new_unchecked(entrypoint.into()));
+        ctx.set_parameter(&[]);
+        receive_fallback(&ctx, host, amount, &mut TestLogger::init())
+    }
+
+    #[concordium_test]
+    /// Test that the balance held by the proxy is reported.
+    fn test_get_balance() {
+        let mut host = initialized_host();
+        host.set_self_balance(Amount::from_ccd(42));
+
+        let ctx = TestReceiveContext::empty();
+        let balance = contract_proxy_get_balance(&ctx, &host);
+        claim_eq!(balance, Ok(Amount::from_ccd(42)));
+    }
+
+    #[concordium_test]
+    /// Test that a restricted entrypoint is only forwarded for allowed senders.
+    fn test_entrypoint_access() {
+        let mut host = initialized_host();
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("updateBattleResult".into()),
+            MockFn::returning_ok(()),
+        );
+
+        let parameter_bytes = to_bytes(&SetEntrypointAccessParams {
+            entrypoint:      "updateBattleResult".into(),
+            allowed_senders: BTreeSet::from([ADMIN_ADDRESS]),
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_proxy_set_entrypoint_access(&ctx, &mut host);
+        claim!(result.is_ok(), "Admin should be able to restrict an entrypoint");
+
+        let result = forward(&mut host, ADMIN_ADDRESS, "updateBattleResult", Amount::zero());
+        claim!(result.is_ok(), "Allowed sender should be forwarded");
+
+        let result = forward(&mut host, USER, "updateBattleResult", Amount::zero());
+        claim_eq!(result, Err(CustomContractError::SenderNotAllowed.into()));
+    }
+
+    #[concordium_test]
+    /// Test that CCD sent through the fallback is only forwarded to payable
+    /// entrypoints and rejected by the proxy otherwise.
+    fn test_fallback_unexpected_amount() {
+        let mut host = initialized_host();
+        let forwarded = Rc::new(Cell::new(0u32));
+        let counter = forwarded.clone();
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("updatePlayerState".into()),
+            MockFn::new_v1(move |_parameter, _amount, _balance, _state| {
+                counter.set(counter.get() + 1);
+                Ok((false, ()))
+            }),
+        );
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("joinQueue".into()),
+            MockFn::returning_ok(()),
+        );
+        host.set_self_balance(Amount::from_ccd(1));
+
+        let result = forward(&mut host, USER, "updatePlayerState", Amount::from_ccd(1));
+        claim_eq!(result, Err(CustomContractError::UnexpectedAmount.into()));
+        claim_eq!(forwarded.get(), 0);
+
+        let result = forward(&mut host, USER, "updatePlayerState", Amount::zero());
+        claim!(result.is_ok(), "A call without CCD should be forwarded");
+        claim_eq!(forwarded.get(), 1);
+
+        let result = forward(&mut host, USER, "joinQueue", Amount::from_ccd(1));
+        claim!(result.is_ok(), "CCD should be forwarded to payable entrypoints");
+    }
+
+    #[concordium_test]
+    /// Test that a multicall forwards every call in order and is rejected as a
+    /// whole if one call is rejected.
+    fn test_multicall() {
+        let mut host = initialized_host();
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("updatePlayerState".into()),
+            MockFn::returning_ok(1u8),
+        );
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("updateBattleResult".into()),
+            MockFn::returning_ok(2u8),
+        );
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("addPlayer".into()),
+            MockFn::returning_err::<()>(CallContractError::Trap),
+        );
+
+        let multicall = |host: &mut TestHost<StateProxy>, entrypoints: &[&str]| {
+            let calls: Vec<MulticallCall> = entrypoints
+                .iter()
+                .map(|entrypoint| MulticallCall {
+                    entrypoint: entrypoint.to_string(),
+                    parameter:  Vec::new(),
+                })
+                .collect();
+            let parameter_bytes = to_bytes(&calls);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(USER);
+            ctx.set_parameter(&parameter_bytes);
+            contract_proxy_multicall(&ctx, host, &mut TestLogger::init())
+        };
+
+        let result = multicall(&mut host, &["updatePlayerState", "updateBattleResult"]);
+        claim_eq!(result, Ok(vec![vec![1u8], vec![2u8]]));
+
+        let result = multicall(&mut host, &["updatePlayerState", "addPlayer"]);
+        claim!(result.is_err(), "A rejected call should reject the multicall");
+
+        let result =
+            multicall(&mut host, &["updatePlayerState"; DEFAULT_MAX_BATCH_SIZE as usize + 1]);
+        claim_eq!(result, Err(CustomContractError::BatchTooLarge.into()));
+    }
+
+    #[concordium_test]
+    /// Test that batch entrypoints respect the maximum batch size set by the
+    /// admin.
+    fn test_max_batch_size() {
+        let mut host = initialized_host();
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("updatePlayerState".into()),
+            MockFn::returning_ok(()),
+        );
+
+        let parameter_bytes = to_bytes(&2u32);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(USER);
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_proxy_set_max_batch_size(&ctx, &mut host);
+        claim_eq!(result, Err(CustomContractError::OnlyAdmin));
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        let result = contract_proxy_set_max_batch_size(&ctx, &mut host);
+        claim!(result.is_ok(), "Admin should be able to set the maximum batch size");
+
+        let multicall = |host: &mut TestHost<StateProxy>, size: usize| {
+            let calls: Vec<MulticallCall> = (0..size)
+                .map(|_| MulticallCall {
+                    entrypoint: "updatePlayerState".into(),
+                    parameter:  Vec::new(),
+                })
+                .collect();
+            let parameter_bytes = to_bytes(&calls);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(USER);
+            ctx.set_parameter(&parameter_bytes);
+            contract_proxy_multicall(&ctx, host, &mut TestLogger::init())
+        };
+        claim!(multicall(&mut host, 2).is_ok(), "A batch at the limit should pass");
+        claim_eq!(multicall(&mut host, 3), Err(CustomContractError::BatchTooLarge.into()));
+    }
+
+    #[concordium_test]
+    /// Test that admin transfers are recorded in order and the history is
+    /// bounded.
+    fn test_admin_history() {
+        let mut host = initialized_host();
+        let mut logger = TestLogger::init();
+
+        let admins: Vec<Address> = (1..=MAX_ADMIN_HISTORY as u8 + 2)
+            .map(|i| Address::Account(AccountAddress([i; 32])))
+            .collect();
+        let mut current_admin = ADMIN_ADDRESS;
+        for new_admin in admins.iter() {
+            let parameter_bytes = to_bytes(new_admin);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(current_admin);
+            ctx.set_parameter(&parameter_bytes);
+            let result = contract_proxy_update_admin(&ctx, &mut host, &mut logger);
+            claim!(result.is_ok(), "Admin should be able to transfer the admin role");
+            current_admin = *new_admin;
+        }
+
+        let get_admin_history = |offset: u32, limit: u32| {
+            let parameter_bytes = to_bytes(&PaginationParams {
+                offset,
+                limit,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_proxy_get_admin_history(&ctx, &host).expect_report("Query should pass")
+        };
+
+        // The initial admin and the first new admin were dropped from the history.
+        claim_eq!(get_admin_history(0, 2), admins[1..3].to_vec());
+        claim_eq!(get_admin_history(MAX_ADMIN_HISTORY as u32 - 1, 10), vec![
+            admins[MAX_ADMIN_HISTORY]
+        ]);
+        claim_eq!(host.state().admin_history.len(), MAX_ADMIN_HISTORY);
+        claim_eq!(host.state().admin, current_admin);
+    }
+
+    #[concordium_test]
+    /// Test that the typed passthroughs decode the implementation's return
+    /// values.
+    fn test_typed_passthroughs() {
+        let mut host = initialized_host();
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("getPlayerData".into()),
+            MockFn::returning_ok((PlayerState::Active, BattleResult::Win)),
+        );
+        let full_player_data = PlayerData {
+            version:        4,
+            state:          PlayerState::Active,
+            last_result:    BattleResult::Win,
+            wins:           5,
+            losses:         2,
+            elo:            1080,
+            state_history:  StateHistory {
+                entries: [None; 8],
+                next:    0,
+            },
+            current_streak: 2,
+            best_streak:    4,
+            last_updated:   Timestamp::from_timestamp_millis(1_000),
+            registered_at:  Timestamp::from_timestamp_millis(500),
+            rate_window:    RateWindow {
+                submitted: vec![Timestamp::from_timestamp_millis(1_000)],
+            },
+            season:         0,
+        };
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("getPlayerFull".into()),
+            MockFn::returning_ok(full_player_data.clone()),
+        );
+        let implementation_state = implementation_state();
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("view".into()),
+            MockFn::returning_ok(implementation_state.clone()),
+        );
+
+        let parameter_bytes = to_bytes(&GamePlayerParams {
+            game_id: 0,
+            player:  USER,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_parameter(&parameter_bytes);
+        let player_data = contract_proxy_get_player_data(&ctx, &host);
+        claim_eq!(player_data, Ok((PlayerState::Active, BattleResult::Win)));
+        let player_data = contract_proxy_get_player_full(&ctx, &host);
+        claim_eq!(player_data, Ok(full_player_data));
+
+        let ctx = TestReceiveContext::empty();
+        let state = contract_proxy_view_implementation(&ctx, &host);
+        claim_eq!(state, Ok(implementation_state));
+    }
+
+    #[concordium_test]
+    /// Test that the version reported by the implementation is returned
+    /// together with its address.
+    fn test_get_implementation_version() {
+        let mut host = initialized_host();
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("getVersion".into()),
+            MockFn::returning_ok(3u32),
+        );
+
+        let ctx = TestReceiveContext::empty();
+        let version = contract_proxy_get_implementation_version(&ctx, &host);
+        claim_eq!(
+            version,
+            Ok(ImplementationVersion {
+                implementation_address: IMPLEMENTATION,
+                version:                3,
+            })
+        );
+    }
+
+    #[concordium_test]
+    /// Test that every event serializes with its distinct tag, and that no
+    /// tag falls into the range a CIS-2 library on the same contract logs
+    /// its events with.
+    fn test_event_tags() {
+        let events = [
+            VersusEvent::NewAdmin(NewAdminEvent {
+                new_admin: ADMIN_ADDRESS,
+            }),
+            VersusEvent::NewImplementation(NewImplementationEvent {
+                new_implementation: IMPLEMENTATION,
+            }),
+            VersusEvent::UpgradeCancelled(ScheduledUpgrade {
+                implementation_address: IMPLEMENTATION,
+                state_address:          None,
+                ready_at:               Timestamp::from_timestamp_millis(0),
+            }),
+            VersusEvent::Forward(ForwardEvent {
+                entrypoint: OwnedEntrypointName::new_unchecked("joinQueue".into()),
+                sender:     USER,
+                amount:     Amount::zero(),
+            }),
+            VersusEvent::MetadataUpdated(Metadata {
+                metadata_url:  None,
+                metadata_hash: None,
+            }),
+        ];
+
+        let tags: Vec<u8> = events.iter().map(|event| to_bytes(event)[0]).collect();
+        claim_eq!(tags, EVENT_TAGS);
+        claim_eq!(tags.iter().collect::<BTreeSet<_>>().len(), events.len());
+        // The CIS-2 library logs transfer, mint, burn, update operator and token
+        // metadata events with the tags from `u8::MAX` down to `u8::MAX - 4`.
+        claim!(
+            tags.iter().all(|tag| !(u8::MAX - 4..=u8::MAX).contains(tag)),
+            "Event tags should not be reserved by CIS-2"
+        );
+    }
+
+    #[concordium_test]
+    /// Test that `getErrorCodes` covers every error with its reject code.
+    fn test_get_error_codes() {
+        let ctx = TestReceiveContext::empty();
+        let host = initialized_host();
+        let error_codes =
+            contract_proxy_get_error_codes(&ctx, &host).expect_report("Query should pass");
+
+        let expected: Vec<(i32, String)> = [
+            (-1, "ParseParams"),
+            (-2, "LogFull"),
+            (-3, "LogMalformed"),
+            (-4, "InvokeContractError"),
+            (-5, "InvokeTransferError"),
+            (-6, "ContractPaused"),
+            (-7, "AlreadyInitialized"),
+            (-8, "UnInitialized"),
+            (-9, "OnlyImplementation"),
+            (-10, "OnlyProxy"),
+            (-11, "StateInvokeError"),
+            (-12, "OnlyAdmin"),
+            (-13, "UnexpectedAmount"),
+            (-14, "SenderNotAllowed"),
+            (-15, "BatchTooLarge"),
+            (-16, "NameTooLong"),
+            (-17, "ParameterTooLarge"),
+            (-18, "NoUpgradeScheduled"),
+            (-19, "UpgradeNotReady"),
+            (-20, "ImplementationNotApproved"),
+            (-21, "MetadataUrlTooLong"),
+            (-22, "MetadataHashWithoutUrl"),
+            (-23, "BackendMismatch"),
+            (-24, "UpgradeScheduled"),
+            (-25, "FundsHeld"),
+            (-26, "UpgradeDelayTooShort"),
+            (-27, "ApprovalNotReady"),
+        ]
+        .iter()
+        .map(|(code, name)| (*code, String::from(*name)))
+        .collect();
+        claim_eq!(error_codes, expected);
+    }
+
+    #[concordium_test]
+    /// Test that the admin can set the name and that over-long names are
+    /// rejected at init and afterwards.
+    fn test_name() {
+        let mut init_ctx = TestInitContext::empty();
+        init_ctx.set_init_origin(ADMIN_ACCOUNT);
+        let parameter_bytes = to_bytes(&InitProxyParams {
+            implementation_address: IMPLEMENTATION,
+            state_address:          STATE,
+            name:                   "a".repeat(MAX_NAME_LENGTH + 1),
+        });
+        init_ctx.set_parameter(&parameter_bytes);
+        let result = contract_proxy_init(&init_ctx, &mut TestStateBuilder::new());
+        claim_eq!(result.err(), Some(CustomContractError::NameTooLong.into()));
+
+        let mut host = initialized_host();
+        let name = "Versus Arena".to_string();
+        let parameter_bytes = to_bytes(&name);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_proxy_set_name(&ctx, &mut host), Ok(()));
+        claim_eq!(contract_proxy_get_name(&ctx, &host), Ok(&name));
+
+        let parameter_bytes = to_bytes(&"a".repeat(MAX_NAME_LENGTH + 1));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_proxy_set_name(&ctx, &mut host),
+            Err(CustomContractError::NameTooLong)
+        );
+
+        let parameter_bytes = to_bytes(&"Other".to_string());
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_sender(USER);
+        claim_eq!(contract_proxy_set_name(&ctx, &mut host), Err(CustomContractError::OnlyAdmin));
+        claim_eq!(contract_proxy_get_name(&ctx, &host), Ok(&name));
+    }
+
+    #[concordium_test]
+    /// Test that `pauseAll` pauses the proxy and the state contract and that
+    /// `unpauseAll` unpauses both.
+    fn test_pause_all() {
+        let mut host = initialized_host();
+        let state_paused = Rc::new(Cell::new(false));
+        let recorded = state_paused.clone();
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("setPaused".into()),
+            MockFn::new_v1(move |parameter, _amount, _balance, _state| {
+                let params: SetPausedParams = from_bytes(parameter.as_ref()).unwrap();
+                recorded.set(params.paused);
+                Ok((false, ()))
+            }),
+        );
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("updateBattleResult".into()),
+            MockFn::returning_ok(()),
+        );
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(USER);
+        claim_eq!(contract_proxy_pause_all(&ctx, &mut host), Err(CustomContractError::OnlyAdmin));
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        claim_eq!(contract_proxy_pause_all(&ctx, &mut host), Ok(()));
+        claim!(host.state().paused, "Proxy should be paused");
+        claim!(state_paused.get(), "State should be paused");
+        let result = forward(&mut host, USER, "updateBattleResult", Amount::zero());
+        claim_eq!(result, Err(CustomContractError::ContractPaused.into()));
+
+        claim_eq!(contract_proxy_unpause_all(&ctx, &mut host), Ok(()));
+        claim!(!host.state().paused, "Proxy should be unpaused");
+        claim!(!state_paused.get(), "State should be unpaused");
+        let result = forward(&mut host, USER, "updateBattleResult", Amount::zero());
+        claim!(result.is_ok(), "Calls should be forwarded again");
+    }
+
+    #[concordium_test]
+    /// Test that only pause exempt entrypoints are forwarded while the state
+    /// contract or the proxy is paused.
+    fn test_fallback_while_paused() {
+        let mut host = initialized_host();
+        for entrypoint in ["getPlayerData", "updateBattleResult"] {
+            host.setup_mock_entrypoint(
+                IMPLEMENTATION,
+                OwnedEntrypointName::new_unchecked(entrypoint.into()),
+                MockFn::returning_ok(()),
+            );
+        }
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getPaused".into()),
+            MockFn::returning_ok(true),
+        );
+
+        let result = forward(&mut host, USER, "getPlayerData", Amount::zero());
+        claim!(result.is_ok(), "Exempt entrypoints should be forwarded while the state is paused");
+        let result = forward(&mut host, USER, "updateBattleResult", Amount::zero());
+        claim_eq!(result, Err(CustomContractError::ContractPaused.into()));
+
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getPaused".into()),
+            MockFn::returning_ok(false),
+        );
+        host.state_mut().paused = true;
+
+        let result = forward(&mut host, USER, "getPlayerData", Amount::zero());
+        claim!(result.is_ok(), "Exempt entrypoints should be forwarded while the proxy is paused");
+        let result = forward(&mut host, USER, "updateBattleResult", Amount::zero());
+        claim_eq!(result, Err(CustomContractError::ContractPaused.into()));
+    }
+
+    #[concordium_test]
+    /// Test that the addresses of all three contracts are returned.
+    fn test_get_addresses() {
+        let host = initialized_host();
+        let proxy = ContractAddress {
+            index:    1,
+            subindex: 0,
+        };
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_self_address(proxy);
+
+        claim_eq!(
+            contract_proxy_get_addresses(&ctx, &host),
+            Ok(ProtocolAddresses {
+                proxy,
+                implementation: IMPLEMENTATION,
+                state:          STATE,
+            })
+        );
+    }
+
+    #[concordium_test]
+    /// Test that only the admin is reported as admin.
+    fn test_am_i_admin() {
+        let host = initialized_host();
+        let mut ctx = TestReceiveContext::empty();
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        claim_eq!(contract_proxy_am_i_admin(&ctx, &host), Ok(true));
+        ctx.set_sender(USER);
+        claim_eq!(contract_proxy_am_i_admin(&ctx, &host), Ok(false));
+    }
+
+    #[concordium_test]
+    /// Test that drifted protocol addresses of the implementation are
+    /// reported.
+    fn test_get_proxy_implementation_mismatch() {
+        let mut host = initialized_host();
+        let proxy = ContractAddress {
+            index:    1,
+            subindex: 0,
+        };
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_self_address(proxy);
+
+        let mut check = |protocol_addresses| {
+            host.setup_mock_entrypoint(
+                IMPLEMENTATION,
+                OwnedEntrypointName::new_unchecked("getProtocolAddresses".into()),
+                MockFn::returning_ok(protocol_addresses),
+            );
+            contract_proxy_get_proxy_implementation_mismatch(&ctx, &host)
+        };
+
+        claim_eq!(
+            check(ProtocolAddressesImplementation::Initialized {
+                proxy_address: proxy,
+                state_address: STATE,
+            }),
+            Ok(ProxyImplementationMismatch {
+                mismatch: false,
+                fields:   Vec::new(),
+            })
+        );
+        claim_eq!(
+            check(ProtocolAddressesImplementation::Initialized {
+                proxy_address: proxy,
+                state_address: IMPLEMENTATION,
+            }),
+            Ok(ProxyImplementationMismatch {
+                mismatch: true,
+                fields:   vec![ProtocolAddressField::State],
+            })
+        );
+        claim_eq!(
+            check(ProtocolAddressesImplementation::UnInitialized),
+            Ok(ProxyImplementationMismatch {
+                mismatch: true,
+                fields:   vec![ProtocolAddressField::Proxy, ProtocolAddressField::State],
+            })
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the fallback and multicall only forward parameters up to the
+    /// maximum parameter size.
+    fn test_max_parameter_size() {
+        let mut host = initialized_host();
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("updateBattleResult".into()),
+            MockFn::returning_ok(()),
+        );
+
+        let parameter_bytes = to_bytes(&8u32);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(USER);
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_proxy_set_max_parameter_size(&ctx, &mut host);
+        claim_eq!(result, Err(CustomContractError::OnlyAdmin));
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        let result = contract_proxy_set_max_parameter_size(&ctx, &mut host);
+        claim!(result.is_ok(), "Admin should be able to set the maximum parameter size");
+
+        let mut forward_parameter = |parameter: &[u8]| {
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(USER);
+            ctx.set_named_entrypoint(OwnedEntrypointName::new_unchecked(
+                "updateBattleResult".into(),
+            ));
+            ctx.set_parameter(parameter);
+            receive_fallback(&ctx, &mut host, Amount::zero(), &mut TestLogger::init())
+        };
+        let result = forward_parameter(&[0; 8]);
+        claim!(result.is_ok(), "A parameter at the limit should be forwarded");
+        let result = forward_parameter(&[0; 9]);
+        claim_eq!(result, Err(CustomContractError::ParameterTooLarge.into()));
+
+        let multicall = |host: &mut TestHost<StateProxy>, parameter: Vec<u8>| {
+            let parameter_bytes = to_bytes(&vec![MulticallCall {
+                entrypoint: "updateBattleResult".into(),
+                parameter,
+            }]);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(USER);
+            ctx.set_parameter(&parameter_bytes);
+            contract_proxy_multicall(&ctx, host, &mut TestLogger::init())
+        };
+        claim!(multicall(&mut host, vec![0; 8]).is_ok(), "A call at the limit should pass");
+        claim_eq!(
+            multicall(&mut host, vec![0; 9]),
+            Err(CustomContractError::ParameterTooLarge.into())
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the full state combines the states of all three contracts.
+    fn test_get_full_state() {
+        let mut host = initialized_host();
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("view".into()),
+            MockFn::returning_ok(implementation_state()),
+        );
+        let basic_state = ReturnBasicState {
+            proxy_address:          ContractAddress {
+                index:    1,
+                subindex: 0,
+            },
+            implementation_address: IMPLEMENTATION,
+            paused:                 true,
+            pause_reason:           Some("Maintenance".into()),
+        };
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("view".into()),
+            MockFn::returning_ok(basic_state.clone()),
+        );
+
+        let ctx = TestReceiveContext::empty();
+        let full_state = contract_proxy_get_full_state(&ctx, &host);
+        claim_eq!(
+            full_state,
+            Ok(FullState {
+                proxy:          host.state().clone(),
+                implementation: implementation_state(),
+                state:          basic_state,
+            })
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the health report of a healthy deployment has no flags set.
+    fn test_get_health() {
+        let mut host = initialized_host();
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("view".into()),
+            MockFn::returning_ok(implementation_state()),
+        );
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getStatus".into()),
+            MockFn::returning_ok(StateStatus {
+                initialized: true,
+                paused:      false,
+                shutdown:    false,
+            }),
+        );
+
+        let ctx = TestReceiveContext::empty();
+        claim_eq!(
+            contract_proxy_get_health(&ctx, &host),
+            Ok(HealthReport {
+                admin:                      ADMIN_ADDRESS,
+                implementation_admin:       ADMIN_ADDRESS,
+                proxy_paused:               false,
+                implementation_initialized: true,
+                maintenance:                false,
+                state_initialized:          true,
+                state_paused:               false,
+                shutdown:                   false,
+            })
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the health report flags a paused and uninitialized
+    /// deployment.
+    fn test_get_health_paused_uninitialized() {
+        let mut host = initialized_host();
+        host.state_mut().paused = true;
+        let mut implementation = implementation_state();
+        implementation.protocol_addresses = ProtocolAddressesImplementation::UnInitialized;
+        implementation.maintenance = true;
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("view".into()),
+            MockFn::returning_ok(implementation),
+        );
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getStatus".into()),
+            MockFn::returning_ok(StateStatus {
+                initialized: false,
+                paused:      true,
+                shutdown:    false,
+            }),
+        );
+
+        let ctx = TestReceiveContext::empty();
+        let report = contract_proxy_get_health(&ctx, &host).expect_report("Health should pass");
+        claim!(report.proxy_paused, "Proxy should be reported paused");
+        claim!(report.state_paused, "State should be reported paused");
+        claim!(report.maintenance, "Maintenance should be reported");
+        claim!(!report.implementation_initialized, "Implementation is not initialized");
+        claim!(!report.state_initialized, "State is not initialized");
+        claim!(!report.shutdown, "State is not shut down");
+    }
+
+    #[concordium_test]
+    /// Test that every combination of pause flags resolves to the pause state
+    /// with the highest precedence.
+    fn test_effective_pause_state() {
+        // (proxy_paused, state_paused, maintenance, shutdown, expected)
+        let cases = [
+            (false, false, false, false, EffectivePauseState::Operational),
+            (true,  false, false, false, EffectivePauseState::Paused),
+            (false, true,  false, false, EffectivePauseState::Paused),
+            (true,  true,  false, false, EffectivePauseState::Paused),
+            (false, false, true,  false, EffectivePauseState::Maintenance),
+            (true,  false, true,  false, EffectivePauseState::Maintenance),
+            (false, true,  true,  false, EffectivePauseState::Maintenance),
+            (true,  true,  true,  false, EffectivePauseState::Maintenance),
+            (false, false, false, true,  EffectivePauseState::ShutDown),
+            (true,  false, false, true,  EffectivePauseState::ShutDown),
+            (false, true,  false, true,  EffectivePauseState::ShutDown),
+            (true,  true,  false, true,  EffectivePauseState::ShutDown),
+            (false, false, true,  true,  EffectivePauseState::ShutDown),
+            (true,  false, true,  true,  EffectivePauseState::ShutDown),
+            (false, true,  true,  true,  EffectivePauseState::ShutDown),
+            (true,  true,  true,  true,  EffectivePauseState::ShutDown),
+        ];
+        for (proxy_paused, state_paused, maintenance, shutdown, expected) in cases {
+            let report = HealthReport {
+                admin: ADMIN_ADDRESS,
+                implementation_admin: ADMIN_ADDRESS,
+                proxy_paused,
+                implementation_initialized: true,
+                maintenance,
+                state_initialized: true,
+                state_paused,
+                shutdown,
+            };
+            claim_eq!(report.effective_pause_state(), expected);
+        }
+
+        let mut host = initialized_host();
+        host.state_mut().paused = true;
+        let mut implementation = implementation_state();
+        implementation.maintenance = true;
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("view".into()),
+            MockFn::returning_ok(implementation),
+        );
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getStatus".into()),
+            MockFn::returning_ok(StateStatus {
+                initialized: true,
+                paused:      true,
+                shutdown:    false,
+            }),
+        );
+
+        let ctx = TestReceiveContext::empty();
+        claim_eq!(
+            contract_proxy_get_effective_pause_state(&ctx, &host),
+            Ok(EffectivePauseState::Maintenance)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that an implementation update is scheduled by the admin and can
+    /// only be executed by anyone after the upgrade delay.
+    fn test_implementation_update_timelock() {
+        let mut host = initialized_host();
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("setImplementationAddress".into()),
+            MockFn::returning_ok(()),
+        );
+        let new_implementation = ContractAddress {
+            index:    10,
+            subindex: 0,
+        };
+        let usable_at = Timestamp::from_timestamp_millis(0);
+        host.state_mut().approved_implementations.insert(new_implementation, usable_at);
+        let scheduled_at = Timestamp::from_timestamp_millis(1_000);
+        let delay = Duration::from_seconds(DEFAULT_UPGRADE_DELAY_SECONDS);
+        let ready_at = scheduled_at.checked_add(delay).expect_report("No overflow");
+        let parameter_bytes = to_bytes(&SetImplementationAddressParams {
+            implementation_address: new_implementation,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(scheduled_at);
+
+        ctx.set_sender(USER);
+        let result = contract_proxy_update_implementation(&ctx, &mut host);
+        claim_eq!(result, Err(CustomContractError::OnlyAdmin));
+
+        let mut logger = TestLogger::init();
+        let result = contract_proxy_execute_implementation_update(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(CustomContractError::NoUpgradeScheduled));
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        let result = contract_proxy_update_implementation(&ctx, &mut host);
+        claim!(result.is_ok(), "Admin should be able to schedule an update");
+        claim_eq!(
+            host.state().scheduled_upgrade,
+            Some(ScheduledUpgrade {
+                implementation_address: new_implementation,
+                state_address: None,
+                ready_at,
+            })
+        );
+        claim_eq!(host.state().implementation_address, IMPLEMENTATION, "Update is not instant");
+
+        ctx.set_sender(USER);
+        let too_early = Timestamp::from_timestamp_millis(ready_at.timestamp_millis() - 1);
+        ctx.set_metadata_slot_time(too_early);
+        let result = contract_proxy_execute_implementation_update(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(CustomContractError::UpgradeNotReady));
+        claim_eq!(host.state().implementation_address, IMPLEMENTATION);
+
+        ctx.set_metadata_slot_time(ready_at);
+        let result = contract_proxy_execute_implementation_update(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Anyone should be able to execute a ready update");
+        claim_eq!(host.state().implementation_address, new_implementation);
+        claim_eq!(host.state().scheduled_upgrade, None);
+        claim_eq!(
+            logger.logs,
+            vec![to_bytes(&VersusEvent::NewImplementation(NewImplementationEvent {
+                new_implementation,
+            }))]
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the admin can cancel a scheduled implementation update, after
+    /// which it can not be executed anymore.
+    fn test_cancel_scheduled_upgrade() {
+        let mut host = initialized_host();
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
+
+        let result = contract_proxy_cancel_scheduled_upgrade(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(CustomContractError::NoUpgradeScheduled));
+
+        let new_implementation = ContractAddress {
+            index:    10,
+            subindex: 0,
+        };
+        let usable_at = Timestamp::from_timestamp_millis(0);
+        host.state_mut().approved_implementations.insert(new_implementation, usable_at);
+        let parameter_bytes = to_bytes(&SetImplementationAddressParams {
+            implementation_address: new_implementation,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_proxy_update_implementation(&ctx, &mut host);
+        claim!(result.is_ok(), "Admin should be able to schedule an update");
+        let scheduled_upgrade = host.state().scheduled_upgrade.expect_report("Update is scheduled");
+
+        ctx.set_sender(USER);
+        let result = contract_proxy_cancel_scheduled_upgrade(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(CustomContractError::OnlyAdmin));
+
+        ctx.set_sender(ADMIN_ADDRESS);
+        let result = contract_proxy_cancel_scheduled_upgrade(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "Admin should be able to cancel the update");
+        claim_eq!(host.state().scheduled_upgrade, None);
+        claim_eq!(logger.logs, vec![to_bytes(&VersusEvent::UpgradeCancelled(scheduled_upgrade))]);
+
+        ctx.set_metadata_slot_time(scheduled_upgrade.ready_at);
+        let result = contract_proxy_execute_implementation_update(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(CustomContractError::NoUpgradeScheduled));
+        claim_eq!(host.state().implementation_address, IMPLEMENTATION);
+    }
+
+    #[concordium_test]
+    /// Test that the scheduled implementation update can be read.
+    fn test_get_scheduled_upgrade() {
+        let mut host = initialized_host();
+        let ctx = TestReceiveContext::empty();
+        claim_eq!(contract_proxy_get_scheduled_upgrade(&ctx, &host), Ok(None));
+
+        let new_implementation = ContractAddress {
+            index:    10,
+            subindex: 0,
+        };
+        let parameter_bytes = to_bytes(&SetImplementationAddressParams {
+            implementation_address: new_implementation,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ADMIN_ADDRESS);
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(5_000));
+        host.state_mut().upgrade_delay_seconds = 60;
+        let usable_at = Timestamp::from_timestamp_millis(0);
+        host.state_mut().approved_implementations.insert(new_implementation, usable_at);
+        let result = contract_proxy_update_implementation(&ctx, &mut host);
+        claim!(result.is_ok(), "Admin should be able to schedule an update");
+
+        claim_eq!(
+            contract_proxy_get_scheduled_upgrade(&ctx, &host),
+            Ok(Some(ScheduledUpgrade {
+                implementation_address: new_implementation,
+                state_address:          None,
+                ready_at:               Timestamp::from_timestamp_millis(65_000),
+            }))
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the upgrade delay has a minimum, that a longer delay applies
+    /// at once and that a shorter delay only applies after the current delay.
+    fn test_set_upgrade_delay() {
+        let mut host = initialized_host();
+        let hour = 60 * 60;
+        let set_upgrade_delay = |host: &mut TestHost<StateProxy>, sender, delay: u64| {
+            let parameter_bytes = to_bytes(&delay);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(sender);
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+            contract_proxy_set_upgrade_delay(&ctx, host)
+        };
+
+        let long_delay = 2 * DEFAULT_UPGRADE_DELAY_SECONDS;
+        claim_eq!(
+            set_upgrade_delay(&mut host, USER, long_delay),
+            Err(CustomContractError::OnlyAdmin)
+        );
+        for delay in [0, MIN_UPGRADE_DELAY_SECONDS - 1] {
+            claim_eq!(
+                set_upgrade_delay(&mut host, ADMIN_ADDRESS, delay),
+                Err(CustomContractError::UpgradeDelayTooShort)
+            );
+        }
+
+        claim_eq!(set_upgrade_delay(&mut host, ADMIN_ADDRESS, long_delay), Ok(()));
+        claim_eq!(host.state().upgrade_delay_seconds, long_delay);
+
+        claim_eq!(set_upgrade_delay(&mut host, ADMIN_ADDRESS, hour), Ok(()));
+        claim_eq!(host.state().upgrade_delay_seconds, long_delay);
+        let ready_at = Timestamp::from_timestamp_millis(long_delay * 1000);
+        claim_eq!(
+            host.state().scheduled_upgrade_delay,
+            Some(ScheduledUpgradeDelay {
+                upgrade_delay_seconds: hour,
+                ready_at,
+            })
+        );
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(USER);
+        let too_early = Timestamp::from_timestamp_millis(ready_at.timestamp_millis() - 1);
+        ctx.set_metadata_slot_time(too_early);
+        claim_eq!(
+            contract_proxy_execute_upgrade_delay_update(&ctx, &mut host),
+            Err(CustomContractError::UpgradeNotReady)
+        );
+        ctx.set_metadata_slot_time(ready_at);
+        claim_eq!(contract_proxy_execute_upgrade_delay_update(&ctx, &mut host), Ok(()));
+        claim_eq!(host.state().upgrade_delay_seconds, hour);
+        claim_eq!(
+            contract_proxy_execute_upgrade_delay_update(&ctx, &mut host),
+            Err(CustomContractError::NoUpgradeScheduled)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the admin can set the metadata URL with a hash and clear it,
+    /// that a hash requires a URL, and that only changes are logged.
+    fn test_metadata_url() {
+        let mut host = initialized_host();
+        let mut logger = TestLogger::init();
+        let set_metadata_url =
+            |host: &mut TestHost<StateProxy>, logger: &mut TestLogger, sender, metadata| {
+                let parameter_bytes = to_bytes(&metadata);
+                let mut ctx = TestReceiveContext::empty();
+                ctx.set_sender(sender);
+                ctx.set_parameter(&parameter_bytes);
+                contract_proxy_set_metadata_url(&ctx, host, logger)
+            };
+        let metadata = Metadata {
+            metadata_url:  Some("https://versus.example/metadata.json".to_string()),
+            metadata_hash: Some([7u8; 32]),
+        };
+        let cleared = Metadata {
+            metadata_url:  None,
+            metadata_hash: None,
+        };
+
+        claim_eq!(
+            set_metadata_url(&mut host, &mut logger, USER, metadata.clone()),
+            Err(CustomContractError::OnlyAdmin)
+        );
+        claim_eq!(
+            set_metadata_url(&mut host, &mut logger, ADMIN_ADDRESS, Metadata {
+                metadata_url:  Some("a".repeat(MAX_METADATA_URL_LENGTH + 1)),
+                metadata_hash: None,
+            }),
+            Err(CustomContractError::MetadataUrlTooLong)
+        );
+        claim_eq!(
+            set_metadata_url(&mut host, &mut logger, ADMIN_ADDRESS, Metadata {
+                metadata_url:  None,
+                metadata_hash: Some([7u8; 32]),
+            }),
+            Err(CustomContractError::MetadataHashWithoutUrl)
+        );
+        claim_eq!(
+            set_metadata_url(&mut host, &mut logger, ADMIN_ADDRESS, metadata.clone()),
+            Ok(())
+        );
+        claim_eq!(
+            set_metadata_url(&mut host, &mut logger, ADMIN_ADDRESS, metadata.clone()),
+            Ok(())
+        );
+        let ctx = TestReceiveContext::empty();
+        claim_eq!(contract_proxy_get_metadata_url(&ctx, &host), Ok(metadata.clone()));
+
+        claim_eq!(
+            set_metadata_url(&mut host, &mut logger, ADMIN_ADDRESS, cleared.clone()),
+            Ok(())
+        );
+        claim_eq!(contract_proxy_get_metadata_url(&ctx, &host), Ok(cleared.clone()));
+        claim_eq!(logger.logs, vec![
+            to_bytes(&VersusEvent::MetadataUpdated(metadata)),
+            to_bytes(&VersusEvent::MetadataUpdated(cleared)),
+        ]);
+    }
+
+    #[concordium_test]
+    /// Test that the fallback logs the forwarded entrypoint only while forward
+    /// events are enabled.
+    fn test_forward_events() {
+        let mut host = initialized_host();
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("joinQueue".into()),
+            MockFn::returning_ok(()),
+        );
+        host.set_self_balance(Amount::from_ccd(2));
+        let entrypoint = OwnedEntrypointName::new_unchecked("joinQueue".into());
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(USER);
+        ctx.set_named_entrypoint(entrypoint.clone());
+        ctx.set_parameter(&[]);
+        let mut logger = TestLogger::init();
+        let result = receive_fallback(&ctx, &mut host, Amount::from_ccd(1), &mut logger);
+        claim!(result.is_ok(), "The call should be forwarded");
+        claim!(logger.logs.is_empty(), "Forward events should be disabled by default");
+
+        let parameter_bytes = to_bytes(&true);
+        let mut admin_ctx = TestReceiveContext::empty();
+        admin_ctx.set_sender(USER);
+        admin_ctx.set_parameter(&parameter_bytes);
+        let result = contract_proxy_set_forward_events(&admin_ctx, &mut host);
+        claim_eq!(result, Err(CustomContractError::OnlyAdmin));
+
+        admin_ctx.set_sender(ADMIN_ADDRESS);
+        let result = contract_proxy_set_forward_events(&admin_ctx, &mut host);
+        claim!(result.is_ok(), "Admin should be able to enable forward events");
+
+        let result = receive_fallback(&ctx, &mut host, Amount::from_ccd(1), &mut logger);
+        claim!(result.is_ok(), "The call should be forwarded");
+        claim_eq!(
+            logger.logs,
+            vec![to_bytes(&VersusEvent::Forward(ForwardEvent {
+                entrypoint,
+                sender: USER,
+                amount: Amount::from_ccd(1),
+            }))]
+        );
+
+        // Every call of a multicall logs a forward event.
+        let call = || MulticallCall {
+            entrypoint: "joinQueue".into(),
+            parameter:  Vec::new(),
+        };
+        let parameter_bytes = to_bytes(&vec![call(), call()]);
+        ctx.set_parameter(&parameter_bytes);
+        let mut logger = TestLogger::init();
+        let result = contract_proxy_multicall(&ctx, &mut host, &mut logger);
+        claim!(result.is_ok(), "The calls should be forwarded");
+        let event = to_bytes(&VersusEvent::Forward(ForwardEvent {
+            entrypoint: OwnedEntrypointName::new_unchecked("joinQueue".into()),
+            sender:     USER,
+            amount:     Amount::zero(),
+        }));
+        claim_eq!(logger.logs, vec![event.clone(), event]);
+    }
+
+    #[concordium_test]
+    /// Test that updates can only be scheduled to implementations approved by
+    /// the admin, once the upgrade delay of the approval has passed.
+    fn test_approve_implementation() {
+        let mut host = initialized_host();
+        let new_implementation = ContractAddress {
+            index:    10,
+            subindex: 0,
+        };
+        let update_bytes = to_bytes(&SetImplementationAddressParams {
+            implementation_address: new_implementation,
+        });
+        let mut update_ctx = TestReceiveContext::empty();
+        update_ctx.set_sender(ADMIN_ADDRESS);
+        update_ctx.set_parameter(&update_bytes);
+        update_ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let result = contract_proxy_update_implementation(&update_ctx, &mut host);
+        claim_eq!(result, Err(CustomContractError::ImplementationNotApproved));
+
+        let approve = |host: &mut TestHost<StateProxy>, sender, approved, now| {
+            let parameter_bytes = to_bytes(&ApproveImplementationParams {
+                implementation_address: new_implementation,
+                approved,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(sender);
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(now));
+            contract_proxy_approve_implementation(&ctx, host)
+        };
+        let usable_at = DEFAULT_UPGRADE_DELAY_SECONDS * 1000;
+        claim_eq!(approve(&mut host, USER, true, 0), Err(CustomContractError::OnlyAdmin));
+        claim_eq!(approve(&mut host, ADMIN_ADDRESS, true, 0), Ok(()));
+        let result = contract_proxy_update_implementation(&update_ctx, &mut host);
+        claim_eq!(result, Err(CustomContractError::ApprovalNotReady));
+
+        // Approving again does not postpone the approval.
+        claim_eq!(approve(&mut host, ADMIN_ADDRESS, true, usable_at - 1), Ok(()));
+        update_ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(usable_at));
+        let result = contract_proxy_update_implementation(&update_ctx, &mut host);
+        claim!(result.is_ok(), "An approved implementation should be scheduled");
+        claim_eq!(
+            host.state().scheduled_upgrade.map(|upgrade| upgrade.implementation_address),
+            Some(new_implementation)
+        );
+
+        claim_eq!(approve(&mut host, ADMIN_ADDRESS, false, usable_at), Ok(()));
+        let result = contract_proxy_update_implementation(&update_ctx, &mut host);
+        claim_eq!(result, Err(CustomContractError::ImplementationNotApproved));
+    }
+
+    #[concordium_test]
+    /// Test that a swap to a correctly wired pair of contracts is scheduled and
+    /// only executed after the upgrade delay while the current implementation
+    /// holds no funds, and that a pair that does not reference each other is
+    /// rejected.
+    fn test_swap_backend() {
+        let proxy = ContractAddress {
+            index:    1,
+            subindex: 0,
+        };
+        let new_implementation = ContractAddress {
+            index:    12,
+            subindex: 0,
+        };
+        let new_state = ContractAddress {
+            index:    13,
+            subindex: 0,
+        };
+        let mut host = initialized_host();
+        let usable_at = Timestamp::from_timestamp_millis(0);
+        host.state_mut().approved_implementations.insert(new_implementation, usable_at);
+
+        let swap_backend = |host: &mut TestHost<StateProxy>,
+                                state_address: ContractAddress,
+                                implementation_address: ContractAddress| {
+            host.setup_mock_entrypoint(
+                new_implementation,
+                OwnedEntrypointName::new_unchecked("getProtocolAddresses".into()),
+                MockFn::returning_ok(ProtocolAddressesImplementation::Initialized {
+                    proxy_address: proxy,
+                    state_address,
+                }),
+            );
+            host.setup_mock_entrypoint(
+                new_state,
+                OwnedEntrypointName::new_unchecked("view".into()),
+                MockFn::returning_ok(ReturnBasicState {
+                    proxy_address: proxy,
+                    implementation_address,
+                    paused: false,
+                    pause_reason: None,
+                }),
+            );
+
+            let parameter_bytes = to_bytes(&SwapBackendParams {
+                implementation_address: new_implementation,
+                state_address:          new_state,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(ADMIN_ADDRESS);
+            ctx.set_self_address(proxy);
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+            contract_proxy_swap_backend(&ctx, host)
+        };
+
+        // The new implementation references the old state.
+        claim_eq!(
+            swap_backend(&mut host, STATE, new_implementation),
+            Err(CustomContractError::BackendMismatch)
+        );
+        // The new state references the old implementation.
+        claim_eq!(
+            swap_backend(&mut host, new_state, IMPLEMENTATION),
+            Err(CustomContractError::BackendMismatch)
+        );
+        claim_eq!(host.state().implementation_address, IMPLEMENTATION);
+        claim_eq!(host.state().state_address, STATE);
+
+        claim_eq!(swap_backend(&mut host, new_state, new_implementation), Ok(()));
+        let ready_at = Timestamp::from_timestamp_millis(DEFAULT_UPGRADE_DELAY_SECONDS * 1000);
+        claim_eq!(
+            host.state().scheduled_upgrade,
+            Some(ScheduledUpgrade {
+                implementation_address: new_implementation,
+                state_address:          Some(new_state),
+                ready_at,
+            })
+        );
+        claim_eq!(host.state().implementation_address, IMPLEMENTATION, "Swap is not instant");
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(USER);
+        ctx.set_self_address(proxy);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let mut logger = TestLogger::init();
+        let result = contract_proxy_execute_implementation_update(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(CustomContractError::UpgradeNotReady));
+
+        // The current implementation holds a reward pool or stakes on a pending
+        // match.
+        ctx.set_metadata_slot_time(ready_at);
+        let mut implementation = implementation_state();
+        implementation.reward_pool = Amount::from_ccd(1);
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("view".into()),
+            MockFn::returning_ok(implementation),
+        );
+        let result = contract_proxy_execute_implementation_update(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(CustomContractError::FundsHeld));
+        host.setup_mock_entrypoint(
+            IMPLEMENTATION,
+            OwnedEntrypointName::new_unchecked("view".into()),
+            MockFn::returning_ok(implementation_state()),
+        );
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getPendingMatches".into()),
+            MockFn::returning_ok(1u32),
+        );
+        let result = contract_proxy_execute_implementation_update(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Err(CustomContractError::FundsHeld));
+        claim_eq!(host.state().state_address, STATE);
+
+        host.setup_mock_entrypoint(
+            STATE,
+            OwnedEntrypointName::new_unchecked("getPendingMatches".into()),
+            MockFn::returning_ok(0u32),
+        );
+        let result = contract_proxy_execute_implementation_update(&ctx, &mut host, &mut logger);
+        claim_eq!(result, Ok(()));
+        claim_eq!(host.state().implementation_address, new_implementation);
+        claim_eq!(host.state().state_address, new_state);
+        claim_eq!(host.state().scheduled_upgrade, None);
+        claim_eq!(
+            logger.logs,
+            vec![to_bytes(&VersusEvent::NewImplementation(NewImplementationEvent {
+                new_implementation,
+            }))]
+        );
+    }
+}
@@ -1,16 +1,270 @@
 //! # A Concordium V1 smart contract
 use concordium_std::*;
-use core::fmt::Debug;
+use core::{cmp::Ordering, convert::TryFrom, fmt::Debug};
+
+/// Elo rating of a newly added player.
+const ELO_INITIAL_RATING: i32 = 1200;
+
+/// Default maximum rating change of a single match.
+const ELO_K_FACTOR: i32 = 32;
+
+/// Largest K-factor that can be configured.
+const ELO_MAX_K_FACTOR: i32 = 100;
+
+/// Rating difference covered by one step of `ELO_EXPECTED_SCORES`.
+const ELO_TABLE_STEP: i32 = 25;
+
+/// Expected score in per mille of the lower rated player, indexed by the
+/// rating difference divided by `ELO_TABLE_STEP`. The values are
+/// `1000 / (1 + 10^(d / 400))` rounded, so that the rating update only uses
+/// integer arithmetic. Differences above 800 use the last entry.
+const ELO_EXPECTED_SCORES: [i32; 33] = [
+    500, 464, 429, 394, 360, 327, 297, 267, 240, 215, 192, 170, 151, 133, 118, 104, 91, 80, 70,
+    61, 53, 46, 40, 35, 31, 27, 23, 20, 17, 15, 13, 11, 10,
+];
+
+/// Maximum number of entries returned by a leaderboard query.
+const MAX_LEADERBOARD_SIZE: u32 = 50;
+
+/// Maximum number of entries returned by a paginated query.
+const MAX_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of player entries `getPlayerRank` scans before it rejects.
+const MAX_PLAYER_RANK_SCAN: usize = 1_000;
+
+/// Maximum number of player entries `getTopByElo` scans before it rejects.
+const MAX_TOP_BY_ELO_SCAN: usize = 1_000;
+
+/// Number of levels of the state root tree above its leaves. The leaf of a
+/// player is picked by the first four bytes of the hash of their address.
+const STATE_ROOT_DEPTH: u8 = 32;
+
+/// Hash of an empty leaf or subtree of the state root tree.
+const EMPTY_NODE: HashSha2256 = HashSha2256([0u8; 32]);
+
+/// Maximum number of pairing queue tickets `pairPlayers` and `getQueue` look
+/// at per call.
+/// Players leaving the queue leave their tickets empty, so without the bound a
+/// call could run out of energy on them.
+const MAX_PAIRING_QUEUE_SCAN: u64 = 1_000;
+
+/// Hours after the completion of a match during which its result can be
+/// disputed.
+const DISPUTE_WINDOW_HOURS: u64 = 24;
+
+/// Hours after a rewarded match of two players during which their matches are
+/// not rewarded again.
+const REWARD_PAIR_COOLDOWN_HOURS: u64 = 24;
+
+/// Shortest season length that can be configured, in seconds.
+const MIN_SEASON_LENGTH_SECONDS: u64 = 60 * 60;
+
+/// Maximum length of a pause reason in bytes.
+const MAX_PAUSE_REASON_LENGTH: usize = 256;
+
+/// Maximum length of the name of a game in bytes.
+const MAX_GAME_NAME_LENGTH: usize = 64;
+
+/// Number of client references of battle result submissions that are kept to
+/// reject duplicate submissions.
+const MAX_CLIENT_REFS: u64 = 1024;
+
+/// Maximum number of results a rate limit can allow per window. Every player
+/// keeps the times of up to this many results.
+const MAX_RATE_LIMIT_RESULTS: u32 = 32;
+
+/// Default inactivity in seconds after which the rating of a player decays.
+const DECAY_INACTIVITY_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+/// Serialization version of `PlayerData`. Version 0 holds the state and the
+/// battle result, version 1 adds the win/loss counters and the Elo rating,
+/// version 2 the state history, version 3 the win streaks, version 4 the
+/// time of the last activity, version 5 the registration time, version 6 the
+/// result rate limit window, version 7 the season of the stats and version 8
+/// the result times of the sliding rate limit window.
+const PLAYER_DATA_VERSION: u8 = 8;
+
+/// Number of state transitions kept in the state history of a player.
+const STATE_HISTORY_LENGTH: usize = 8;
+
+/// Identifier of a match.
+type MatchId = u64;
+
+/// Identifier of a tournament.
+type TournamentId = u64;
+
+/// Maximum number of players of a tournament.
+const MAX_TOURNAMENT_PLAYERS: usize = 64;
+
+/// The shares of a tournament payout split add up to this many basis points.
+const PAYOUT_SPLIT_TOTAL: u16 = 10_000;
+
+/// Identifier of a game. Every game tracks its own players.
+type GameId = u32;
+
+/// The game that exists in every deployment. Functions without a game id
+/// work on the players of this game.
+const DEFAULT_GAME_ID: GameId = 0;
 
 // Types
 
-/// The state tracked for each address.
-#[derive(Serialize, SchemaType)]
+/// The state tracked for each address. It is serialized with a leading
+/// version byte, so that data written by an older implementation can still be
+/// read after an upgrade.
+#[derive(Serial, SchemaType, Clone)]
 struct PlayerData {
+    /// Serialization version, always `PLAYER_DATA_VERSION` once parsed.
+    version:        u8,
     /// The player's state
-    state:  PlayerState,
-    /// The player's battle result
-    result: BattleResult,
+    state:          PlayerState,
+    /// Result of the last battle counted in `wins` or `losses`, `NoResult`
+    /// if there is none.
+    last_result:    BattleResult,
+    /// Number of battles the player has won.
+    wins:           u32,
+    /// Number of battles the player has lost.
+    losses:         u32,
+    /// The player's Elo rating.
+    elo:            i32,
+    /// The most recent state transitions of the player.
+    state_history:  StateHistory,
+    /// Number of consecutive wins if positive, of consecutive losses if
+    /// negative.
+    current_streak: i32,
+    /// Longest run of consecutive wins.
+    best_streak:    u32,
+    /// Time the player was added or their last battle result was recorded.
+    last_updated:   Timestamp,
+    /// Time the player was added.
+    registered_at:  Timestamp,
+    /// Battle results submitted for the player within the rate limit window.
+    rate_window:    RateWindow,
+    /// Season the stats were recorded in. They are reset when the player is
+    /// next accessed in a later season, see `roll_over_player`.
+    season:         u32,
+}
+
+impl Deserial for PlayerData {
+    fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
+        let version = u8::deserial(source)?;
+        ensure!(version <= PLAYER_DATA_VERSION, ParseError::default());
+        let state = source.get()?;
+        // Earlier versions stored the result of the last update, including
+        // updates without a result, at the position of the last result.
+        let last_result = source.get()?;
+        // Fields added after the version of the data get their defaults.
+        let (wins, losses, elo) = if version >= 1 {
+            (source.get()?, source.get()?, source.get()?)
+        } else {
+            (0, 0, ELO_INITIAL_RATING)
+        };
+        let state_history = if version >= 2 {
+            source.get()?
+        } else {
+            StateHistory::new()
+        };
+        let (current_streak, best_streak) = if version >= 3 {
+            (source.get()?, source.get()?)
+        } else {
+            (0, 0)
+        };
+        // Players without a recorded activity count as inactive.
+        let last_updated = if version >= 4 {
+            source.get()?
+        } else {
+            Timestamp::from_timestamp_millis(0)
+        };
+        // Players registered before the registration time was tracked are
+        // past any grace period.
+        let registered_at = if version >= 5 {
+            source.get()?
+        } else {
+            Timestamp::from_timestamp_millis(0)
+        };
+        let rate_window = if version >= 8 {
+            source.get()?
+        } else if version >= 6 {
+            // Earlier versions counted the results of a fixed window. They
+            // count as submitted at the start of that window.
+            let start: Timestamp = source.get()?;
+            let count: u32 = source.get()?;
+            RateWindow {
+                submitted: vec![start; count.min(MAX_RATE_LIMIT_RESULTS) as usize],
+            }
+        } else {
+            RateWindow::new()
+        };
+        // Data without a season counts as recorded in the first season.
+        let season = if version >= 7 {
+            source.get()?
+        } else {
+            0
+        };
+        Ok(PlayerData {
+            version: PLAYER_DATA_VERSION,
+            state,
+            last_result,
+            wins,
+            losses,
+            elo,
+            state_history,
+            current_streak,
+            best_streak,
+            last_updated,
+            registered_at,
+            rate_window,
+            season,
+        })
+    }
+}
+
+/// Fixed-length ring buffer of the most recent state transitions of a player
+/// together with the time of the transition.
+#[derive(Serialize, SchemaType, Clone)]
+struct StateHistory {
+    /// The transitions, `None` for slots that were not written yet.
+    entries: [Option<(PlayerState, Timestamp)>; STATE_HISTORY_LENGTH],
+    /// Index of the slot written next. Once all slots are written, this is
+    /// the slot of the oldest transition.
+    next:    u8,
+}
+
+impl StateHistory {
+    fn new() -> Self {
+        StateHistory {
+            entries: [None; STATE_HISTORY_LENGTH],
+            next:    0,
+        }
+    }
+
+    /// Record a transition, overwriting the oldest one if the buffer is full.
+    fn push(&mut self, state: PlayerState, time: Timestamp) {
+        let next = usize::from(self.next);
+        self.entries[next] = Some((state, time));
+        self.next = ((next + 1) % STATE_HISTORY_LENGTH) as u8;
+    }
+
+    /// The recorded transitions, oldest first.
+    fn to_vec(&self) -> Vec<(PlayerState, Timestamp)> {
+        let (newer, older) = self.entries.split_at(usize::from(self.next));
+        older.iter().chain(newer).flatten().copied().collect()
+    }
+}
+
+/// The battle results submitted for a player within the rate limit window.
+#[derive(Serialize, SchemaType, Clone)]
+struct RateWindow {
+    /// Times of the submitted results, oldest first. Results that left the
+    /// window are removed with the next submission.
+    submitted: Vec<Timestamp>,
+}
+
+impl RateWindow {
+    fn new() -> Self {
+        RateWindow {
+            submitted: Vec::new(),
+        }
+    }
 }
 
 /// The `state` contract state.
@@ -18,11 +272,118 @@ struct PlayerData {
 #[concordium(state_parameter = "S")]
 struct State<S> {
     /// Addresses of the protocol
-    protocol_addresses: ProtocolAddressesState,
-    /// The state of the one player.
-    player_data:        StateMap<Address, PlayerData, S>,
+    protocol_addresses:      ProtocolAddressesState,
+    /// The data of each player, by the game they play in and their address.
+    player_data:             StateMap<(GameId, Address), PlayerData, S>,
+    /// Id of the next created game.
+    next_game_id:            GameId,
+    /// The metadata and the number of players of each game.
+    games:                   StateMap<GameId, GameInfo, S>,
+    /// Nodes of the state root tree by their height above the leaves and
+    /// their position in their level. Empty subtrees are not stored.
+    state_root_nodes:        StateMap<(u8, u32), HashSha2256, S>,
+    /// Players of the default game by the position of their leaf in the state
+    /// root tree, ordered by their serialized address.
+    state_root_leaves:       StateMap<u32, Vec<Address>, S>,
     /// Contract is paused/unpaused.
-    paused:             bool,
+    paused:                  bool,
+    /// Why the contract was paused, cleared when it is unpaused.
+    pause_reason:            Option<String>,
+    /// When the contract was paused, cleared when it is unpaused.
+    paused_at:               Option<Timestamp>,
+    /// All mutations are rejected after an emergency shutdown.
+    shutdown:                bool,
+    /// The state of newly added players.
+    default_player_state:    PlayerState,
+    /// All matches by their id.
+    matches:                 StateMap<MatchId, Match, S>,
+    /// Ids of the matches that are still pending.
+    pending_matches:         StateSet<MatchId, S>,
+    /// Id of the next created match.
+    next_match_id:           MatchId,
+    /// The player waiting in the matchmaking queue of each game.
+    queued_players:          StateMap<GameId, Address, S>,
+    /// Players waiting to be paired by `pairPlayers` with the game they wait
+    /// in, by their ticket.
+    pairing_queue:           StateMap<u64, (GameId, Address), S>,
+    /// Ticket of each player waiting in the pairing queue of a game.
+    pairing_tickets:         StateMap<(GameId, Address), u64, S>,
+    /// Lowest ticket that can still be waiting in the pairing queue.
+    pairing_queue_head:      u64,
+    /// Ticket of the next player joining the pairing queue.
+    pairing_queue_tail:      u64,
+    /// Number of players waiting in the pairing queue.
+    pairing_queue_length:    u32,
+    /// The number of the current season.
+    current_season:          u32,
+    /// Start of the current season.
+    season_start:            Timestamp,
+    /// Length of a season in seconds. Seasons do not roll over if zero.
+    season_length_seconds:   u64,
+    /// Reject results once the current season has ended until the rollover
+    /// is applied with `rollOverSeason`, instead of applying the rollover
+    /// together with the result.
+    rollover_lock:           bool,
+    /// Number of players added in each season.
+    players_added:           StateMap<u32, u32, S>,
+    /// The number and total duration of all recorded matches.
+    match_durations:         MatchDurations,
+    /// The account that deployed the contract. It can write player data
+    /// directly while the data fix mode is enabled.
+    state_admin:             Address,
+    /// The state admin can only write player data directly while enabled.
+    data_fix_mode:           bool,
+    /// Players with fewer completed matches do not appear on the leaderboard.
+    leaderboard_min_matches: u32,
+    /// The wins and losses recorded over all players and seasons.
+    result_totals:           ResultTotals,
+    /// The K-factors used for rating updates.
+    elo_params:              EloParams,
+    /// Seconds a result recorded with `recordMatch` waits before it counts.
+    /// Results of `completeMatch` and `updateBattleResult` count at once.
+    finality_delay_seconds:  u64,
+    /// Recorded match results that do not count yet, by their id. The ids
+    /// from `pending_results_head` up to `next_result_id` form a queue in the
+    /// order the results were recorded.
+    pending_results:         StateMap<u64, PendingResult, S>,
+    /// Id of the next pending result.
+    next_result_id:          u64,
+    /// Id of the oldest pending result that was not finalized yet.
+    pending_results_head:    u64,
+    /// Accounts allowed to submit battle results when `referee_only` is set.
+    referees:                StateSet<Address, S>,
+    /// Battle results can only be submitted by referees.
+    referee_only:            bool,
+    /// Only accounts can be added as players.
+    accounts_only:           bool,
+    /// The rating decay of inactive players.
+    decay_params:            DecayParams,
+    /// Seconds after being added before results can be recorded for a
+    /// player.
+    battle_grace_seconds:    u64,
+    /// Number of players in the `Active` state.
+    active_player_count:     u32,
+    /// Client references of the most recent battle result submissions.
+    client_refs:             StateSet<[u8; 32], S>,
+    /// The kept client references by the order they were submitted in.
+    client_ref_order:        StateMap<u64, [u8; 32], S>,
+    /// Position of the next submitted client reference.
+    next_client_ref:         u64,
+    /// The maximum number of battle results per player and window.
+    result_rate_limit:       RateLimit,
+    /// The stakes of the players of a tournament until its prizes are
+    /// distributed.
+    tournament_stakes:       StateMap<(TournamentId, Address), Amount, S>,
+    /// All tournaments by their id.
+    tournaments:             StateMap<TournamentId, Tournament, S>,
+    /// Id of the next created tournament.
+    next_tournament_id:      TournamentId,
+    /// When a match of two players was last rewarded, by the ordered pair.
+    pair_rewarded_at:        StateMap<(Address, Address), Timestamp, S>,
+    /// Number of pending matches, disputed matches, pending results and
+    /// tournaments without distributed prizes of each player. Players are
+    /// only deleted without such references.
+    player_references:       StateMap<Address, u32, S>,
 }
 
 #[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
@@ -32,20 +393,216 @@ enum PlayerState {
     Suspended
 }
 
-#[derive(Debug, Serialize, SchemaType, Clone, Copy)]
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
 enum BattleResult {
     NoResult,
     Win,
     Loss
 }
 
+/// The K-factors of the Elo rating update. Players with fewer completed
+/// matches than `provisional_matches` use `provisional_k`, so that the
+/// ratings of new players settle faster.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+struct EloParams {
+    /// K-factor of players with fewer than `provisional_matches` matches.
+    provisional_k:       i32,
+    /// K-factor of all other players.
+    established_k:       i32,
+    /// Number of completed matches after which a player is established.
+    provisional_matches: u32,
+}
+
+/// The rating decay of inactive players.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+struct DecayParams {
+    /// Seconds without activity after which the rating of a player decays.
+    inactivity_seconds: u64,
+    /// Rating lost by an inactive player per decay. Zero disables the decay.
+    amount:             i32,
+    /// Ratings do not decay below the floor.
+    floor:              i32,
+}
+
+/// The maximum number of battle results that can be submitted for a player
+/// within a sliding window: a result is rejected if `max_results` results were
+/// submitted for the player within the last `window_seconds`.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+struct RateLimit {
+    /// Results allowed per window, at most `MAX_RATE_LIMIT_RESULTS`. Zero
+    /// disables the rate limit.
+    max_results:    u32,
+    /// Length of the window in seconds, not zero while results are limited.
+    window_seconds: u64,
+}
+
+/// The number and total duration of all recorded matches.
+#[derive(Serialize, SchemaType, Clone, Copy, Debug, PartialEq)]
+struct MatchDurations {
+    /// Number of recorded matches.
+    matches:       u64,
+    /// Sum of the durations of the recorded matches in seconds.
+    total_seconds: u64,
+}
+
+/// The number of wins and losses recorded over all players and seasons.
+/// Season resets keep them, reverted results are subtracted again.
+#[derive(Serialize, SchemaType, Clone, Copy, Debug, PartialEq)]
+struct ResultTotals {
+    /// Number of recorded wins.
+    wins:   u64,
+    /// Number of recorded losses.
+    losses: u64,
+}
+
+impl ResultTotals {
+    /// Count a recorded result.
+    fn count(&mut self, result: BattleResult) -> ContractResult<()> {
+        let total = match result {
+            BattleResult::Win => &mut self.wins,
+            BattleResult::Loss => &mut self.losses,
+            BattleResult::NoResult => return Ok(()),
+        };
+        *total = total.checked_add(1).ok_or(CustomContractError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// The return type for the state contract function `getMatchDurationStats`.
+#[derive(Serialize, SchemaType, Debug, PartialEq)]
+struct MatchDurationStats {
+    /// Number of recorded matches.
+    matches:         u64,
+    /// Sum of the durations of the recorded matches in seconds.
+    total_seconds:   u64,
+    /// Average duration of the recorded matches in whole seconds, zero if no
+    /// match was recorded.
+    average_seconds: u64,
+}
+
+/// The return type for the state contract function `updateBattleResult`. The
+/// stats of the player before and after the result was applied.
+#[derive(Serialize, SchemaType, Debug, PartialEq)]
+struct ResultSnapshot {
+    /// Wins of the player before the result.
+    wins_before: u32,
+    /// Wins of the player after the result.
+    wins_after:  u32,
+    /// Elo rating of the player before the result.
+    elo_before:  i32,
+    /// Elo rating of the player after the result.
+    elo_after:   i32,
+}
+
+/// The return type for the state contract function `completeMatch`.
+#[derive(Serialize, SchemaType, Debug, PartialEq)]
+struct CompletedMatch {
+    /// The total amount staked on the match, owed to the winner.
+    pot:        Amount,
+    /// The winner can be rewarded, the players of the match were not rewarded
+    /// within `REWARD_PAIR_COOLDOWN_HOURS`.
+    rewardable: bool,
+}
+
+/// A recorded match result waiting for the finality delay to pass.
+#[derive(Serialize, SchemaType, Clone)]
+struct PendingResult {
+    /// The game the match was played in.
+    game_id:     GameId,
+    /// Player who won the match.
+    winner:      Address,
+    /// Player who lost the match.
+    loser:       Address,
+    /// Time the result was recorded at.
+    recorded_at: Timestamp,
+}
+
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+enum MatchStatus {
+    Pending,
+    Completed,
+    Cancelled,
+    Disputed,
+    Resolved
+}
+
+/// A match between two players.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct Match {
+    /// The game the match is played in.
+    game_id:      GameId,
+    /// First player of the match.
+    player_a:     Address,
+    /// Second player of the match.
+    player_b:     Address,
+    /// Pending, Completed, Cancelled, Disputed or Resolved
+    status:       MatchStatus,
+    /// The winner of a completed match.
+    winner:       Option<Address>,
+    /// Amount staked by the first player.
+    stake_a:      Amount,
+    /// Amount staked by the second player.
+    stake_b:      Amount,
+    /// Time the match was completed at.
+    completed_at: Option<Timestamp>,
+    /// Elo rating gained by the winner and lost by the loser when the match
+    /// was completed.
+    elo_deltas:   (i32, i32),
+}
+
+/// A pairing of a round of a tournament.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct BracketMatch {
+    /// First player of the pairing.
+    player_a: Address,
+    /// Second player of the pairing.
+    player_b: Address,
+    /// The winner, once the result was reported.
+    winner:   Option<Address>,
+}
+
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq)]
+enum TournamentStatus {
+    /// The matches of the current round are played.
+    InProgress,
+    /// The final was played.
+    Completed {
+        /// The winner of the final.
+        champion: Address,
+    },
+}
+
+/// A single-elimination tournament. The players of the first round are paired
+/// in the order they are listed in, the winners of a round are paired in the
+/// order of their matches in the next round.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct Tournament {
+    /// Identifier of the tournament.
+    id:                 TournamentId,
+    /// The game the tournament is played in.
+    game_id:            GameId,
+    /// The players of the tournament.
+    players:            Vec<Address>,
+    /// The rounds played so far, the last one is the current round.
+    rounds:             Vec<Vec<BracketMatch>>,
+    /// InProgress or Completed
+    status:             TournamentStatus,
+    /// Shares of the prize pool of the top finishers in basis points, starting
+    /// with the champion.
+    payout_split:       Vec<u16>,
+    /// Whether the prizes of the completed tournament were distributed.
+    prizes_distributed: bool,
+    /// The amount staked on the tournament and not paid out as prizes.
+    pool:               Amount,
+}
+
 #[derive(Serialize, PartialEq, Clone)]
 enum ProtocolAddressesState {
     UnInitialized,
     Initialized {
-        /// Address of the w_ccd proxy contract.
+        /// Address of the versus proxy contract.
         proxy_address:          ContractAddress,
-        /// Address of the w_ccd implementation contract.
+        /// Address of the versus implementation contract.
         implementation_address: ContractAddress,
     },
 }
@@ -53,9 +610,9 @@ enum ProtocolAddressesState {
 /// The parameter type for the state contract function `initialize`.
 #[derive(Serialize, SchemaType)]
 struct InitializeStateParams {
-    /// Address of the w_ccd proxy contract.
+    /// Address of the versus proxy contract.
     proxy_address:          ContractAddress,
-    /// Address of the w_ccd implementation contract.
+    /// Address of the versus implementation contract.
     implementation_address: ContractAddress,
 }
 
@@ -63,7 +620,7 @@ struct InitializeStateParams {
 /// `setImplementationAddress`.
 #[derive(Serialize, SchemaType)]
 struct SetImplementationAddressParams {
-    /// Address of the w_ccd implementation contract.
+    /// Address of the versus implementation contract.
     implementation_address: ContractAddress,
 }
 
@@ -77,19 +634,290 @@ struct SetPausedParams {
 /// The parameter type for the state contract function `updatePlayerState`.
 #[derive(Serialize, SchemaType)]
 struct UpdatePlayerStateParams {
+    /// The game of the player.
+    game_id: GameId,
     /// Player to update state.
-    player: Address,
+    player:  Address,
     /// Active or Suspended
-    state:  PlayerState,
+    state:   PlayerState,
+}
+
+/// The metadata and the number of players of a game, as returned by
+/// `getGames`.
+#[derive(Debug, Serialize, SchemaType, Clone, PartialEq)]
+struct GameInfo {
+    /// Name of the game.
+    name:         Option<String>,
+    /// Time the game was created at.
+    created_at:   Timestamp,
+    /// Number of players added to the game.
+    player_count: u32,
+}
+
+/// The parameter type for the state contract functions `addPlayer`,
+/// `getPlayerData`, `getPlayerSummary`, `isAdded`, `resetPlayer`, the queue
+/// functions and the player queries.
+#[derive(Serialize, SchemaType)]
+struct GamePlayerParams {
+    /// The game of the player.
+    game_id: GameId,
+    /// The player.
+    player:  Address,
 }
 
 /// The parameter type for the state contract function `updateBattleResult`.
 #[derive(Serialize, SchemaType)]
 struct UpdateBattleResultParams {
+    /// The game the battle was played in.
+    game_id:    GameId,
     /// Player to update state.
-    player: Address,
+    player:     Address,
     /// Win or Loss
-    result: BattleResult,
+    result:     BattleResult,
+    /// Reference chosen by the client, so that a retried submission is
+    /// rejected instead of being applied twice.
+    client_ref: Option<[u8; 32]>,
+}
+
+/// The parameter type for the state contract function `adminSetPlayerData`.
+#[derive(Serialize, SchemaType)]
+struct AdminSetPlayerDataParams {
+    /// The game of the player.
+    game_id:     GameId,
+    /// The player whose data is written.
+    player:      Address,
+    /// The complete data written for the player.
+    player_data: PlayerData,
+}
+
+/// The parameter type for the state contract function `recordMatch`.
+#[derive(Serialize, SchemaType)]
+struct RecordMatchParams {
+    /// The game the match was played in.
+    game_id:          GameId,
+    /// Player who won the match.
+    winner:           Address,
+    /// Player who lost the match.
+    loser:            Address,
+    /// How long the match took in seconds, has to be nonzero.
+    duration_seconds: u32,
+}
+
+/// The parameter type for the state contract function `createMatch`.
+#[derive(Serialize, SchemaType)]
+struct CreateMatchParams {
+    /// The game the match is played in.
+    game_id:  GameId,
+    /// First player of the match.
+    player_a: Address,
+    /// Second player of the match.
+    player_b: Address,
+}
+
+/// The parameter type for the state contract function `completeMatch`.
+#[derive(Serialize, SchemaType)]
+struct CompleteMatchParams {
+    /// The match to complete.
+    match_id: MatchId,
+    /// Player who won the match.
+    winner:   Address,
+}
+
+/// The parameter type for the state contract function `stakeMatch`.
+#[derive(Serialize, SchemaType)]
+struct StakeMatchParams {
+    /// The match to stake on.
+    match_id: MatchId,
+    /// Player of the match adding the stake.
+    player:   Address,
+    /// The staked amount.
+    amount:   Amount,
+}
+
+/// The parameter type for the state contract function `refundStake`.
+#[derive(Serialize, SchemaType)]
+struct RefundStakeParams {
+    /// The pending match the stake was placed on.
+    match_id: MatchId,
+    /// Player of the match refunded their stake.
+    player:   Address,
+}
+
+/// The parameter type for the state contract function `stakeTournament`.
+#[derive(Serialize, SchemaType)]
+struct StakeTournamentParams {
+    /// The tournament to stake on.
+    tournament_id: TournamentId,
+    /// Player adding the stake.
+    player:        Address,
+    /// The staked amount.
+    amount:        Amount,
+}
+
+/// The parameter type for the state contract function `createTournament`.
+#[derive(Serialize, SchemaType)]
+struct CreateTournamentParams {
+    /// The game the tournament is played in.
+    game_id:      GameId,
+    /// The players of the tournament.
+    players:      Vec<Address>,
+    /// Shares of the prize pool of the top finishers in basis points, starting
+    /// with the champion. They have to add up to `PAYOUT_SPLIT_TOTAL`.
+    payout_split: Vec<u16>,
+}
+
+/// The parameter type for the state contract function `reportTournamentMatch`.
+#[derive(Serialize, SchemaType)]
+struct ReportTournamentMatchParams {
+    /// The tournament the match was played in.
+    tournament_id: TournamentId,
+    /// Player who won the match.
+    winner:        Address,
+    /// Player who lost the match.
+    loser:         Address,
+}
+
+/// The parameter type for the state contract function `transferProfile`.
+#[derive(Serialize, SchemaType)]
+struct TransferProfileParams {
+    /// The game the player data is moved in.
+    game_id: GameId,
+    /// Address the player data is moved from.
+    from:    Address,
+    /// Address the player data is moved to.
+    to:      Address,
+}
+
+/// The parameter type for the state contract function `disputeMatch`.
+#[derive(Serialize, SchemaType)]
+struct DisputeMatchParams {
+    /// The match to dispute.
+    match_id: MatchId,
+    /// Player of the match disputing the result.
+    player:   Address,
+}
+
+/// The parameter type for the state contract function `resolveDispute`.
+#[derive(Serialize, SchemaType)]
+struct ResolveDisputeParams {
+    /// The disputed match.
+    match_id: MatchId,
+    /// Whether the result is overturned instead of upheld.
+    overturn: bool,
+}
+
+/// The parameter type for the state contract function `getMatches`.
+#[derive(Serialize, SchemaType)]
+struct GetMatchesParams {
+    /// Id of the first match to return, the `next` cursor of the previous page.
+    from:  MatchId,
+    /// Maximum number of matches to return, capped at `MAX_PAGE_SIZE`.
+    limit: u32,
+}
+
+/// The return type for the state contract function `getWinStreak`.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+struct WinStreak {
+    /// Number of consecutive wins if positive, of consecutive losses if
+    /// negative.
+    current: i32,
+    /// Longest run of consecutive wins.
+    best:    u32,
+}
+
+/// The return type for the state contract function `getPlayerSummary`.
+#[derive(Serial, SchemaType)]
+struct PlayerSummary {
+    /// The complete data of the player.
+    player_data:    PlayerData,
+    /// Number of battles the player has won or lost.
+    total_matches:  u64,
+    /// The leaderboard rank of the player, see `getPlayerRank`.
+    rank:           u32,
+    /// Number of consecutive wins if positive, of consecutive losses if
+    /// negative.
+    current_streak: i32,
+    /// The player's Elo rating.
+    elo:            i32,
+}
+
+/// The return type for the state contract function `getMatches`.
+#[derive(Serialize, SchemaType)]
+struct MatchesPage {
+    /// The matches of this page in ascending order of their id.
+    matches: Vec<(MatchId, Match)>,
+    /// Cursor to request the next page with, if there are more matches.
+    next:    Option<MatchId>,
+}
+
+/// The parameter type for the state contract function `getQueue`.
+#[derive(Serialize, SchemaType)]
+struct GetQueueParams {
+    /// First ticket to look at, the `next` cursor of the previous page.
+    from:  u64,
+    /// Maximum number of players to return, capped at `MAX_PAGE_SIZE`.
+    limit: u32,
+}
+
+/// The return type for the state contract function `getQueue`.
+#[derive(Serialize, SchemaType, Debug, PartialEq)]
+struct QueuePage {
+    /// The waiting players of this page with the game they wait in, in the
+    /// order they joined the queue.
+    players: Vec<(GameId, Address)>,
+    /// Cursor to request the next page with, if there are more tickets.
+    next:    Option<u64>,
+}
+
+/// The parameter type for paginated queries.
+#[derive(Serialize, SchemaType)]
+struct PaginationParams {
+    /// Number of entries to skip.
+    offset: u32,
+    /// Maximum number of entries to return, capped at `MAX_PAGE_SIZE`.
+    limit:  u32,
+}
+
+/// The parameter type for the state contract functions `resetAllPlayers` and
+/// `decayRatings`, which visit the players in pages.
+#[derive(Serialize, SchemaType)]
+struct PlayerCursorParams {
+    /// Key of the last player of the previous page, `None` for the first page.
+    after: Option<(GameId, Address)>,
+    /// Maximum number of players to visit, capped at `MAX_PAGE_SIZE`.
+    limit: u32,
+}
+
+/// The parameter type for the state contract function `getTopByElo`.
+#[derive(Serialize, SchemaType)]
+struct TopByEloParams {
+    /// The game to get the leaderboard of.
+    game_id: GameId,
+    /// Maximum number of players to return, capped at `MAX_LEADERBOARD_SIZE`.
+    limit:   u32,
+}
+
+/// The return type for the state contract function `resetAllPlayers`.
+#[derive(Debug, Serialize, SchemaType, PartialEq)]
+struct ResetProgress {
+    /// Key of the last reset player to continue the reset after.
+    next: Option<(GameId, Address)>,
+    /// Whether all players were reset.
+    done: bool,
+}
+
+/// The return type for the state contract function `getPlayerProof`. Merkle
+/// proof of a leaf of the state root tree.
+#[derive(Serialize, SchemaType)]
+struct MerkleProof {
+    /// Position of the leaf.
+    index:    u32,
+    /// The players of the leaf with their data, ordered by their serialized
+    /// address.
+    players:  Vec<(Address, PlayerData)>,
+    /// Hashes of the siblings on the path from the leaf to the root, starting
+    /// with the sibling of the leaf.
+    siblings: Vec<HashSha2256>,
 }
 
 /// The return type for the state contract function `view`.
@@ -101,6 +929,44 @@ struct ReturnBasicState {
     implementation_address: ContractAddress,
     /// Contract is paused/unpaused.
     paused:                 bool,
+    /// Why the contract was paused, cleared when it is unpaused.
+    pause_reason:           Option<String>,
+}
+
+/// The return type for the state contract function `getExtendedState`.
+#[derive(Serialize, SchemaType, Debug, PartialEq)]
+struct ExtendedState {
+    /// Address of the versus proxy contract.
+    proxy_address:          ContractAddress,
+    /// Address of the versus implementation contract.
+    implementation_address: ContractAddress,
+    /// Contract is paused/unpaused.
+    paused:                 bool,
+    /// Why the contract was paused, cleared when it is unpaused.
+    pause_reason:           Option<String>,
+    /// The contract was shut down and rejects all mutations.
+    shutdown:               bool,
+    /// Number of players over all games.
+    player_count:           u32,
+    /// Number of active players.
+    active_player_count:    u32,
+    /// The number of the current season.
+    current_season:         u32,
+    /// Number of wins recorded over all players and seasons.
+    total_wins:             u64,
+    /// Number of losses recorded over all players and seasons.
+    total_losses:           u64,
+}
+
+/// The return type for the state contract function `getStatus`.
+#[derive(Serialize, SchemaType, Debug, PartialEq)]
+struct StateStatus {
+    /// The protocol addresses are set.
+    initialized: bool,
+    /// Contract is paused/unpaused.
+    paused:      bool,
+    /// The contract was shut down and rejects all mutations.
+    shutdown:    bool,
 }
 
 /// Your smart contract errors.
@@ -124,6 +990,105 @@ enum CustomContractError {
     OnlyImplementation,
     /// Only proxy contract.
     OnlyProxy,
+    /// Player is not added.
+    PlayerNotFound,
+    /// A player can not play a match against themselves.
+    InvalidMatch,
+    /// A counter would overflow.
+    ArithmeticOverflow,
+    /// Match does not exist.
+    MatchNotFound,
+    /// Match is already completed or cancelled.
+    MatchNotPending,
+    /// Player is not active.
+    PlayerNotActive,
+    /// Player is already waiting in the queue.
+    AlreadyQueued,
+    /// Player is not waiting in the queue.
+    NotQueued,
+    /// Already added as player.
+    AlreadyAdded,
+    /// Match is not completed.
+    MatchNotCompleted,
+    /// Match is not disputed.
+    MatchNotDisputed,
+    /// The dispute window of the match is closed.
+    DisputeWindowClosed,
+    /// The season length is below the minimum season length.
+    SeasonTooShort,
+    /// A K-factor is not positive or above the maximum K-factor.
+    InvalidEloParams,
+    /// Contract is shut down.
+    ContractShutDown,
+    /// Address is not a referee.
+    NotReferee,
+    /// Only accounts can be added as players.
+    ContractPlayerNotAllowed,
+    /// The decay amount is negative or the inactivity period is zero.
+    InvalidDecayParams,
+    /// The player was added too recently to have results recorded.
+    GracePeriodActive,
+    /// The pause reason is longer than `MAX_PAUSE_REASON_LENGTH` bytes.
+    PauseReasonTooLong,
+    /// A battle result with the same client reference was already submitted.
+    DuplicateSubmission,
+    /// Game does not exist.
+    GameNotFound,
+    /// The game name is longer than `MAX_GAME_NAME_LENGTH` bytes.
+    GameNameTooLong,
+    /// A query would scan more entries than its scan limit allows.
+    ScanLimitExceeded,
+    /// The player already has the maximum number of results in the current
+    /// rate limit window.
+    RateLimited,
+    /// The number of players of a tournament is not a power of two between 2
+    /// and `MAX_TOURNAMENT_PLAYERS`, or a player is listed twice.
+    InvalidTournament,
+    /// Tournament not found.
+    TournamentNotFound,
+    /// The final of the tournament was already played.
+    TournamentCompleted,
+    /// The players are not paired in the current round of the tournament.
+    NotInCurrentRound,
+    /// The result of the match was already reported, so that one of the
+    /// players is eliminated.
+    PlayerEliminated,
+    /// Not all results of the current round were reported yet.
+    RoundNotComplete,
+    /// The payout split does not add up to `PAYOUT_SPLIT_TOTAL` or has more
+    /// shares than the tournament has players.
+    InvalidPayoutSplit,
+    /// The final of the tournament was not played yet.
+    TournamentNotCompleted,
+    /// The prizes of the tournament were already distributed.
+    PrizesAlreadyDistributed,
+    /// The current season has ended and results are rejected until the
+    /// rollover is applied.
+    SeasonRollover,
+    /// The duration of a match is zero.
+    InvalidDuration,
+    /// Only the state admin.
+    OnlyStateAdmin,
+    /// The data fix mode is not enabled.
+    DataFixModeDisabled,
+    /// The player has not completed enough matches to appear on the
+    /// leaderboard.
+    NotOnLeaderboard,
+    /// The player does not play in the tournament.
+    NotInTournament,
+    /// The contract is not shut down.
+    NotShutDown,
+    /// The rate limit allows results but its window length is zero, or it
+    /// allows more than `MAX_RATE_LIMIT_RESULTS` results.
+    InvalidRateLimit,
+    /// The player is part of a pending match, a disputed match, a pending
+    /// result or a tournament without distributed prizes.
+    PlayerReferenced,
+    /// Newly added players can not start in the `NotAdded` state.
+    InvalidDefaultPlayerState,
+    /// Stakes can only be placed on matches between accounts, as the pot is
+    /// paid to the winner.
+    OnlyAccount,
 }
 
 type ContractResult<A> = Result<A, CustomContractError>;
@@ -148,12 +1113,83 @@ impl<S: HasStateApi> State<S> {
     /// data by default. The ProtocolAddressesState is uninitialized.
     /// The ProtocolAddressesState has to be set with the `initialize`
     /// function after the `proxy` contract is deployed.
-    fn new(state_builder: &mut StateBuilder<S>) -> Self {
+    fn new(state_builder: &mut StateBuilder<S>, state_admin: Address) -> Self {
+        let mut games = state_builder.new_map();
+        games.insert(DEFAULT_GAME_ID, GameInfo {
+            name:         None,
+            created_at:   Timestamp::from_timestamp_millis(0),
+            player_count: 0,
+        });
+
         // Setup state.
         State {
-            protocol_addresses: ProtocolAddressesState::UnInitialized,
-            player_data:        state_builder.new_map(),
-            paused:             false,
+            protocol_addresses:      ProtocolAddressesState::UnInitialized,
+            player_data:             state_builder.new_map(),
+            next_game_id:            DEFAULT_GAME_ID + 1,
+            games,
+            state_root_nodes:        state_builder.new_map(),
+            state_root_leaves:       state_builder.new_map(),
+            paused:                  false,
+            pause_reason:            None,
+            paused_at:               None,
+            shutdown:                false,
+            default_player_state:    PlayerState::Suspended,
+            matches:                 state_builder.new_map(),
+            pending_matches:         state_builder.new_set(),
+            next_match_id:           0,
+            queued_players:          state_builder.new_map(),
+            pairing_queue:           state_builder.new_map(),
+            pairing_tickets:         state_builder.new_map(),
+            pairing_queue_head:      0,
+            pairing_queue_tail:      0,
+            pairing_queue_length:    0,
+            current_season:          0,
+            season_start:            Timestamp::from_timestamp_millis(0),
+            season_length_seconds:   0,
+            rollover_lock:           false,
+            players_added:           state_builder.new_map(),
+            match_durations:         MatchDurations {
+                matches:       0,
+                total_seconds: 0,
+            },
+            state_admin,
+            data_fix_mode:           false,
+            leaderboard_min_matches: 0,
+            result_totals:           ResultTotals {
+                wins:   0,
+                losses: 0,
+            },
+            elo_params:              EloParams {
+                provisional_k:       ELO_K_FACTOR,
+                established_k:       ELO_K_FACTOR,
+                provisional_matches: 0,
+            },
+            finality_delay_seconds:  0,
+            pending_results:         state_builder.new_map(),
+            next_result_id:          0,
+            pending_results_head:    0,
+            referees:                state_builder.new_set(),
+            referee_only:            false,
+            accounts_only:           false,
+            decay_params:            DecayParams {
+                inactivity_seconds: DECAY_INACTIVITY_SECONDS,
+                amount:             0,
+                floor:              ELO_INITIAL_RATING,
+            },
+            battle_grace_seconds:    0,
+            active_player_count:     0,
+            client_refs:             state_builder.new_set(),
+            client_ref_order:        state_builder.new_map(),
+            next_client_ref:         0,
+            result_rate_limit:       RateLimit {
+                max_results:    0,
+                window_seconds: 0,
+            },
+            tournament_stakes:       state_builder.new_map(),
+            tournaments:             state_builder.new_map(),
+            next_tournament_id:      0,
+            pair_rewarded_at:        state_builder.new_map(),
+            player_references:       state_builder.new_map(),
         }
     }
 }
@@ -163,11 +1199,14 @@ impl<S: HasStateApi> State<S> {
 /// Init function that creates a new smart contract.
 #[init(contract = "Versus-State")]
 fn contract_state_init<S: HasStateApi>(
-    _ctx: &impl HasInitContext,
+    ctx: &impl HasInitContext,
     state_builder: &mut StateBuilder<S>,
 ) -> InitResult<State<S>> {
     // Construct the initial contract state.
-    let state = State::new(state_builder);
+    let state = State::new(state_builder, Address::Account(ctx.init_origin()));
+    if let Some(mut game) = state.games.get_mut(&DEFAULT_GAME_ID) {
+        game.created_at = ctx.metadata().slot_time();
+    }
 
     Ok(state)
 }
@@ -204,76 +1243,580 @@ fn contract_state_initialize<S: HasStateApi>(
     Ok(())
 }
 
-// Simple helper functions to ensure that a call comes from the implementation
-// or the proxy.
+/// Helper function to compute the Elo rating change of a match for a player
+/// with the given K-factor.
+fn elo_delta(winner_elo: i32, loser_elo: i32, k_factor: i32) -> i32 {
+    let diff = loser_elo - winner_elo;
+    let index = ((diff.abs() / ELO_TABLE_STEP) as usize).min(ELO_EXPECTED_SCORES.len() - 1);
+    // Expected score of the winner in per mille.
+    let expected = if diff >= 0 {
+        ELO_EXPECTED_SCORES[index]
+    } else {
+        1000 - ELO_EXPECTED_SCORES[index]
+    };
 
-fn only_implementation(
-    implementation_address: ContractAddress,
-    sender: Address,
+    (k_factor * (1000 - expected) + 500) / 1000
+}
+
+/// Helper function to get the K-factor of a player.
+fn k_factor(elo_params: &EloParams, player_data: &PlayerData) -> i32 {
+    if player_data.wins.saturating_add(player_data.losses) < elo_params.provisional_matches {
+        elo_params.provisional_k
+    } else {
+        elo_params.established_k
+    }
+}
+
+/// Helper function to increment a win/loss counter, rejecting instead of
+/// wrapping around on overflow.
+fn checked_increment(counter: u32) -> ContractResult<u32> {
+    counter.checked_add(1).ok_or(CustomContractError::ArithmeticOverflow)
+}
+
+/// Helper function to ensure that the account that started the transaction is
+/// a referee.
+fn ensure_referee<S: HasStateApi>(
+    state: &State<S>,
+    ctx: &impl HasReceiveContext,
 ) -> ContractResult<()> {
     ensure!(
-        sender.matches_contract(&implementation_address),
-        CustomContractError::OnlyImplementation
+        state.referees.contains(&Address::Account(ctx.invoker())),
+        CustomContractError::NotReferee
     );
-
     Ok(())
 }
 
-fn only_proxy(
-    proxy_address: ContractAddress,
-    sender: Address
+/// Helper function to ensure that results are submitted by a referee if
+/// `referee_only` is set.
+fn ensure_referee_if_required<S: HasStateApi>(
+    state: &State<S>,
+    ctx: &impl HasReceiveContext,
 ) -> ContractResult<()> {
-    ensure!(
-        sender.matches_contract(&proxy_address),
-        CustomContractError::OnlyProxy
-    );
+    if state.referee_only {
+        ensure_referee(state, ctx)?;
+    }
+    Ok(())
+}
 
+/// Helper function to ensure that a game was created.
+fn ensure_game_exists<S: HasStateApi>(state: &State<S>, game_id: GameId) -> ContractResult<()> {
+    ensure!(game_id < state.next_game_id, CustomContractError::GameNotFound);
     Ok(())
 }
 
-/// Helper function to get protocol addresses from the state contract.
-fn get_protocol_addresses_from_state<S>(
-    host: &impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<(ContractAddress, ContractAddress)> {
-    if let ProtocolAddressesState::Initialized {
-        proxy_address,
-        implementation_address,
-    } = host.state().protocol_addresses
-    {
-        Ok((proxy_address, implementation_address))
-    } else {
-        bail!(CustomContractError::UnInitialized);
+/// Helper function to keep the number of players of a game in sync when a
+/// player is added to or removed from it.
+fn update_game_player_count<S: HasStateApi>(
+    state: &mut State<S>,
+    game_id: GameId,
+    added: bool,
+) -> ContractResult<()> {
+    if let Some(mut game) = state.games.get_mut(&game_id) {
+        game.player_count = if added {
+            checked_increment(game.player_count)?
+        } else {
+            game.player_count.saturating_sub(1)
+        };
     }
-}
 
-// Getter and setter functions
+    Ok(())
+}
 
-/// Set implementation_address. Only the proxy can invoke this function.
-/// The admin on the proxy will initiate the `updateImplementation` function on
-/// the proxy which will invoke this function.
-#[receive(
-    contract = "Versus-State",
-    name = "setImplementationAddress",
-    parameter = "SetImplementationAddressParams",
-    error = "CustomContractError",
-    mutable
-)]
-fn contract_state_set_implementation_address<S: HasStateApi>(
-    ctx: &impl HasReceiveContext,
-    host: &mut impl HasHost<State<S>, StateApiType = S>,
+/// Helper function to remember the client reference of a battle result
+/// submission. A reference that was seen before is rejected. Only the most
+/// recent `MAX_CLIENT_REFS` references are kept to cap the storage.
+fn record_client_ref<S: HasStateApi>(
+    state: &mut State<S>,
+    client_ref: [u8; 32],
 ) -> ContractResult<()> {
-    let (proxy_address, _implementation_address) = get_protocol_addresses_from_state(host)?;
+    ensure!(state.client_refs.insert(client_ref), CustomContractError::DuplicateSubmission);
 
-    // Only proxy can update the implementation address.
-    only_proxy(proxy_address, ctx.sender())?;
+    let position = state.next_client_ref;
+    state.client_ref_order.insert(position, client_ref);
+    state.next_client_ref = position + 1;
+    if position >= MAX_CLIENT_REFS {
+        if let Some(oldest) = state.client_ref_order.remove_and_get(&(position - MAX_CLIENT_REFS)) {
+            state.client_refs.remove(&oldest);
+        }
+    }
 
-    // Set implementation address.
-    let params: SetImplementationAddressParams = ctx.parameter_cursor().get()?;
+    Ok(())
+}
 
-    host.state_mut().protocol_addresses = ProtocolAddressesState::Initialized {
-        proxy_address,
-        implementation_address: params.implementation_address,
-    };
+/// Helper function to pause or unpause the contract. Pausing an already paused
+/// contract keeps the time it was first paused at.
+fn set_paused<S: HasStateApi>(state: &mut State<S>, paused: bool, now: Timestamp) {
+    if !paused {
+        state.paused_at = None;
+    } else if !state.paused {
+        state.paused_at = Some(now);
+    }
+    state.paused = paused;
+}
+
+/// Helper function to remove a player from the matchmaking queue and the
+/// pairing queue of a game, so that they can not be paired in it anymore.
+fn remove_from_queues<S: HasStateApi>(state: &mut State<S>, key: &(GameId, Address)) {
+    let (game_id, player) = *key;
+    if state.queued_players.get(&game_id).is_some_and(|queued| *queued == player) {
+        state.queued_players.remove(&game_id);
+    }
+    if let Some(ticket) = state.pairing_tickets.remove_and_get(key) {
+        state.pairing_queue.remove(&ticket);
+        state.pairing_queue_length = state.pairing_queue_length.saturating_sub(1);
+    }
+}
+
+/// Helper function to keep the number of active players in sync with a state
+/// transition of a player. `NotAdded` stands for players that are added or
+/// removed.
+fn update_active_player_count<S: HasStateApi>(
+    state: &mut State<S>,
+    previous: PlayerState,
+    next: PlayerState,
+) -> ContractResult<()> {
+    match (previous == PlayerState::Active, next == PlayerState::Active) {
+        (false, true) => {
+            state.active_player_count = checked_increment(state.active_player_count)?
+        }
+        (true, false) => state.active_player_count = state.active_player_count.saturating_sub(1),
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Helper function to extend or break the streak of a player with a battle
+/// result. A win after losses starts a new win streak and the other way
+/// round, `NoResult` keeps the streak.
+fn update_streak(player_data: &mut PlayerData, result: BattleResult) -> ContractResult<()> {
+    let streak = player_data.current_streak;
+    player_data.current_streak = match result {
+        BattleResult::Win if streak > 0 => streak.checked_add(1),
+        BattleResult::Win => Some(1),
+        BattleResult::Loss if streak < 0 => streak.checked_sub(1),
+        BattleResult::Loss => Some(-1),
+        BattleResult::NoResult => Some(streak),
+    }
+    .ok_or(CustomContractError::ArithmeticOverflow)?;
+    player_data.best_streak = player_data.best_streak.max(player_data.current_streak.max(0) as u32);
+    Ok(())
+}
+
+/// Helper function to update the battle results, the win/loss counters and
+/// the Elo ratings of both players of a match. Returns the Elo rating gained
+/// by the winner and lost by the loser.
+fn record_match_result<S: HasStateApi>(
+    state: &mut State<S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+    game_id: GameId,
+    winner: Address,
+    loser: Address,
+    now: Timestamp,
+) -> ContractResult<(i32, i32)> {
+    ensure!(winner != loser, CustomContractError::InvalidMatch);
+    let keys = [(game_id, winner), (game_id, loser)];
+
+    let mut winner = state
+        .player_data
+        .get_mut(&(game_id, winner))
+        .ok_or(CustomContractError::PlayerNotFound)?;
+    let mut loser = state
+        .player_data
+        .get_mut(&(game_id, loser))
+        .ok_or(CustomContractError::PlayerNotFound)?;
+    roll_over_player(&mut winner, state.current_season);
+    roll_over_player(&mut loser, state.current_season);
+    ensure_grace_period_passed(state.battle_grace_seconds, &winner, now)?;
+    ensure_grace_period_passed(state.battle_grace_seconds, &loser, now)?;
+
+    let winner_delta = elo_delta(winner.elo, loser.elo, k_factor(&state.elo_params, &winner));
+    let loser_delta = elo_delta(winner.elo, loser.elo, k_factor(&state.elo_params, &loser));
+
+    winner.last_result = BattleResult::Win;
+    winner.wins = checked_increment(winner.wins)?;
+    winner.elo += winner_delta;
+    update_streak(&mut winner, BattleResult::Win)?;
+    winner.last_updated = now;
+
+    loser.last_result = BattleResult::Loss;
+    loser.losses = checked_increment(loser.losses)?;
+    loser.elo -= loser_delta;
+    update_streak(&mut loser, BattleResult::Loss)?;
+    loser.last_updated = now;
+    drop((winner, loser));
+
+    state.result_totals.count(BattleResult::Win)?;
+    state.result_totals.count(BattleResult::Loss)?;
+    for key in &keys {
+        update_state_root(state, crypto_primitives, key);
+    }
+
+    Ok((winner_delta, loser_delta))
+}
+
+/// Helper function to ensure that the grace period of a newly added player has
+/// passed, so that results can be recorded for them.
+fn ensure_grace_period_passed(
+    grace_seconds: u64,
+    player_data: &PlayerData,
+    now: Timestamp,
+) -> ContractResult<()> {
+    let grace = Duration::from_seconds(grace_seconds);
+    ensure!(
+        player_data.registered_at.checked_add(grace).is_some_and(|end| end <= now),
+        CustomContractError::GracePeriodActive
+    );
+    Ok(())
+}
+
+/// Helper function to check whether a pending result can still be applied:
+/// both players have to exist and their grace periods have to have passed when
+/// the result was recorded.
+fn pending_result_valid<S: HasStateApi>(state: &State<S>, result: &PendingResult) -> bool {
+    result.winner != result.loser
+        && [result.winner, result.loser].iter().all(|player| {
+            state.player_data.get(&(result.game_id, *player)).is_some_and(|player_data| {
+                ensure_grace_period_passed(
+                    state.battle_grace_seconds,
+                    &player_data,
+                    result.recorded_at,
+                )
+                .is_ok()
+            })
+        })
+}
+
+/// Helper function to count a submitted result in the sliding rate limit window
+/// of a player, see `RateLimit`.
+fn count_rate_limited_result(
+    rate_limit: &RateLimit,
+    player_data: &mut PlayerData,
+    now: Timestamp,
+) -> ContractResult<()> {
+    if rate_limit.max_results == 0 {
+        return Ok(());
+    }
+    let window = Duration::from_seconds(rate_limit.window_seconds);
+    let submitted = &mut player_data.rate_window.submitted;
+    submitted.retain(|time| time.checked_add(window).is_none_or(|end| end > now));
+    ensure!(
+        submitted.len() < rate_limit.max_results as usize,
+        CustomContractError::RateLimited
+    );
+    submitted.push(now);
+    Ok(())
+}
+
+/// Helper function to count a match result in the rate limit windows of both
+/// players of the match.
+fn count_rate_limited_match<S: HasStateApi>(
+    state: &mut State<S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+    game_id: GameId,
+    winner: Address,
+    loser: Address,
+    now: Timestamp,
+) -> ContractResult<()> {
+    ensure!(winner != loser, CustomContractError::InvalidMatch);
+    for player in [winner, loser] {
+        let mut player_data = state
+            .player_data
+            .get_mut(&(game_id, player))
+            .ok_or(CustomContractError::PlayerNotFound)?;
+        count_rate_limited_result(&state.result_rate_limit, &mut player_data, now)?;
+        drop(player_data);
+        update_state_root(state, crypto_primitives, &(game_id, player));
+    }
+    Ok(())
+}
+
+/// Helper function to start a new season once the current one has ended
+/// before a result is applied. With the rollover lock set, the result is
+/// rejected instead, so that it is not applied to a season it was not
+/// submitted for.
+fn roll_over_season<S: HasStateApi>(
+    state: &mut State<S>,
+    ctx: &impl HasReceiveContext,
+) -> ContractResult<()> {
+    if state.rollover_lock && ended_seasons(state, ctx)? > 0 {
+        bail!(CustomContractError::SeasonRollover);
+    }
+    apply_season_rollover(state, ctx)
+}
+
+/// Helper function to get the number of seasons that ended since the start of
+/// the current season.
+fn ended_seasons<S: HasStateApi>(
+    state: &State<S>,
+    ctx: &impl HasReceiveContext,
+) -> ContractResult<u64> {
+    if state.season_length_seconds == 0 {
+        return Ok(0);
+    }
+
+    let season_length_millis = state
+        .season_length_seconds
+        .checked_mul(1000)
+        .ok_or(CustomContractError::ArithmeticOverflow)?;
+    let elapsed = ctx
+        .metadata()
+        .slot_time()
+        .timestamp_millis()
+        .saturating_sub(state.season_start.timestamp_millis());
+    Ok(elapsed / season_length_millis)
+}
+
+/// Helper function to get the number of the season the current time falls in,
+/// including seasons that ended but were not rolled over yet.
+fn season_now<S: HasStateApi>(
+    state: &State<S>,
+    ctx: &impl HasReceiveContext,
+) -> ContractResult<u32> {
+    u32::try_from(ended_seasons(state, ctx)?)
+        .ok()
+        .and_then(|ended_seasons| state.current_season.checked_add(ended_seasons))
+        .ok_or(CustomContractError::ArithmeticOverflow)
+}
+
+/// Helper function to start a new season once the current one has ended.
+/// Seasons that passed without any recorded result are skipped. The stats of
+/// the players are not touched here, each player is reset when they are next
+/// accessed, see `roll_over_player`, so the cost does not depend on the number
+/// of players.
+fn apply_season_rollover<S: HasStateApi>(
+    state: &mut State<S>,
+    ctx: &impl HasReceiveContext,
+) -> ContractResult<()> {
+    let passed_seasons = ended_seasons(state, ctx)?;
+    if passed_seasons == 0 {
+        return Ok(());
+    }
+
+    // Cannot overflow, as `ended_seasons` checked the multiplication.
+    let season_length_millis = state.season_length_seconds * 1000;
+    let season_start = state.season_start.timestamp_millis();
+
+    state.current_season = u32::try_from(passed_seasons)
+        .ok()
+        .and_then(|passed_seasons| state.current_season.checked_add(passed_seasons))
+        .ok_or(CustomContractError::ArithmeticOverflow)?;
+    state.season_start =
+        Timestamp::from_timestamp_millis(season_start + passed_seasons * season_length_millis);
+
+    Ok(())
+}
+
+/// Helper function to reset the battle result, the counters, the Elo rating
+/// and the streaks of a player.
+fn reset_stats(player_data: &mut PlayerData) {
+    player_data.last_result = BattleResult::NoResult;
+    player_data.wins = 0;
+    player_data.losses = 0;
+    player_data.elo = ELO_INITIAL_RATING;
+    player_data.current_streak = 0;
+    player_data.best_streak = 0;
+}
+
+/// Helper function to reset the stats of a player that were recorded in an
+/// earlier season than `season`, and to stamp the player with `season`.
+fn roll_over_player(player_data: &mut PlayerData, season: u32) {
+    if player_data.season < season {
+        reset_stats(player_data);
+        player_data.season = season;
+    }
+}
+
+/// Helper function to get a copy of the data of a player with the stats of
+/// the current season.
+fn player_in_season<S: HasStateApi>(
+    state: &State<S>,
+    key: &(GameId, Address),
+) -> Option<PlayerData> {
+    let mut player_data = state.player_data.get(key)?.clone();
+    roll_over_player(&mut player_data, state.current_season);
+    Some(player_data)
+}
+
+/// Helper function to revert the win/loss counters and the Elo ratings of a
+/// match recorded with `record_match_result`.
+fn revert_match_result<S: HasStateApi>(
+    state: &mut State<S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+    game_id: GameId,
+    winner: Address,
+    loser: Address,
+    (winner_delta, loser_delta): (i32, i32),
+) -> ContractResult<()> {
+    let keys = [(game_id, winner), (game_id, loser)];
+    let mut winner = state
+        .player_data
+        .get_mut(&(game_id, winner))
+        .ok_or(CustomContractError::PlayerNotFound)?;
+    let mut loser = state
+        .player_data
+        .get_mut(&(game_id, loser))
+        .ok_or(CustomContractError::PlayerNotFound)?;
+    roll_over_player(&mut winner, state.current_season);
+    roll_over_player(&mut loser, state.current_season);
+
+    winner.wins = winner.wins.saturating_sub(1);
+    winner.elo -= winner_delta;
+
+    loser.losses = loser.losses.saturating_sub(1);
+    loser.elo += loser_delta;
+    drop((winner, loser));
+
+    let totals = &mut state.result_totals;
+    totals.wins = totals.wins.saturating_sub(1);
+    totals.losses = totals.losses.saturating_sub(1);
+    for key in &keys {
+        update_state_root(state, crypto_primitives, key);
+    }
+
+    Ok(())
+}
+
+/// Helper function to store a new pending match between two players of a
+/// game.
+fn insert_match<S: HasStateApi>(
+    state: &mut State<S>,
+    game_id: GameId,
+    player_a: Address,
+    player_b: Address,
+) -> ContractResult<MatchId> {
+    ensure!(player_a != player_b, CustomContractError::InvalidMatch);
+    ensure!(
+        state.player_data.get(&(game_id, player_a)).is_some(),
+        CustomContractError::PlayerNotFound
+    );
+    ensure!(
+        state.player_data.get(&(game_id, player_b)).is_some(),
+        CustomContractError::PlayerNotFound
+    );
+
+    let match_id = state.next_match_id;
+    state.next_match_id =
+        match_id.checked_add(1).ok_or(CustomContractError::ArithmeticOverflow)?;
+
+    state.matches.insert(match_id, Match {
+        game_id,
+        player_a,
+        player_b,
+        status: MatchStatus::Pending,
+        winner: None,
+        stake_a: Amount::zero(),
+        stake_b: Amount::zero(),
+        completed_at: None,
+        elo_deltas: (0, 0),
+    });
+    state.pending_matches.insert(match_id);
+    add_player_reference(state, player_a)?;
+    add_player_reference(state, player_b)?;
+
+    Ok(match_id)
+}
+
+/// Helper function to count a reference to a player, see `player_references`.
+fn add_player_reference<S: HasStateApi>(
+    state: &mut State<S>,
+    player: Address,
+) -> ContractResult<()> {
+    let mut references = state.player_references.entry(player).or_insert_with(|| 0);
+    *references = checked_increment(*references)?;
+    Ok(())
+}
+
+/// Helper function to release a reference to a player counted with
+/// `add_player_reference`.
+fn release_player_reference<S: HasStateApi>(state: &mut State<S>, player: Address) {
+    let remaining =
+        state.player_references.get(&player).map_or(0, |references| references.saturating_sub(1));
+    if remaining == 0 {
+        state.player_references.remove(&player);
+    } else {
+        state.player_references.insert(player, remaining);
+    }
+}
+
+// Simple helper functions to ensure that a call comes from the implementation
+// or the proxy.
+
+fn only_implementation(
+    implementation_address: ContractAddress,
+    sender: Address,
+) -> ContractResult<()> {
+    ensure!(
+        sender.matches_contract(&implementation_address),
+        CustomContractError::OnlyImplementation
+    );
+
+    Ok(())
+}
+
+fn only_proxy(
+    proxy_address: ContractAddress,
+    sender: Address
+) -> ContractResult<()> {
+    ensure!(
+        sender.matches_contract(&proxy_address),
+        CustomContractError::OnlyProxy
+    );
+
+    Ok(())
+}
+
+/// Helper function to ensure that the contract is not shut down.
+fn when_not_shut_down<S>(host: &impl HasHost<State<S>, StateApiType = S>) -> ContractResult<()> {
+    ensure!(!host.state().shutdown, CustomContractError::ContractShutDown);
+    Ok(())
+}
+
+/// Helper function to get protocol addresses from the state contract.
+fn get_protocol_addresses_from_state<S>(
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<(ContractAddress, ContractAddress)> {
+    if let ProtocolAddressesState::Initialized {
+        proxy_address,
+        implementation_address,
+    } = host.state().protocol_addresses
+    {
+        Ok((proxy_address, implementation_address))
+    } else {
+        bail!(CustomContractError::UnInitialized);
+    }
+}
+
+// Getter and setter functions
+
+/// Set implementation_address. Only the proxy can invoke this function.
+/// The admin on the proxy will initiate the `updateImplementation` function on
+/// the proxy which will invoke this function.
+#[receive(
+    contract = "Versus-State",
+    name = "setImplementationAddress",
+    parameter = "SetImplementationAddressParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_set_implementation_address<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (proxy_address, _implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only proxy can update the implementation address.
+    only_proxy(proxy_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    // Set implementation address.
+    let params: SetImplementationAddressParams = ctx.parameter_cursor().get()?;
+
+    host.state_mut().protocol_addresses = ProtocolAddressesState::Initialized {
+        proxy_address,
+        implementation_address: params.implementation_address,
+    };
 
     Ok(())
 }
@@ -290,255 +1833,6319 @@ fn contract_state_set_paused<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<State<S>, StateApiType = S>,
 ) -> ContractResult<()> {
-    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+    let (proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
 
-    // Only implementation can set state.
-    only_implementation(implementation_address, ctx.sender())?;
+    // Only implementation or proxy can set paused. The proxy pauses all
+    // contracts together with `pauseAll`.
+    if only_proxy(proxy_address, ctx.sender()).is_err() {
+        only_implementation(implementation_address, ctx.sender())?;
+    }
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
 
-    // Set paused.
+    // Set paused. A pause without a reason clears a previous reason.
     let params: SetPausedParams = ctx.parameter_cursor().get()?;
-    host.state_mut().paused = params.paused;
+    let state = host.state_mut();
+    set_paused(state, params.paused, ctx.metadata().slot_time());
+    state.pause_reason = None;
     Ok(())
 }
 
-/// Update player state.
+/// Pause the contract and record why. The reason can be at most
+/// `MAX_PAUSE_REASON_LENGTH` bytes long and is cleared on unpause.
 #[receive(
     contract = "Versus-State",
-    name = "updatePlayerState",
-    parameter = "UpdatePlayerStateParams",
+    name = "pauseWithReason",
+    parameter = "String",
     error = "CustomContractError",
     mutable
 )]
-fn contract_state_update_player_state<S: HasStateApi>(
+fn contract_state_pause_with_reason<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<State<S>, StateApiType = S>,
 ) -> ContractResult<()> {
     let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
 
-    // Only implementation can set state.
+    // Only implementation can pause with a reason.
     only_implementation(implementation_address, ctx.sender())?;
 
-    // update player state.
-    let params: UpdatePlayerStateParams = ctx.parameter_cursor().get()?;
-    let (state, _state_builder) = host.state_and_builder();
-
-    let mut player_data = state.player_data.entry(params.player).or_insert_with(|| PlayerData {
-        state:   PlayerState::Active,
-        result:  BattleResult::NoResult,
-    });
-    player_data.state = params.state;
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
 
-    // host.state_mut().player_data.entry(params.player).and_modify(|player_data| {
-    //     player_data.state = params.state
-    // })
+    let reason: String = ctx.parameter_cursor().get()?;
+    ensure!(reason.len() <= MAX_PAUSE_REASON_LENGTH, CustomContractError::PauseReasonTooLong);
 
+    let state = host.state_mut();
+    set_paused(state, true, ctx.metadata().slot_time());
+    state.pause_reason = Some(reason);
     Ok(())
 }
 
-/// Update player battle result.
+/// Shut the contract down permanently. Afterwards all mutating entrypoints
+/// reject while queries keep working. There is no way to undo the shutdown.
 #[receive(
     contract = "Versus-State",
-    name = "updateBattleResult",
-    parameter = "UpdateBattleResultParams",
+    name = "emergencyShutdown",
     error = "CustomContractError",
     mutable
 )]
-fn contract_state_update_battle_result<S: HasStateApi>(
+fn contract_state_emergency_shutdown<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<State<S>, StateApiType = S>,
 ) -> ContractResult<()> {
     let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
 
-    // Only implementation can set result.
+    // Only implementation can shut the contract down.
     only_implementation(implementation_address, ctx.sender())?;
 
-    // update player state.
-    let params: UpdateBattleResultParams = ctx.parameter_cursor().get()?;
-    let (state, _state_builder) = host.state_and_builder();
-
-    let mut player_data = state.player_data.entry(params.player).or_insert_with(|| PlayerData {
-        state:   PlayerState::Active,
-        result:  BattleResult::NoResult,
-    });
-    player_data.result = params.result;
-
-    // host.state_mut().player_data.entry(params.player).and_modify(|player_data| {
-    //     player_data.result = params.result
-    // })
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
 
+    host.state_mut().shutdown = true;
     Ok(())
 }
 
-/// Add new player with concordium id.
+/// Clear the stake of a player on a match that was still pending when the
+/// contract was shut down and return it, so that the implementation can refund
+/// it. This is the only mutation accepted after the shutdown.
 #[receive(
     contract = "Versus-State",
-    name = "addPlayer",
-    parameter = "Address",
+    name = "refundStake",
+    parameter = "RefundStakeParams",
+    return_value = "Amount",
     error = "CustomContractError",
     mutable
 )]
-fn contract_state_set_player_data<S: HasStateApi>(
+fn contract_state_refund_stake<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
     host: &mut impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<()> {
+) -> ContractResult<Amount> {
     let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
 
-    // Only implementation can set result.
+    // Only implementation can refund stakes.
     only_implementation(implementation_address, ctx.sender())?;
 
-    // add new player.
-    let params: Address = ctx.parameter_cursor().get()?;
-    let (state, _state_builder) = host.state_and_builder();
+    // Check that the contract is shut down.
+    ensure!(host.state().shutdown, CustomContractError::NotShutDown);
 
-    state.player_data.entry(params).or_insert_with(|| PlayerData {
-        state:   PlayerState::Active,
-        result:  BattleResult::NoResult,
-    });
+    let params: RefundStakeParams = ctx.parameter_cursor().get()?;
 
-    Ok(())
+    let mut game = host
+        .state_mut()
+        .matches
+        .get_mut(&params.match_id)
+        .ok_or(CustomContractError::MatchNotFound)?;
+    ensure_eq!(game.status, MatchStatus::Pending, CustomContractError::MatchNotPending);
+
+    let stake = if params.player == game.player_a {
+        &mut game.stake_a
+    } else if params.player == game.player_b {
+        &mut game.stake_b
+    } else {
+        bail!(CustomContractError::InvalidMatch);
+    };
+
+    Ok(core::mem::replace(stake, Amount::zero()))
 }
 
-/// Get paused.
+/// Set the state of newly added players. Rejects `NotAdded`, as added players
+/// have to be distinguishable from players that are not added.
 #[receive(
     contract = "Versus-State",
-    name = "getPaused",
-    return_value = "bool",
-    error = "CustomContractError"
+    name = "setDefaultPlayerState",
+    parameter = "PlayerState",
+    error = "CustomContractError",
+    mutable
 )]
-fn contract_state_get_paused<S: HasStateApi>(
-    _ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<bool> {
-    Ok(host.state().paused)
+fn contract_state_set_default_player_state<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can set state.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    // Set default player state.
+    let params: PlayerState = ctx.parameter_cursor().get()?;
+    ensure!(params != PlayerState::NotAdded, CustomContractError::InvalidDefaultPlayerState);
+    host.state_mut().default_player_state = params;
+    Ok(())
 }
 
-/// Get player data.
+/// Set the season length in seconds. The current season restarts now. A
+/// length of zero disables the rollover of seasons.
 #[receive(
     contract = "Versus-State",
-    name = "getPlayerData",
-    parameter = "Address",
-    return_value = "(PlayerState, BattleResult)",
-    error = "CustomContractError"
+    name = "setSeasonLength",
+    parameter = "u64",
+    error = "CustomContractError",
+    mutable
 )]
-fn contract_state_get_player_data<S: HasStateApi>(
+fn contract_state_set_season_length<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<(PlayerState, BattleResult)> {
-    let params: Address = ctx.parameter_cursor().get()?;
-    
-    let player_state = host.state().player_data.get(&params).unwrap().state;
-    let player_result = host.state().player_data.get(&params).unwrap().result;
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can set the season length.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let season_length_seconds: u64 = ctx.parameter_cursor().get()?;
+    ensure!(
+        season_length_seconds == 0 || season_length_seconds >= MIN_SEASON_LENGTH_SECONDS,
+        CustomContractError::SeasonTooShort
+    );
 
-    Ok((player_state, player_result))
+    let state = host.state_mut();
+    state.season_length_seconds = season_length_seconds;
+    state.season_start = ctx.metadata().slot_time();
+    Ok(())
 }
 
-/// Get player data.
+/// Set the number of completed matches a player needs to appear on the
+/// leaderboard. Zero lists all players.
 #[receive(
     contract = "Versus-State",
-    name = "isAdded",
-    parameter = "Address",
-    return_value = "bool",
-    error = "CustomContractError"
+    name = "setLeaderboardMinMatches",
+    parameter = "u32",
+    error = "CustomContractError",
+    mutable
 )]
-fn contract_state_is_added<S: HasStateApi>(
+fn contract_state_set_leaderboard_min_matches<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<bool> {
-    let params: Address = ctx.parameter_cursor().get()?;
-    
-    let player_state = host.state().player_data.get(&params).unwrap().state;
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can set the leaderboard minimum.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
 
-    Ok(player_state != PlayerState::NotAdded)
+    let leaderboard_min_matches: u32 = ctx.parameter_cursor().get()?;
+    host.state_mut().leaderboard_min_matches = leaderboard_min_matches;
+    Ok(())
 }
 
-/// Function to view state of the state contract.
+/// Set whether results are rejected once the current season has ended, until
+/// the rollover is applied with `rollOverSeason`.
 #[receive(
     contract = "Versus-State",
-    name = "view",
-    return_value = "ReturnBasicState",
-    error = "CustomContractError"
+    name = "setRolloverLock",
+    parameter = "bool",
+    error = "CustomContractError",
+    mutable
 )]
-fn contract_state_view<S: HasStateApi>(
-    _ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
-) -> ContractResult<ReturnBasicState> {
-    let (proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+fn contract_state_set_rollover_lock<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
 
-    let state = ReturnBasicState {
-        proxy_address,
-        implementation_address,
-        paused: host.state().paused,
-    };
-    Ok(state)
+    // Only implementation can set the rollover lock.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let rollover_lock: bool = ctx.parameter_cursor().get()?;
+    host.state_mut().rollover_lock = rollover_lock;
+    Ok(())
+}
+
+/// Start a new season if the current one has ended and clear the rollover lock
+/// for results of the new season. The stats of each player are reset when the
+/// player is next accessed. Returns the number of the current season.
+#[receive(
+    contract = "Versus-State",
+    name = "rollOverSeason",
+    return_value = "u32",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_roll_over_season<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<u32> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can roll over seasons.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let state = host.state_mut();
+    apply_season_rollover(state, ctx)?;
+    Ok(state.current_season)
+}
+
+/// Set the K-factors of the Elo rating update.
+#[receive(
+    contract = "Versus-State",
+    name = "setEloParams",
+    parameter = "EloParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_set_elo_params<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can set the Elo parameters.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: EloParams = ctx.parameter_cursor().get()?;
+    ensure!(
+        [params.provisional_k, params.established_k]
+            .iter()
+            .all(|k_factor| *k_factor > 0 && *k_factor <= ELO_MAX_K_FACTOR),
+        CustomContractError::InvalidEloParams
+    );
+
+    host.state_mut().elo_params = params;
+    Ok(())
+}
+
+/// Set the rating decay of inactive players.
+#[receive(
+    contract = "Versus-State",
+    name = "setDecayParams",
+    parameter = "DecayParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_set_decay_params<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can set the rating decay.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: DecayParams = ctx.parameter_cursor().get()?;
+    ensure!(
+        params.amount >= 0 && params.inactivity_seconds > 0,
+        CustomContractError::InvalidDecayParams
+    );
+
+    host.state_mut().decay_params = params;
+    Ok(())
+}
+
+/// Set the seconds after being added before results can be recorded for a
+/// player.
+#[receive(
+    contract = "Versus-State",
+    name = "setBattleGracePeriod",
+    parameter = "u64",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_set_battle_grace_period<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can set the grace period.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let battle_grace_seconds: u64 = ctx.parameter_cursor().get()?;
+
+    host.state_mut().battle_grace_seconds = battle_grace_seconds;
+    Ok(())
+}
+
+/// Set the maximum number of battle results that can be submitted for a
+/// player within a window. The limit applies to `updateBattleResult`,
+/// `recordMatch` and `completeMatch`.
+#[receive(
+    contract = "Versus-State",
+    name = "setResultRateLimit",
+    parameter = "RateLimit",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_set_result_rate_limit<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can set the rate limit.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let rate_limit: RateLimit = ctx.parameter_cursor().get()?;
+    ensure!(
+        rate_limit.max_results == 0 || rate_limit.window_seconds > 0,
+        CustomContractError::InvalidRateLimit
+    );
+    ensure!(
+        rate_limit.max_results <= MAX_RATE_LIMIT_RESULTS,
+        CustomContractError::InvalidRateLimit
+    );
+
+    host.state_mut().result_rate_limit = rate_limit;
+    Ok(())
+}
+
+/// Decay the ratings of inactive players by the configured amount, without
+/// going below the floor. Players are visited in pages of at most
+/// `MAX_PAGE_SIZE` and the key of the last visited player is returned to
+/// continue after, or `None` once all players were visited. A decay restarts
+/// the inactivity period of the player, so ratings decay at most once per
+/// period.
+#[receive(
+    contract = "Versus-State",
+    name = "decayRatings",
+    parameter = "PlayerCursorParams",
+    return_value = "Option<(GameId, Address)>",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_decay_ratings<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<Option<(GameId, Address)>> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can decay ratings.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: PlayerCursorParams = ctx.parameter_cursor().get()?;
+    let limit = params.limit.min(MAX_PAGE_SIZE);
+    let now = ctx.metadata().slot_time();
+    let state = host.state_mut();
+    let decay = state.decay_params;
+    let inactivity = Duration::from_seconds(decay.inactivity_seconds);
+
+    let mut visited = Vec::new();
+    for (key, mut player_data) in state
+        .player_data
+        .iter_mut()
+        .filter(|(key, _)| after_cursor(key, &params.after))
+        .take(limit as usize)
+    {
+        visited.push(*key);
+        roll_over_player(&mut player_data, state.current_season);
+        let inactive =
+            player_data.last_updated.checked_add(inactivity).is_some_and(|end| end <= now);
+        if decay.amount > 0 && inactive && player_data.elo > decay.floor {
+            player_data.elo = player_data.elo.saturating_sub(decay.amount).max(decay.floor);
+            player_data.last_updated = now;
+        }
+    }
+    for key in &visited {
+        update_state_root(state, crypto_primitives, key);
+    }
+
+    if visited.len() < limit as usize {
+        return Ok(None);
+    }
+    Ok(visited.last().copied())
+}
+
+/// Helper function to check whether a player comes after the `after` cursor
+/// of a page. The player map is iterated in the order of the serialized keys,
+/// so players added or removed between two pages do not shift the next page.
+fn after_cursor(key: &(GameId, Address), after: &Option<(GameId, Address)>) -> bool {
+    after.as_ref().is_none_or(|after| to_bytes(key) > to_bytes(after))
+}
+
+/// Reset the stats of a single player in a game.
+#[receive(
+    contract = "Versus-State",
+    name = "resetPlayer",
+    parameter = "GamePlayerParams",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_reset_player<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can reset players.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let key = (params.game_id, params.player);
+    let state = host.state_mut();
+    let mut player_data =
+        state.player_data.get_mut(&key).ok_or(CustomContractError::PlayerNotFound)?;
+    reset_stats(&mut player_data);
+    drop(player_data);
+    update_state_root(state, crypto_primitives, &key);
+
+    Ok(())
+}
+
+/// Reset the stats of all players, one page of at most `MAX_PAGE_SIZE` players
+/// at a time, so that a reset of many players can be spread over several
+/// transactions. The caller passes the returned key of the last reset player
+/// to continue the reset after it until it is done.
+#[receive(
+    contract = "Versus-State",
+    name = "resetAllPlayers",
+    parameter = "PlayerCursorParams",
+    return_value = "ResetProgress",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_reset_all_players<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<ResetProgress> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can reset players.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: PlayerCursorParams = ctx.parameter_cursor().get()?;
+    let limit = params.limit.min(MAX_PAGE_SIZE);
+
+    let state = host.state_mut();
+    let mut visited = Vec::new();
+    for (key, mut player_data) in state
+        .player_data
+        .iter_mut()
+        .filter(|(key, _)| after_cursor(key, &params.after))
+        .take(limit as usize)
+    {
+        visited.push(*key);
+        reset_stats(&mut player_data);
+    }
+    for key in &visited {
+        update_state_root(state, crypto_primitives, key);
+    }
+
+    Ok(ResetProgress {
+        next: visited.last().copied().or(params.after),
+        done: visited.len() < limit as usize,
+    })
+}
+
+/// Update the state of a player in a game.
+#[receive(
+    contract = "Versus-State",
+    name = "updatePlayerState",
+    parameter = "UpdatePlayerStateParams",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_update_player_state<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can set state.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    // update player state.
+    let params: UpdatePlayerStateParams = ctx.parameter_cursor().get()?;
+    let (state, _state_builder) = host.state_and_builder();
+
+    // Only players registered through `addPlayer` can be updated.
+    let key = (params.game_id, params.player);
+    let previous_state = state
+        .player_data
+        .get(&key)
+        .map(|player_data| player_data.state)
+        .ok_or(CustomContractError::PlayerNotFound)?;
+    update_active_player_count(state, previous_state, params.state)?;
+
+    // Only active players can wait to be paired.
+    if params.state != PlayerState::Active {
+        remove_from_queues(state, &key);
+    }
+
+    let mut player_data =
+        state.player_data.get_mut(&key).ok_or(CustomContractError::PlayerNotFound)?;
+    player_data.state = params.state;
+    player_data.state_history.push(params.state, ctx.metadata().slot_time());
+    drop(player_data);
+    update_state_root(state, crypto_primitives, &key);
+
+    // host.state_mut().player_data.entry(params.player).and_modify(|player_data| {
+    //     player_data.state = params.state
+    // })
+
+    Ok(())
 }
 
-// #[concordium_cfg_test]
-// mod tests {
-//     use super::*;
-//     use test_infrastructure::*;
+/// Update player battle result. Returns the wins and Elo rating of the player
+/// before and after the update. The result counts at once, regardless of the
+/// finality delay. Players have to be added to the game first.
+#[receive(
+    contract = "Versus-State",
+    name = "updateBattleResult",
+    parameter = "UpdateBattleResultParams",
+    return_value = "ResultSnapshot",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_update_battle_result<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<ResultSnapshot> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
 
-//     type ContractResult<A> = Result<A, Error>;
+    // Only implementation can set result.
+    only_implementation(implementation_address, ctx.sender())?;
 
-//     #[concordium_test]
-//     /// Test that initializing the contract succeeds with some state.
-//     fn test_init() {
-//         let ctx = TestInitContext::empty();
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
 
-//         let mut state_builder = TestStateBuilder::new();
+    // Check that the result is submitted by a referee if required.
+    ensure_referee_if_required(host.state(), ctx)?;
 
-//         let state_result = init(&ctx, &mut state_builder);
-//         state_result.expect_report("Contract initialization results in error");
-//     }
+    // update player state.
+    let params: UpdateBattleResultParams = ctx.parameter_cursor().get()?;
+    let now = ctx.metadata().slot_time();
+    let state = host.state_mut();
+    roll_over_season(state, ctx)?;
 
-//     #[concordium_test]
-//     /// Test that invoking the `receive` endpoint with the `false` parameter
-//     /// succeeds in updating the contract.
-//     fn test_throw_no_error() {
-//         let ctx = TestInitContext::empty();
+    ensure_game_exists(state, params.game_id)?;
 
-//         let mut state_builder = TestStateBuilder::new();
+    // Reject a retried submission.
+    if let Some(client_ref) = params.client_ref {
+        record_client_ref(state, client_ref)?;
+    }
 
-//         // Initializing state
-//         let initial_state = init(&ctx, &mut state_builder).expect("Initialization should pass");
+    let key = (params.game_id, params.player);
+    let season = state.current_season;
+    let mut player_data =
+        state.player_data.get_mut(&key).ok_or(CustomContractError::PlayerNotFound)?;
+    roll_over_player(&mut player_data, season);
+    ensure_grace_period_passed(state.battle_grace_seconds, &player_data, now)?;
+    count_rate_limited_result(&state.result_rate_limit, &mut player_data, now)?;
+    let (wins_before, elo_before) = (player_data.wins, player_data.elo);
+    // The last result is only updated together with the counters, so that
+    // it cannot disagree with them.
+    match params.result {
+        BattleResult::Win => player_data.wins = checked_increment(player_data.wins)?,
+        BattleResult::Loss => player_data.losses = checked_increment(player_data.losses)?,
+        BattleResult::NoResult => (),
+    }
+    if params.result != BattleResult::NoResult {
+        player_data.last_result = params.result;
+    }
+    update_streak(&mut player_data, params.result)?;
+    player_data.last_updated = now;
+    let snapshot = ResultSnapshot {
+        wins_before,
+        wins_after: player_data.wins,
+        elo_before,
+        elo_after: player_data.elo,
+    };
+    drop(player_data);
+    state.result_totals.count(params.result)?;
+    update_state_root(state, crypto_primitives, &key);
 
-//         let mut ctx = TestReceiveContext::empty();
+    Ok(snapshot)
+}
 
-//         let throw_error = false;
-//         let parameter_bytes = to_bytes(&throw_error);
-//         ctx.set_parameter(&parameter_bytes);
+/// Record the result of a match between two players. This updates the battle
+/// result and the win/loss counters of both players and moves their Elo
+/// ratings. With a finality delay the result is kept as pending result
+/// instead, until `finalizeResults` applies it after the delay.
+#[receive(
+    contract = "Versus-State",
+    name = "recordMatch",
+    parameter = "RecordMatchParams",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_record_match<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
 
-//         let mut host = TestHost::new(initial_state, state_builder);
+    // Only implementation can record a match.
+    only_implementation(implementation_address, ctx.sender())?;
 
-//         // Call the contract function.
-//         let result: ContractResult<()> = receive(&ctx, &mut host);
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
 
-//         // Check the result.
-//         claim!(result.is_ok(), "Results in rejection");
-//     }
+    // Check that the result is submitted by a referee if required.
+    ensure_referee_if_required(host.state(), ctx)?;
 
-//     #[concordium_test]
-//     /// Test that invoking the `receive` endpoint with the `true` parameter
-//     /// results in the `YourError` being thrown.
-//     fn test_throw_error() {
-//         let ctx = TestInitContext::empty();
+    let params: RecordMatchParams = ctx.parameter_cursor().get()?;
+    ensure!(params.duration_seconds > 0, CustomContractError::InvalidDuration);
+    let state = host.state_mut();
+    let now = ctx.metadata().slot_time();
 
-//         let mut state_builder = TestStateBuilder::new();
+    // The grace periods are checked when the result is recorded, so that a
+    // pending result does not fail once it is finalized.
+    for player in [params.winner, params.loser] {
+        let player_data = state
+            .player_data
+            .get(&(params.game_id, player))
+            .ok_or(CustomContractError::PlayerNotFound)?;
+        ensure_grace_period_passed(state.battle_grace_seconds, &player_data, now)?;
+    }
 
-//         // Initializing state
-//         let initial_state = init(&ctx, &mut state_builder).expect("Initialization should pass");
+    // The duration counts when the match is recorded, also if its result is
+    // still pending.
+    let durations = &mut state.match_durations;
+    durations.matches =
+        durations.matches.checked_add(1).ok_or(CustomContractError::ArithmeticOverflow)?;
+    durations.total_seconds = durations
+        .total_seconds
+        .checked_add(u64::from(params.duration_seconds))
+        .ok_or(CustomContractError::ArithmeticOverflow)?;
 
-//         let mut ctx = TestReceiveContext::empty();
+    // Results count against the rate limit when they are recorded, also if
+    // they are still pending.
+    count_rate_limited_match(
+        state,
+        crypto_primitives,
+        params.game_id,
+        params.winner,
+        params.loser,
+        now,
+    )?;
 
-//         let throw_error = true;
-//         let parameter_bytes = to_bytes(&throw_error);
-//         ctx.set_parameter(&parameter_bytes);
+    if state.finality_delay_seconds > 0 {
+        let result_id = state.next_result_id;
+        state.next_result_id =
+            result_id.checked_add(1).ok_or(CustomContractError::ArithmeticOverflow)?;
+        state.pending_results.insert(result_id, PendingResult {
+            game_id:     params.game_id,
+            winner:      params.winner,
+            loser:       params.loser,
+            recorded_at: now,
+        });
+        add_player_reference(state, params.winner)?;
+        add_player_reference(state, params.loser)?;
+        return Ok(());
+    }
 
-//         let mut host = TestHost::new(initial_state, state_builder);
+    roll_over_season(state, ctx)?;
+    record_match_result(
+        state,
+        crypto_primitives,
+        params.game_id,
+        params.winner,
+        params.loser,
+        now,
+    )?;
 
-//         // Call the contract function.
-//         let error: ContractResult<()> = receive(&ctx, &mut host);
+    Ok(())
+}
 
-//         // Check the result.
-//         claim_eq!(error, Err(Error::YourError), "Function should throw an error.");
-//     }
-// }
+/// Apply the pending results whose finality delay has passed, in the order
+/// they were recorded. At most `MAX_PAGE_SIZE` results are applied per call.
+/// Results are taken from the head of the queue, so the cost does not grow
+/// with the number of pending results. Results that can not be applied anymore,
+/// e.g. of players that were deleted in the meantime, are dropped, so that they
+/// do not block the results behind them. Returns the number of applied results.
+#[receive(
+    contract = "Versus-State",
+    name = "finalizeResults",
+    return_value = "u32",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_finalize_results<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<u32> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can finalize results.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    // Check that the results are applied by a referee if required.
+    ensure_referee_if_required(host.state(), ctx)?;
+
+    let now = ctx.metadata().slot_time();
+    let state = host.state_mut();
+    roll_over_season(state, ctx)?;
+
+    // Results are recorded in time order, so the first result that has not
+    // matured yet ends the page.
+    let delay = Duration::from_seconds(state.finality_delay_seconds);
+    let mut applied = 0;
+    let mut visited = 0;
+    while visited < MAX_PAGE_SIZE && state.pending_results_head < state.next_result_id {
+        let result_id = state.pending_results_head;
+        let result = state.pending_results.get(&result_id).map(|result| result.clone());
+        if let Some(result) = &result {
+            if result.recorded_at.checked_add(delay).is_none_or(|end| end > now) {
+                break;
+            }
+        }
+        visited += 1;
+        state.pending_results_head = result_id + 1;
+        // Skip ids without a result.
+        let result = match result {
+            Some(result) => result,
+            None => continue,
+        };
+        state.pending_results.remove(&result_id);
+        release_player_reference(state, result.winner);
+        release_player_reference(state, result.loser);
+        if pending_result_valid(state, &result) {
+            let PendingResult {
+                game_id,
+                winner,
+                loser,
+                recorded_at,
+            } = result;
+            record_match_result(state, crypto_primitives, game_id, winner, loser, recorded_at)?;
+            applied += 1;
+        }
+    }
+
+    Ok(applied)
+}
+
+/// Set the finality delay of recorded match results in seconds. Results that
+/// are already pending keep waiting for the new delay.
+#[receive(
+    contract = "Versus-State",
+    name = "setFinalityDelay",
+    parameter = "u64",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_set_finality_delay<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can set the finality delay.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let finality_delay_seconds: u64 = ctx.parameter_cursor().get()?;
+    host.state_mut().finality_delay_seconds = finality_delay_seconds;
+    Ok(())
+}
+
+/// Add a referee that may submit battle results. Adding an existing referee
+/// has no effect.
+#[receive(
+    contract = "Versus-State",
+    name = "addReferee",
+    parameter = "Address",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_add_referee<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can add referees.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let referee: Address = ctx.parameter_cursor().get()?;
+    host.state_mut().referees.insert(referee);
+    Ok(())
+}
+
+/// Remove a referee.
+#[receive(
+    contract = "Versus-State",
+    name = "removeReferee",
+    parameter = "Address",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_remove_referee<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can remove referees.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let referee: Address = ctx.parameter_cursor().get()?;
+    ensure!(host.state_mut().referees.remove(&referee), CustomContractError::NotReferee);
+    Ok(())
+}
+
+/// Set whether battle results can only be submitted by referees. The
+/// submitting account is the account that sent the transaction.
+#[receive(
+    contract = "Versus-State",
+    name = "setRefereeOnly",
+    parameter = "bool",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_set_referee_only<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can require referees.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let referee_only: bool = ctx.parameter_cursor().get()?;
+    host.state_mut().referee_only = referee_only;
+    Ok(())
+}
+
+/// Set whether only accounts can be added as players. Players that are
+/// already added are kept.
+#[receive(
+    contract = "Versus-State",
+    name = "setAccountsOnly",
+    parameter = "bool",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_set_accounts_only<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can restrict players to accounts.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let accounts_only: bool = ctx.parameter_cursor().get()?;
+    host.state_mut().accounts_only = accounts_only;
+    Ok(())
+}
+
+/// Create a new pending match between two players and return its id.
+#[receive(
+    contract = "Versus-State",
+    name = "createMatch",
+    parameter = "CreateMatchParams",
+    return_value = "MatchId",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_create_match<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<MatchId> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can create a match.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: CreateMatchParams = ctx.parameter_cursor().get()?;
+
+    insert_match(host.state_mut(), params.game_id, params.player_a, params.player_b)
+}
+
+/// Complete a pending match. This records the result of the match for both
+/// players like `recordMatch` and returns the total amount staked on the
+/// match, which is owed to the winner. The stakes move with the result, so it
+/// has to be reported by a referee, whether or not `referee_only` is set.
+/// Unlike `recordMatch`, the result counts at once, regardless of the finality
+/// delay, as the match can be disputed instead. The winner is only rewarded
+/// for the first match of the two players within `REWARD_PAIR_COOLDOWN_HOURS`.
+#[receive(
+    contract = "Versus-State",
+    name = "completeMatch",
+    parameter = "CompleteMatchParams",
+    return_value = "CompletedMatch",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_complete_match<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<CompletedMatch> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can complete a match.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    // Check that the result is reported by a referee.
+    ensure_referee(host.state(), ctx)?;
+
+    let params: CompleteMatchParams = ctx.parameter_cursor().get()?;
+    let slot_time = ctx.metadata().slot_time();
+    let state = host.state_mut();
+    roll_over_season(state, ctx)?;
+
+    let (game_id, loser, pot) = {
+        let mut game =
+            state.matches.get_mut(&params.match_id).ok_or(CustomContractError::MatchNotFound)?;
+        ensure_eq!(game.status, MatchStatus::Pending, CustomContractError::MatchNotPending);
+
+        let loser = if params.winner == game.player_a {
+            game.player_b
+        } else if params.winner == game.player_b {
+            game.player_a
+        } else {
+            bail!(CustomContractError::InvalidMatch);
+        };
+        game.status = MatchStatus::Completed;
+        game.winner = Some(params.winner);
+        game.completed_at = Some(slot_time);
+        (game.game_id, loser, game.stake_a + game.stake_b)
+    };
+    state.pending_matches.remove(&params.match_id);
+    release_player_reference(state, params.winner);
+    release_player_reference(state, loser);
+
+    count_rate_limited_match(state, crypto_primitives, game_id, params.winner, loser, slot_time)?;
+    let elo_deltas =
+        record_match_result(state, crypto_primitives, game_id, params.winner, loser, slot_time)?;
+    state.matches.get_mut(&params.match_id).ok_or(CustomContractError::MatchNotFound)?.elo_deltas =
+        elo_deltas;
+
+    let pair = if params.winner < loser {
+        (params.winner, loser)
+    } else {
+        (loser, params.winner)
+    };
+    let cooldown = Duration::from_hours(REWARD_PAIR_COOLDOWN_HOURS);
+    let rewardable = match state.pair_rewarded_at.get(&pair) {
+        Some(rewarded_at) => rewarded_at.checked_add(cooldown).is_some_and(|end| slot_time > end),
+        None => true,
+    };
+    if rewardable {
+        state.pair_rewarded_at.insert(pair, slot_time);
+    }
+
+    Ok(CompletedMatch {
+        pot,
+        rewardable,
+    })
+}
+
+/// Dispute the result of a completed match. Only players of the match can
+/// dispute it and only within `DISPUTE_WINDOW_HOURS` after its completion.
+#[receive(
+    contract = "Versus-State",
+    name = "disputeMatch",
+    parameter = "DisputeMatchParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_dispute_match<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can dispute a match.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: DisputeMatchParams = ctx.parameter_cursor().get()?;
+    let state = host.state_mut();
+
+    let mut game =
+        state.matches.get_mut(&params.match_id).ok_or(CustomContractError::MatchNotFound)?;
+    ensure_eq!(game.status, MatchStatus::Completed, CustomContractError::MatchNotCompleted);
+    ensure!(
+        params.player == game.player_a || params.player == game.player_b,
+        CustomContractError::InvalidMatch
+    );
+
+    let dispute_window = Duration::from_hours(DISPUTE_WINDOW_HOURS);
+    let window_end = game
+        .completed_at
+        .and_then(|completed_at| completed_at.checked_add(dispute_window))
+        .ok_or(CustomContractError::DisputeWindowClosed)?;
+    ensure!(ctx.metadata().slot_time() <= window_end, CustomContractError::DisputeWindowClosed);
+
+    game.status = MatchStatus::Disputed;
+    let (game_id, players) = (game.game_id, [game.player_a, game.player_b]);
+    drop(game);
+
+    // The result of a deleted player can not be overturned anymore.
+    for player in players {
+        ensure!(
+            state.player_data.get(&(game_id, player)).is_some(),
+            CustomContractError::PlayerNotFound
+        );
+        add_player_reference(state, player)?;
+    }
+
+    Ok(())
+}
+
+/// Resolve a disputed match by either upholding or overturning its result.
+/// Overturning reverts the win/loss counters and the Elo ratings of the
+/// recorded result and records the opposite result instead. Stakes that were
+/// already paid out are not moved. A resolved match can not be disputed again.
+#[receive(
+    contract = "Versus-State",
+    name = "resolveDispute",
+    parameter = "ResolveDisputeParams",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_resolve_dispute<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can resolve a dispute.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: ResolveDisputeParams = ctx.parameter_cursor().get()?;
+    let state = host.state_mut();
+
+    let (game_id, winner, loser, elo_deltas, completed_at) = {
+        let mut game =
+            state.matches.get_mut(&params.match_id).ok_or(CustomContractError::MatchNotFound)?;
+        ensure_eq!(game.status, MatchStatus::Disputed, CustomContractError::MatchNotDisputed);
+        game.status = MatchStatus::Resolved;
+
+        let winner = game.winner.ok_or(CustomContractError::InvalidMatch)?;
+        let loser = if winner == game.player_a {
+            game.player_b
+        } else {
+            game.player_a
+        };
+        let completed_at = game.completed_at.ok_or(CustomContractError::MatchNotCompleted)?;
+        (game.game_id, winner, loser, game.elo_deltas, completed_at)
+    };
+    release_player_reference(state, winner);
+    release_player_reference(state, loser);
+
+    if params.overturn {
+        revert_match_result(state, crypto_primitives, game_id, winner, loser, elo_deltas)?;
+        let elo_deltas =
+            record_match_result(state, crypto_primitives, game_id, loser, winner, completed_at)?;
+
+        let mut game =
+            state.matches.get_mut(&params.match_id).ok_or(CustomContractError::MatchNotFound)?;
+        game.winner = Some(loser);
+        game.elo_deltas = elo_deltas;
+    }
+
+    Ok(())
+}
+
+/// Add a stake of a player to a pending match.
+#[receive(
+    contract = "Versus-State",
+    name = "stakeMatch",
+    parameter = "StakeMatchParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_stake_match<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can record stakes.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: StakeMatchParams = ctx.parameter_cursor().get()?;
+
+    let mut game = host
+        .state_mut()
+        .matches
+        .get_mut(&params.match_id)
+        .ok_or(CustomContractError::MatchNotFound)?;
+    ensure_eq!(game.status, MatchStatus::Pending, CustomContractError::MatchNotPending);
+    // The pot can only be paid out if the winner is an account.
+    ensure!(
+        matches!((game.player_a, game.player_b), (Address::Account(_), Address::Account(_))),
+        CustomContractError::OnlyAccount
+    );
+
+    let stake = if params.player == game.player_a {
+        &mut game.stake_a
+    } else if params.player == game.player_b {
+        &mut game.stake_b
+    } else {
+        bail!(CustomContractError::InvalidMatch);
+    };
+    *stake = stake.checked_add(params.amount).ok_or(CustomContractError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// Add a stake of a player of a tournament to the prize pool of the
+/// tournament. Stakes can only be added until the tournament is completed.
+#[receive(
+    contract = "Versus-State",
+    name = "stakeTournament",
+    parameter = "StakeTournamentParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_stake_tournament<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can record stakes.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: StakeTournamentParams = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    let mut tournament = state
+        .tournaments
+        .get_mut(&params.tournament_id)
+        .ok_or(CustomContractError::TournamentNotFound)?;
+    ensure_eq!(
+        tournament.status,
+        TournamentStatus::InProgress,
+        CustomContractError::TournamentCompleted
+    );
+    ensure!(tournament.players.contains(&params.player), CustomContractError::NotInTournament);
+    tournament.pool =
+        tournament.pool.checked_add(params.amount).ok_or(CustomContractError::ArithmeticOverflow)?;
+
+    let key = (params.tournament_id, params.player);
+    let mut stake = state.tournament_stakes.entry(key).or_insert_with(Amount::zero);
+    *stake = stake.checked_add(params.amount).ok_or(CustomContractError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// Add an active player to the matchmaking queue of a game. If another player
+/// is already waiting in the game, both are paired in a new match of the game
+/// and its id is returned. Otherwise the player waits in the queue and `None`
+/// is returned.
+#[receive(
+    contract = "Versus-State",
+    name = "joinQueue",
+    parameter = "GamePlayerParams",
+    return_value = "Option<MatchId>",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_join_queue<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Option<MatchId>> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can queue players.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let state = host.state_mut();
+
+    let player_state = state
+        .player_data
+        .get(&(params.game_id, params.player))
+        .ok_or(CustomContractError::PlayerNotFound)?
+        .state;
+    ensure_eq!(player_state, PlayerState::Active, CustomContractError::PlayerNotActive);
+
+    let queued_player = state.queued_players.get(&params.game_id).map(|player| *player);
+    match queued_player {
+        Some(queued_player) if queued_player == params.player => {
+            bail!(CustomContractError::AlreadyQueued)
+        }
+        Some(queued_player) => {
+            state.queued_players.remove(&params.game_id);
+            insert_match(state, params.game_id, queued_player, params.player).map(Some)
+        }
+        None => {
+            state.queued_players.insert(params.game_id, params.player);
+            Ok(None)
+        }
+    }
+}
+
+/// Remove the waiting player from the matchmaking queue of a game.
+#[receive(
+    contract = "Versus-State",
+    name = "leaveQueue",
+    parameter = "GamePlayerParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_leave_queue<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can remove players from the queue.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let state = host.state_mut();
+
+    ensure!(
+        state.queued_players.get(&params.game_id).is_some_and(|queued| *queued == params.player),
+        CustomContractError::NotQueued
+    );
+    state.queued_players.remove(&params.game_id);
+
+    Ok(())
+}
+
+/// Add an active player to the pairing queue, where they wait until they are
+/// paired with another player of the same game by `pairPlayers`.
+#[receive(
+    contract = "Versus-State",
+    name = "joinPairingQueue",
+    parameter = "GamePlayerParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_join_pairing_queue<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can queue players.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let key = (params.game_id, params.player);
+    let state = host.state_mut();
+
+    let player_state =
+        state.player_data.get(&key).ok_or(CustomContractError::PlayerNotFound)?.state;
+    ensure_eq!(player_state, PlayerState::Active, CustomContractError::PlayerNotActive);
+    ensure!(state.pairing_tickets.get(&key).is_none(), CustomContractError::AlreadyQueued);
+
+    let ticket = state.pairing_queue_tail;
+    state.pairing_queue_tail =
+        ticket.checked_add(1).ok_or(CustomContractError::ArithmeticOverflow)?;
+    state.pairing_queue.insert(ticket, key);
+    state.pairing_tickets.insert(key, ticket);
+    state.pairing_queue_length = checked_increment(state.pairing_queue_length)?;
+
+    Ok(())
+}
+
+/// Remove a waiting player from the pairing queue of a game.
+#[receive(
+    contract = "Versus-State",
+    name = "leavePairingQueue",
+    parameter = "GamePlayerParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_leave_pairing_queue<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can remove players from the queue.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let state = host.state_mut();
+
+    let ticket = state
+        .pairing_tickets
+        .remove_and_get(&(params.game_id, params.player))
+        .ok_or(CustomContractError::NotQueued)?;
+    state.pairing_queue.remove(&ticket);
+    state.pairing_queue_length = state.pairing_queue_length.saturating_sub(1);
+
+    Ok(())
+}
+
+/// Pair the players waiting in the pairing queue in new matches of their game
+/// and return their ids. Up to `MAX_PAGE_SIZE` players are taken from the
+/// front of the queue, and the players of each game are shuffled with a seed
+/// derived from the slot time and the next match id, so that every pairing
+/// can be reproduced from the block it was made in. A player is only taken
+/// together with another player of the same game, so if an odd number of
+/// players of a game is waiting, the last one keeps waiting at the front of
+/// the queue. At most `MAX_PAIRING_QUEUE_SCAN` tickets are looked at per call,
+/// the next call continues after them.
+#[receive(
+    contract = "Versus-State",
+    name = "pairPlayers",
+    return_value = "Vec<MatchId>",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_pair_players<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<Vec<MatchId>> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can pair players.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let state = host.state_mut();
+
+    // Collect the waiting players of each game in ticket order, skipping
+    // players that left. A player is only taken once another player of their
+    // game is found.
+    let mut games: collections::BTreeMap<GameId, Vec<Address>> = collections::BTreeMap::new();
+    let mut unpaired: collections::BTreeMap<GameId, (u64, Address)> = collections::BTreeMap::new();
+    let mut taken = 0;
+    let mut head = state.pairing_queue_head;
+    let scan_end = state.pairing_queue_tail.min(head.saturating_add(MAX_PAIRING_QUEUE_SCAN));
+    while head < scan_end && taken < MAX_PAGE_SIZE as usize {
+        if let Some((game_id, player)) = state.pairing_queue.get(&head).map(|key| *key) {
+            match unpaired.remove(&game_id) {
+                Some((ticket, other)) => {
+                    for (ticket, player) in [(ticket, other), (head, player)] {
+                        state.pairing_queue.remove(&ticket);
+                        state.pairing_tickets.remove(&(game_id, player));
+                    }
+                    games.entry(game_id).or_default().extend([other, player]);
+                    taken += 2;
+                }
+                None => {
+                    unpaired.insert(game_id, (head, player));
+                }
+            }
+        }
+        head += 1;
+    }
+    // Players without a partner keep waiting at the front of the queue.
+    if let Some(ticket) = unpaired.values().map(|(ticket, _player)| *ticket).min() {
+        head = head.min(ticket);
+    }
+    state.pairing_queue_head = head;
+    state.pairing_queue_length = state.pairing_queue_length.saturating_sub(taken as u32);
+
+    let mut match_ids = Vec::with_capacity(taken / 2);
+    for (game_id, mut players) in games {
+        let seed = crypto_primitives
+            .hash_sha2_256(&to_bytes(&(ctx.metadata().slot_time(), state.next_match_id)));
+        shuffle_players(crypto_primitives, &seed, &mut players);
+        for pair in players.chunks(2) {
+            match_ids.push(insert_match(state, game_id, pair[0], pair[1])?);
+        }
+    }
+
+    Ok(match_ids)
+}
+
+/// Cancel a pending match without recording a result. Returns the cancelled
+/// match, so that the stakes can be refunded.
+#[receive(
+    contract = "Versus-State",
+    name = "cancelMatch",
+    parameter = "MatchId",
+    return_value = "Match",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_cancel_match<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Match> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can cancel a match.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let match_id: MatchId = ctx.parameter_cursor().get()?;
+    let state = host.state_mut();
+
+    let game = {
+        let mut game = state.matches.get_mut(&match_id).ok_or(CustomContractError::MatchNotFound)?;
+        ensure_eq!(game.status, MatchStatus::Pending, CustomContractError::MatchNotPending);
+        game.status = MatchStatus::Cancelled;
+        game.clone()
+    };
+    state.pending_matches.remove(&match_id);
+    release_player_reference(state, game.player_a);
+    release_player_reference(state, game.player_b);
+
+    Ok(game)
+}
+
+/// Create a new game with its own players and an optional name and return its
+/// id.
+#[receive(
+    contract = "Versus-State",
+    name = "createGame",
+    parameter = "Option<String>",
+    return_value = "GameId",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_create_game<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<GameId> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can create a game.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let name: Option<String> = ctx.parameter_cursor().get()?;
+    ensure!(
+        name.as_ref().is_none_or(|name| name.len() <= MAX_GAME_NAME_LENGTH),
+        CustomContractError::GameNameTooLong
+    );
+
+    let state = host.state_mut();
+    let game_id = state.next_game_id;
+    state.next_game_id = checked_increment(game_id)?;
+    state.games.insert(game_id, GameInfo {
+        name,
+        created_at: ctx.metadata().slot_time(),
+        player_count: 0,
+    });
+
+    Ok(game_id)
+}
+
+/// Helper function to pair the players of a tournament round in order.
+fn pair_bracket(players: &[Address]) -> Vec<BracketMatch> {
+    players
+        .chunks(2)
+        .map(|pair| BracketMatch {
+            player_a: pair[0],
+            player_b: pair[1],
+            winner:   None,
+        })
+        .collect()
+}
+
+/// Create a single-elimination tournament of players of a game and return its
+/// id. The number of players has to be a power of two and the
+/// payout split has to add up to `PAYOUT_SPLIT_TOTAL`.
+#[receive(
+    contract = "Versus-State",
+    name = "createTournament",
+    parameter = "CreateTournamentParams",
+    return_value = "TournamentId",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_create_tournament<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<TournamentId> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can create a tournament.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let CreateTournamentParams {
+        game_id,
+        players,
+        payout_split,
+    } = ctx.parameter_cursor().get()?;
+    ensure!(
+        players.len() >= 2
+            && players.len() <= MAX_TOURNAMENT_PLAYERS
+            && players.len().is_power_of_two(),
+        CustomContractError::InvalidTournament
+    );
+    ensure!(
+        players.iter().enumerate().all(|(index, player)| !players[..index].contains(player)),
+        CustomContractError::InvalidTournament
+    );
+    ensure!(
+        payout_split.len() <= players.len()
+            && payout_split.iter().map(|share| u32::from(*share)).sum::<u32>()
+                == u32::from(PAYOUT_SPLIT_TOTAL),
+        CustomContractError::InvalidPayoutSplit
+    );
+
+    let state = host.state_mut();
+    for player in &players {
+        ensure!(
+            state.player_data.get(&(game_id, *player)).is_some(),
+            CustomContractError::PlayerNotFound
+        );
+    }
+
+    for player in &players {
+        add_player_reference(state, *player)?;
+    }
+
+    let tournament_id = state.next_tournament_id;
+    state.next_tournament_id =
+        tournament_id.checked_add(1).ok_or(CustomContractError::ArithmeticOverflow)?;
+    state.tournaments.insert(tournament_id, Tournament {
+        id: tournament_id,
+        game_id,
+        rounds: vec![pair_bracket(&players)],
+        players,
+        status: TournamentStatus::InProgress,
+        payout_split,
+        prizes_distributed: false,
+        pool: Amount::zero(),
+    });
+
+    Ok(tournament_id)
+}
+
+/// Report the result of a match of the current round of a tournament. Both
+/// players have to be paired in the current round and the result of their
+/// match must not be reported yet. Results are reported by referees.
+#[receive(
+    contract = "Versus-State",
+    name = "reportTournamentMatch",
+    parameter = "ReportTournamentMatchParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_report_tournament_match<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can report tournament matches.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    // Check that the result is reported by a referee.
+    ensure_referee(host.state(), ctx)?;
+
+    let params: ReportTournamentMatchParams = ctx.parameter_cursor().get()?;
+    ensure!(params.winner != params.loser, CustomContractError::InvalidMatch);
+
+    let mut tournament = host
+        .state_mut()
+        .tournaments
+        .get_mut(&params.tournament_id)
+        .ok_or(CustomContractError::TournamentNotFound)?;
+    ensure_eq!(
+        tournament.status,
+        TournamentStatus::InProgress,
+        CustomContractError::TournamentCompleted
+    );
+
+    let round = tournament.rounds.last_mut().ok_or(CustomContractError::InvalidTournament)?;
+    let pairing = round
+        .iter_mut()
+        .find(|pairing| {
+            let players = [pairing.player_a, pairing.player_b];
+            players.contains(&params.winner) && players.contains(&params.loser)
+        })
+        .ok_or(CustomContractError::NotInCurrentRound)?;
+    ensure!(pairing.winner.is_none(), CustomContractError::PlayerEliminated);
+    pairing.winner = Some(params.winner);
+
+    Ok(())
+}
+
+/// Advance a tournament to the next round once all results of the current
+/// round are reported. The winners are paired in the order of their matches.
+/// After the final, the tournament is completed with its winner as champion.
+/// Only referees can advance a tournament.
+#[receive(
+    contract = "Versus-State",
+    name = "advanceRound",
+    parameter = "TournamentId",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_advance_round<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can advance tournaments.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    // Check that the round is advanced by a referee.
+    ensure_referee(host.state(), ctx)?;
+
+    let tournament_id: TournamentId = ctx.parameter_cursor().get()?;
+    let mut tournament = host
+        .state_mut()
+        .tournaments
+        .get_mut(&tournament_id)
+        .ok_or(CustomContractError::TournamentNotFound)?;
+    ensure_eq!(
+        tournament.status,
+        TournamentStatus::InProgress,
+        CustomContractError::TournamentCompleted
+    );
+
+    let winners = tournament
+        .rounds
+        .last()
+        .ok_or(CustomContractError::InvalidTournament)?
+        .iter()
+        .map(|pairing| pairing.winner)
+        .collect::<Option<Vec<Address>>>()
+        .ok_or(CustomContractError::RoundNotComplete)?;
+    if let [champion] = winners[..] {
+        tournament.status = TournamentStatus::Completed {
+            champion,
+        };
+    } else {
+        tournament.rounds.push(pair_bracket(&winners));
+    }
+
+    Ok(())
+}
+
+/// Mark the prizes of a completed tournament as distributed and return the top
+/// finishers with their prize, their share of the prize pool of the
+/// tournament. The champion finishes first and the runner-up second, players
+/// eliminated in the same earlier round are ranked in the order of their
+/// matches. Rounding remainders stay in the pool of the tournament and the
+/// stakes of its players are cleared.
+#[receive(
+    contract = "Versus-State",
+    name = "distributePrizes",
+    parameter = "TournamentId",
+    return_value = "Vec<(Address, Amount)>",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_distribute_prizes<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Vec<(Address, Amount)>> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can distribute prizes.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let tournament_id: TournamentId = ctx.parameter_cursor().get()?;
+    let state = host.state_mut();
+    let mut tournament = state
+        .tournaments
+        .get_mut(&tournament_id)
+        .ok_or(CustomContractError::TournamentNotFound)?;
+    let champion = match tournament.status {
+        TournamentStatus::Completed {
+            champion,
+        } => champion,
+        TournamentStatus::InProgress => bail!(CustomContractError::TournamentNotCompleted),
+    };
+    ensure!(!tournament.prizes_distributed, CustomContractError::PrizesAlreadyDistributed);
+    tournament.prizes_distributed = true;
+
+    let losers = tournament.rounds.iter().rev().flatten().filter_map(|pairing| {
+        match pairing.winner {
+            Some(winner) if winner == pairing.player_a => Some(pairing.player_b),
+            Some(_) => Some(pairing.player_a),
+            None => None,
+        }
+    });
+    let finishers = core::iter::once(champion).chain(losers);
+
+    let pool = tournament.pool;
+    let prizes: Vec<(Address, Amount)> = finishers
+        .zip(tournament.payout_split.iter())
+        .map(|(finisher, share)| {
+            let prize = u128::from(pool.micro_ccd) * u128::from(*share)
+                / u128::from(PAYOUT_SPLIT_TOTAL);
+            (finisher, Amount::from_micro_ccd(prize as u64))
+        })
+        .collect();
+    for (_finisher, prize) in &prizes {
+        tournament.pool -= *prize;
+    }
+    let players = tournament.players.clone();
+    drop(tournament);
+    for player in players {
+        state.tournament_stakes.remove(&(tournament_id, player));
+        release_player_reference(state, player);
+    }
+
+    Ok(prizes)
+}
+
+/// Add new player with concordium id to a game.
+#[receive(
+    contract = "Versus-State",
+    name = "addPlayer",
+    parameter = "GamePlayerParams",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_set_player_data<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can set result.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    // add new player.
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let (state, _state_builder) = host.state_and_builder();
+    let default_player_state = state.default_player_state;
+    ensure_game_exists(state, params.game_id)?;
+
+    // Check that the player is an account if contracts are not allowed.
+    if state.accounts_only {
+        ensure!(
+            matches!(params.player, Address::Account(_)),
+            CustomContractError::ContractPlayerNotAllowed
+        );
+    }
+
+    let key = (params.game_id, params.player);
+    if state.player_data.get(&key).is_none() {
+        update_active_player_count(state, PlayerState::NotAdded, default_player_state)?;
+        update_game_player_count(state, params.game_id, true)?;
+        let season = season_now(state, ctx)?;
+        let mut players_added = state.players_added.entry(season).or_insert_with(|| 0);
+        *players_added = checked_increment(*players_added)?;
+    }
+
+    let now = ctx.metadata().slot_time();
+    let season = state.current_season;
+    state.player_data.entry(key).or_insert_with(|| PlayerData {
+        version:        PLAYER_DATA_VERSION,
+        state:          default_player_state,
+        last_result:    BattleResult::NoResult,
+        wins:           0,
+        losses:         0,
+        elo:            ELO_INITIAL_RATING,
+        state_history:  StateHistory::new(),
+        current_streak: 0,
+        best_streak:    0,
+        last_updated:   now,
+        registered_at:  now,
+        rate_window:    RateWindow::new(),
+        season,
+    });
+    update_state_root(state, crypto_primitives, &key);
+
+    Ok(())
+}
+
+/// Remove all data stored for a player in every game. Rejects while the player
+/// is part of a pending match, a disputed match, a pending result or a
+/// tournament without distributed prizes, so that these can still be settled.
+/// Completed, cancelled and resolved matches, tournaments with distributed
+/// prizes and the reward cooldowns of pairs keep the address of the player as
+/// a record of past results.
+#[receive(
+    contract = "Versus-State",
+    name = "forceDeletePlayer",
+    parameter = "Address",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_force_delete_player<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can delete players.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: Address = ctx.parameter_cursor().get()?;
+    let state = host.state_mut();
+    ensure!(
+        state.player_references.get(&params).is_none(),
+        CustomContractError::PlayerReferenced
+    );
+
+    let mut found = false;
+    for game_id in DEFAULT_GAME_ID..state.next_game_id {
+        if let Some(player_data) = state.player_data.remove_and_get(&(game_id, params)) {
+            update_active_player_count(state, player_data.state, PlayerState::NotAdded)?;
+            update_game_player_count(state, game_id, false)?;
+            update_state_root(state, crypto_primitives, &(game_id, params));
+            // A deleted player can not be paired anymore.
+            remove_from_queues(state, &(game_id, params));
+            found = true;
+        }
+    }
+    ensure!(found, CustomContractError::PlayerNotFound);
+
+    Ok(())
+}
+
+/// Enable or disable the data fix mode, in which the state admin can write
+/// player data directly with `adminSetPlayerData`. Only the state admin can
+/// call this function.
+#[receive(
+    contract = "Versus-State",
+    name = "setDataFixMode",
+    parameter = "bool",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_state_set_data_fix_mode<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<()> {
+    // Only the state admin can toggle the data fix mode.
+    ensure_eq!(ctx.sender(), host.state().state_admin, CustomContractError::OnlyStateAdmin);
+
+    let data_fix_mode: bool = ctx.parameter_cursor().get()?;
+    host.state_mut().data_fix_mode = data_fix_mode;
+    Ok(())
+}
+
+/// Write the complete data of a player to correct it in an emergency. This
+/// bypasses the implementation and all checks of the regular updates, so it is
+/// only available to the state admin while the data fix mode is enabled. The
+/// player is added to the game if they are not added yet.
+#[receive(
+    contract = "Versus-State",
+    name = "adminSetPlayerData",
+    parameter = "AdminSetPlayerDataParams",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_admin_set_player_data<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    // Only the state admin can write player data directly.
+    ensure_eq!(ctx.sender(), host.state().state_admin, CustomContractError::OnlyStateAdmin);
+
+    // Check that the data fix mode is enabled.
+    ensure!(host.state().data_fix_mode, CustomContractError::DataFixModeDisabled);
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: AdminSetPlayerDataParams = ctx.parameter_cursor().get()?;
+    let state = host.state_mut();
+    ensure_game_exists(state, params.game_id)?;
+
+    let key = (params.game_id, params.player);
+    let previous = state.player_data.get(&key).map(|player_data| player_data.state);
+    update_active_player_count(
+        state,
+        previous.unwrap_or(PlayerState::NotAdded),
+        params.player_data.state,
+    )?;
+    if previous.is_none() {
+        update_game_player_count(state, params.game_id, true)?;
+    }
+    // The written stats count as stats of the current season.
+    let mut player_data = params.player_data;
+    player_data.season = state.current_season;
+    state.player_data.insert(key, player_data);
+    update_state_root(state, crypto_primitives, &key);
+
+    Ok(())
+}
+
+/// Move the data of a player in a game to a new address. The player keeps
+/// their place in the queues and in the pending matches of the game.
+#[receive(
+    contract = "Versus-State",
+    name = "transferProfile",
+    parameter = "TransferProfileParams",
+    error = "CustomContractError",
+    mutable,
+    crypto_primitives
+)]
+fn contract_state_transfer_profile<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    let (_proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    // Only implementation can transfer profiles.
+    only_implementation(implementation_address, ctx.sender())?;
+
+    // Check that the contract is not shut down.
+    when_not_shut_down(host)?;
+
+    let params: TransferProfileParams = ctx.parameter_cursor().get()?;
+    let state = host.state_mut();
+
+    let from = (params.game_id, params.from);
+    let to = (params.game_id, params.to);
+    ensure!(state.player_data.get(&to).is_none(), CustomContractError::AlreadyAdded);
+    let player_data =
+        state.player_data.remove_and_get(&from).ok_or(CustomContractError::PlayerNotFound)?;
+    state.player_data.insert(to, player_data);
+    update_state_root(state, crypto_primitives, &from);
+    update_state_root(state, crypto_primitives, &to);
+
+    if state.queued_players.get(&params.game_id).is_some_and(|queued| *queued == params.from) {
+        state.queued_players.insert(params.game_id, params.to);
+    }
+    if let Some(ticket) = state.pairing_tickets.remove_and_get(&from) {
+        state.pairing_tickets.insert(to, ticket);
+        state.pairing_queue.insert(ticket, to);
+    }
+
+    for match_id in state.pending_matches.iter() {
+        let mut game = state.matches.get_mut(&match_id).ok_or(CustomContractError::MatchNotFound)?;
+        if game.game_id != params.game_id {
+            continue;
+        }
+        if game.player_a == params.from {
+            game.player_a = params.to;
+        }
+        if game.player_b == params.from {
+            game.player_b = params.to;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get paused.
+#[receive(
+    contract = "Versus-State",
+    name = "getPaused",
+    return_value = "bool",
+    error = "CustomContractError"
+)]
+fn contract_state_get_paused<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<bool> {
+    Ok(host.state().paused)
+}
+
+/// Get the operational status of the contract. Unlike `view`, this does not
+/// reject before the contract is initialized.
+#[receive(
+    contract = "Versus-State",
+    name = "getStatus",
+    return_value = "StateStatus",
+    error = "CustomContractError"
+)]
+fn contract_state_get_status<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<StateStatus> {
+    let state = host.state();
+    Ok(StateStatus {
+        initialized: matches!(state.protocol_addresses, ProtocolAddressesState::Initialized { .. }),
+        paused:      state.paused,
+        shutdown:    state.shutdown,
+    })
+}
+
+/// Get player data in a game. Rejects with `PlayerNotFound` for players that
+/// are not added to the game.
+#[receive(
+    contract = "Versus-State",
+    name = "getPlayerData",
+    parameter = "GamePlayerParams",
+    return_value = "(PlayerState, BattleResult)",
+    error = "CustomContractError"
+)]
+fn contract_state_get_player_data<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<(PlayerState, BattleResult)> {
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let key = (params.game_id, params.player);
+
+    let player_data =
+        player_in_season(host.state(), &key).ok_or(CustomContractError::PlayerNotFound)?;
+
+    Ok((player_data.state, player_data.last_result))
+}
+
+/// Get the complete data of a player in a game, including the counters and
+/// the Elo rating.
+#[receive(
+    contract = "Versus-State",
+    name = "getPlayerFull",
+    parameter = "GamePlayerParams",
+    return_value = "PlayerData",
+    error = "CustomContractError"
+)]
+fn contract_state_get_player_full<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<PlayerData> {
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+
+    let player_data = player_in_season(host.state(), &(params.game_id, params.player))
+        .ok_or(CustomContractError::PlayerNotFound)?;
+
+    Ok(player_data)
+}
+
+/// Get the most recent state transitions of a player in a game, oldest first.
+/// At most `STATE_HISTORY_LENGTH` transitions are kept.
+#[receive(
+    contract = "Versus-State",
+    name = "getPlayerStateHistory",
+    parameter = "GamePlayerParams",
+    return_value = "Vec<(PlayerState, Timestamp)>",
+    error = "CustomContractError"
+)]
+fn contract_state_get_player_state_history<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Vec<(PlayerState, Timestamp)>> {
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+
+    let player_data = host
+        .state()
+        .player_data
+        .get(&(params.game_id, params.player))
+        .ok_or(CustomContractError::PlayerNotFound)?;
+
+    Ok(player_data.state_history.to_vec())
+}
+
+/// Get the current and the best win streak of a player in a game.
+#[receive(
+    contract = "Versus-State",
+    name = "getWinStreak",
+    parameter = "GamePlayerParams",
+    return_value = "WinStreak",
+    error = "CustomContractError"
+)]
+fn contract_state_get_win_streak<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<WinStreak> {
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+
+    let player_data = player_in_season(host.state(), &(params.game_id, params.player))
+        .ok_or(CustomContractError::PlayerNotFound)?;
+
+    Ok(WinStreak {
+        current: player_data.current_streak,
+        best:    player_data.best_streak,
+    })
+}
+
+/// Get the data of a player in a game. Unlike `getPlayerData` this does not
+/// fail for players that are not added but returns the data of a `NotAdded`
+/// player instead.
+#[receive(
+    contract = "Versus-State",
+    name = "getPlayerDataOrDefault",
+    parameter = "GamePlayerParams",
+    return_value = "PlayerData",
+    error = "CustomContractError"
+)]
+fn contract_state_get_player_data_or_default<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<PlayerData> {
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+
+    let player_data = match player_in_season(host.state(), &(params.game_id, params.player)) {
+        Some(player_data) => player_data,
+        None => PlayerData {
+            version:        PLAYER_DATA_VERSION,
+            state:          PlayerState::NotAdded,
+            last_result:    BattleResult::NoResult,
+            wins:           0,
+            losses:         0,
+            elo:            ELO_INITIAL_RATING,
+            state_history:  StateHistory::new(),
+            current_streak: 0,
+            best_streak:    0,
+            last_updated:   Timestamp::from_timestamp_millis(0),
+            registered_at:  Timestamp::from_timestamp_millis(0),
+            rate_window:    RateWindow::new(),
+            season:         host.state().current_season,
+        },
+    };
+
+    Ok(player_data)
+}
+
+/// Get the leaderboard rank of a player in a game. The rank is 1-based and
+/// counts the players of the game on the leaderboard with strictly more wins,
+/// so players with equal wins share a rank. Rejects with `NotOnLeaderboard` if
+/// the player has completed fewer than `leaderboard_min_matches` matches. This
+/// iterates over all players, so the energy cost grows linearly with the
+/// number of players. Rejects with `ScanLimitExceeded` instead of running out
+/// of energy if there are more than `MAX_PLAYER_RANK_SCAN` entries.
+#[receive(
+    contract = "Versus-State",
+    name = "getPlayerRank",
+    parameter = "GamePlayerParams",
+    return_value = "u32",
+    error = "CustomContractError"
+)]
+fn contract_state_get_player_rank<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<u32> {
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+    let state = host.state();
+
+    let player_data = player_in_season(state, &(params.game_id, params.player))
+        .ok_or(CustomContractError::PlayerNotFound)?;
+    ensure!(
+        on_leaderboard(state.leaderboard_min_matches, &player_data),
+        CustomContractError::NotOnLeaderboard
+    );
+
+    player_rank(state, params.game_id, player_data.wins)
+}
+
+/// Helper function to check whether a player has completed enough matches to
+/// appear on the leaderboard.
+fn on_leaderboard(min_matches: u32, player_data: &PlayerData) -> bool {
+    u64::from(player_data.wins) + u64::from(player_data.losses) >= u64::from(min_matches)
+}
+
+/// Helper function to get the leaderboard rank of a player with the given
+/// number of wins in a game. Only players on the leaderboard are counted.
+fn player_rank<S: HasStateApi>(
+    state: &State<S>,
+    game_id: GameId,
+    wins: u32,
+) -> ContractResult<u32> {
+    let mut ahead = 0;
+    for (scanned, (key, data)) in state.player_data.iter().enumerate() {
+        ensure!(scanned < MAX_PLAYER_RANK_SCAN, CustomContractError::ScanLimitExceeded);
+        let mut data = data.clone();
+        roll_over_player(&mut data, state.current_season);
+        if key.0 == game_id
+            && data.wins > wins
+            && on_leaderboard(state.leaderboard_min_matches, &data)
+        {
+            ahead += 1;
+        }
+    }
+
+    Ok(ahead + 1)
+}
+
+/// Get the data of a player in a game together with the number of matches,
+/// the leaderboard rank in the game, the current streak and the Elo rating, so
+/// that a profile can be shown with a single query. A player that is not on the leaderboard
+/// yet gets the rank they would have on it. Computing the rank iterates over
+/// all players like `getPlayerRank`, so the energy cost grows linearly with
+/// the number of players and the query rejects with `ScanLimitExceeded` if
+/// there are more than `MAX_PLAYER_RANK_SCAN` entries.
+#[receive(
+    contract = "Versus-State",
+    name = "getPlayerSummary",
+    parameter = "GamePlayerParams",
+    return_value = "PlayerSummary",
+    error = "CustomContractError"
+)]
+fn contract_state_get_player_summary<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<PlayerSummary> {
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+
+    let player_data = player_in_season(host.state(), &(params.game_id, params.player))
+        .ok_or(CustomContractError::PlayerNotFound)?;
+
+    Ok(PlayerSummary {
+        total_matches: u64::from(player_data.wins) + u64::from(player_data.losses),
+        rank: player_rank(host.state(), params.game_id, player_data.wins)?,
+        current_streak: player_data.current_streak,
+        elo: player_data.elo,
+        player_data,
+    })
+}
+
+/// Helper function to order players on the leaderboard by their address, Elo
+/// rating and wins. Higher ratings come first, players with equal ratings are
+/// ordered by more wins first and then by their serialized address in
+/// ascending order, so that the order does not depend on the iteration order
+/// of the player map.
+fn leaderboard_order(a: &(Address, i32, u32), b: &(Address, i32, u32)) -> Ordering {
+    b.1.cmp(&a.1).then(b.2.cmp(&a.2)).then_with(|| to_bytes(&a.0).cmp(&to_bytes(&b.0)))
+}
+
+/// Get the players of a game with the highest Elo ratings in descending
+/// order. Players with fewer than `leaderboard_min_matches` completed matches
+/// are left out. Ties are broken by more wins and then by the serialized
+/// address, see `leaderboard_order`. The number of returned players is capped
+/// at `MAX_LEADERBOARD_SIZE`. This iterates over all players, so the energy
+/// cost grows linearly with the number of players. Rejects with
+/// `ScanLimitExceeded` instead of running out of energy if there are more than
+/// `MAX_TOP_BY_ELO_SCAN` entries.
+#[receive(
+    contract = "Versus-State",
+    name = "getTopByElo",
+    parameter = "TopByEloParams",
+    return_value = "Vec<(Address, i32)>",
+    error = "CustomContractError"
+)]
+fn contract_state_get_top_by_elo<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Vec<(Address, i32)>> {
+    let params: TopByEloParams = ctx.parameter_cursor().get()?;
+    let limit = params.limit.min(MAX_LEADERBOARD_SIZE) as usize;
+    let min_matches = host.state().leaderboard_min_matches;
+    let season = host.state().current_season;
+
+    // Keep the best `limit` players seen so far in leaderboard order.
+    let mut top: Vec<(Address, i32, u32)> = Vec::with_capacity(limit + 1);
+    for (scanned, (key, data)) in host.state().player_data.iter().enumerate() {
+        ensure!(scanned < MAX_TOP_BY_ELO_SCAN, CustomContractError::ScanLimitExceeded);
+        let (game_id, player) = *key;
+        let mut data = data.clone();
+        roll_over_player(&mut data, season);
+        if game_id != params.game_id || !on_leaderboard(min_matches, &data) {
+            continue;
+        }
+        let entry = (player, data.elo, data.wins);
+        let position = top
+            .iter()
+            .position(|other| leaderboard_order(&entry, other) == Ordering::Less)
+            .unwrap_or(top.len());
+        if position < limit {
+            top.insert(position, entry);
+            top.truncate(limit);
+        }
+    }
+
+    Ok(top.into_iter().map(|(player, elo, _wins)| (player, elo)).collect())
+}
+
+/// Get the matches that are still pending. This only iterates over the
+/// pending matches and not over all matches.
+#[receive(
+    contract = "Versus-State",
+    name = "getPendingMatches",
+    parameter = "PaginationParams",
+    return_value = "Vec<(MatchId, Match)>",
+    error = "CustomContractError"
+)]
+fn contract_state_get_pending_matches<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Vec<(MatchId, Match)>> {
+    let params: PaginationParams = ctx.parameter_cursor().get()?;
+    let state = host.state();
+
+    let mut matches = Vec::new();
+    for match_id in state
+        .pending_matches
+        .iter()
+        .skip(params.offset as usize)
+        .take(params.limit.min(MAX_PAGE_SIZE) as usize)
+    {
+        let game = state.matches.get(&match_id).ok_or(CustomContractError::MatchNotFound)?;
+        matches.push((*match_id, game.clone()));
+    }
+
+    Ok(matches)
+}
+
+/// Helper function to shuffle players with a Fisher-Yates shuffle, drawing the
+/// swap positions from hashes of the seed.
+fn shuffle_players(
+    crypto_primitives: &impl HasCryptoPrimitives,
+    seed: &HashSha2256,
+    players: &mut [Address],
+) {
+    for index in (1..players.len()).rev() {
+        let mut data = seed.0.to_vec();
+        data.extend(to_bytes(&(index as u32)));
+        let hash = crypto_primitives.hash_sha2_256(&data);
+        let mut random = [0u8; 8];
+        random.copy_from_slice(&hash.0[..8]);
+        let swap_index = u64::from_le_bytes(random) % (index as u64 + 1);
+        players.swap(index, swap_index as usize);
+    }
+}
+
+/// Helper function to get the position of the leaf of a player in the state
+/// root tree.
+fn merkle_position(crypto_primitives: &impl HasCryptoPrimitives, player: &Address) -> u32 {
+    let hash = crypto_primitives.hash_sha2_256(&to_bytes(player));
+    u32::from_be_bytes([hash.0[0], hash.0[1], hash.0[2], hash.0[3]])
+}
+
+/// Helper function to hash a leaf of the state root tree. An empty leaf is
+/// `EMPTY_NODE`.
+fn merkle_leaf(
+    crypto_primitives: &impl HasCryptoPrimitives,
+    players: &Vec<(Address, PlayerData)>,
+) -> HashSha2256 {
+    if players.is_empty() {
+        return EMPTY_NODE;
+    }
+    let mut data = vec![0u8];
+    data.extend(to_bytes(players));
+    crypto_primitives.hash_sha2_256(&data)
+}
+
+/// Helper function to hash two nodes of the state root tree. The parent of two
+/// empty subtrees is empty.
+fn merkle_parent(
+    crypto_primitives: &impl HasCryptoPrimitives,
+    left: &HashSha2256,
+    right: &HashSha2256,
+) -> HashSha2256 {
+    if *left == EMPTY_NODE && *right == EMPTY_NODE {
+        return EMPTY_NODE;
+    }
+    let mut data = vec![1u8];
+    data.extend_from_slice(&left.0);
+    data.extend_from_slice(&right.0);
+    crypto_primitives.hash_sha2_256(&data)
+}
+
+/// Helper function to get a node of the state root tree.
+fn merkle_node<S: HasStateApi>(state: &State<S>, height: u8, position: u32) -> HashSha2256 {
+    state.state_root_nodes.get(&(height, position)).map_or(EMPTY_NODE, |node| *node)
+}
+
+/// Helper function to get the players of a leaf of the state root tree with
+/// their data.
+fn merkle_leaf_players<S: HasStateApi>(
+    state: &State<S>,
+    position: u32,
+) -> Vec<(Address, PlayerData)> {
+    let players = match state.state_root_leaves.get(&position) {
+        Some(players) => players.clone(),
+        None => return Vec::new(),
+    };
+    players
+        .into_iter()
+        .filter_map(|player| {
+            let player_data = state.player_data.get(&(DEFAULT_GAME_ID, player))?;
+            Some((player, player_data.clone()))
+        })
+        .collect()
+}
+
+/// Helper function to update the state root tree after the data of a player
+/// was written or removed. Only players of the default game are part of the
+/// tree. One leaf and `STATE_ROOT_DEPTH` inner nodes are hashed, so the cost
+/// does not depend on the number of players.
+fn update_state_root<S: HasStateApi>(
+    state: &mut State<S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+    key: &(GameId, Address),
+) {
+    let (game_id, player) = *key;
+    if game_id != DEFAULT_GAME_ID {
+        return;
+    }
+
+    let mut position = merkle_position(crypto_primitives, &player);
+    let mut players = state.state_root_leaves.get(&position).map_or_else(Vec::new, |p| p.clone());
+    let present = state.player_data.get(key).is_some();
+    match players.binary_search_by_key(&to_bytes(&player), to_bytes) {
+        Ok(index) if !present => {
+            players.remove(index);
+        }
+        Err(index) if present => players.insert(index, player),
+        _ => (),
+    }
+    if players.is_empty() {
+        state.state_root_leaves.remove(&position);
+    } else {
+        state.state_root_leaves.insert(position, players);
+    }
+
+    let mut hash = merkle_leaf(crypto_primitives, &merkle_leaf_players(state, position));
+    for height in 0..=STATE_ROOT_DEPTH {
+        if hash == EMPTY_NODE {
+            state.state_root_nodes.remove(&(height, position));
+        } else {
+            state.state_root_nodes.insert((height, position), hash);
+        }
+        if height == STATE_ROOT_DEPTH {
+            break;
+        }
+        let sibling = merkle_node(state, height, position ^ 1);
+        hash = if position & 1 == 0 {
+            merkle_parent(crypto_primitives, &hash, &sibling)
+        } else {
+            merkle_parent(crypto_primitives, &sibling, &hash)
+        };
+        position >>= 1;
+    }
+}
+
+/// Get a Merkle root committing to all players of the default game and their
+/// data, so that light clients can verify player data with `getPlayerProof`.
+/// The tree has `STATE_ROOT_DEPTH` levels above its leaves. A player is part of
+/// the leaf at the position given by the first four bytes, in big endian, of
+/// the hash of their serialized address. A leaf is the hash of `0 || players`
+/// with its players and their data ordered by the serialized address, an inner
+/// node is the hash of `1 || left || right`. Empty leaves and the parents of
+/// two empty nodes are all zeros, so the root of no players is all zeros. The
+/// tree is updated whenever player data is written, so the root is read
+/// without scanning the players.
+#[receive(
+    contract = "Versus-State",
+    name = "getStateRoot",
+    return_value = "HashSha2256",
+    error = "CustomContractError"
+)]
+fn contract_state_get_state_root<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<HashSha2256> {
+    Ok(merkle_node(host.state(), STATE_ROOT_DEPTH, 0))
+}
+
+/// Get the proof of the leaf of a player in the `getStateRoot` tree. The
+/// player is part of the root with the returned data if they are one of the
+/// players of the leaf, otherwise the proof shows that they are absent.
+#[receive(
+    contract = "Versus-State",
+    name = "getPlayerProof",
+    parameter = "Address",
+    return_value = "MerkleProof",
+    error = "CustomContractError",
+    crypto_primitives
+)]
+fn contract_state_get_player_proof<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<MerkleProof> {
+    let params: Address = ctx.parameter_cursor().get()?;
+    let state = host.state();
+
+    let index = merkle_position(crypto_primitives, &params);
+    let siblings = (0..STATE_ROOT_DEPTH)
+        .map(|height| merkle_node(state, height, (index >> height) ^ 1))
+        .collect();
+
+    Ok(MerkleProof {
+        index,
+        players: merkle_leaf_players(state, index),
+        siblings,
+    })
+}
+
+/// Get a page of all matches for archival. Matches are returned in ascending
+/// order of their id. Match ids are assigned sequentially and matches are
+/// never removed, so following the `next` cursor until it is `None` returns
+/// every match exactly once.
+#[receive(
+    contract = "Versus-State",
+    name = "getMatches",
+    parameter = "GetMatchesParams",
+    return_value = "MatchesPage",
+    error = "CustomContractError"
+)]
+fn contract_state_get_matches<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<MatchesPage> {
+    let params: GetMatchesParams = ctx.parameter_cursor().get()?;
+    let state = host.state();
+
+    let end = params
+        .from
+        .saturating_add(u64::from(params.limit.min(MAX_PAGE_SIZE)))
+        .min(state.next_match_id);
+
+    let mut matches = Vec::new();
+    for match_id in params.from..end {
+        let game = state.matches.get(&match_id).ok_or(CustomContractError::MatchNotFound)?;
+        matches.push((match_id, game.clone()));
+    }
+
+    Ok(MatchesPage {
+        matches,
+        next: if end < state.next_match_id {
+            Some(end)
+        } else {
+            None
+        },
+    })
+}
+
+/// Get the time the contract was paused at, or `None` if it is not paused.
+#[receive(
+    contract = "Versus-State",
+    name = "getPausedSince",
+    return_value = "Option<Timestamp>",
+    error = "CustomContractError"
+)]
+fn contract_state_get_paused_since<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Option<Timestamp>> {
+    Ok(host.state().paused_at)
+}
+
+/// Get the number of players waiting in the pairing queue.
+#[receive(
+    contract = "Versus-State",
+    name = "getQueueLength",
+    return_value = "u32",
+    error = "CustomContractError"
+)]
+fn contract_state_get_queue_length<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<u32> {
+    Ok(host.state().pairing_queue_length)
+}
+
+/// Get a page of the players waiting in the pairing queue with the game they
+/// wait in, in the order they joined it. At most `MAX_PAIRING_QUEUE_SCAN` tickets are looked at per
+/// page, so a page can hold fewer players than requested while the `next`
+/// cursor is set. Following the `next` cursor until it is `None` returns every
+/// waiting player.
+#[receive(
+    contract = "Versus-State",
+    name = "getQueue",
+    parameter = "GetQueueParams",
+    return_value = "QueuePage",
+    error = "CustomContractError"
+)]
+fn contract_state_get_queue<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<QueuePage> {
+    let params: GetQueueParams = ctx.parameter_cursor().get()?;
+    let state = host.state();
+
+    let limit = params.limit.min(MAX_PAGE_SIZE) as usize;
+    let mut ticket = params.from.max(state.pairing_queue_head);
+    let scan_end = state.pairing_queue_tail.min(ticket.saturating_add(MAX_PAIRING_QUEUE_SCAN));
+    let mut players = Vec::new();
+    while ticket < scan_end && players.len() < limit {
+        if let Some(key) = state.pairing_queue.get(&ticket) {
+            players.push(*key);
+        }
+        ticket += 1;
+    }
+
+    Ok(QueuePage {
+        players,
+        next: if ticket < state.pairing_queue_tail {
+            Some(ticket)
+        } else {
+            None
+        },
+    })
+}
+
+/// Get a page of the games with their metadata and number of players, in the
+/// order they were created in.
+#[receive(
+    contract = "Versus-State",
+    name = "getGames",
+    parameter = "PaginationParams",
+    return_value = "Vec<(GameId, GameInfo)>",
+    error = "CustomContractError"
+)]
+fn contract_state_get_games<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Vec<(GameId, GameInfo)>> {
+    let params: PaginationParams = ctx.parameter_cursor().get()?;
+    let state = host.state();
+
+    let games = (DEFAULT_GAME_ID..state.next_game_id)
+        .filter_map(|game_id| state.games.get(&game_id).map(|game| (game_id, game.clone())))
+        .skip(params.offset as usize)
+        .take(params.limit.min(MAX_PAGE_SIZE) as usize)
+        .collect();
+
+    Ok(games)
+}
+
+/// Get a tournament with all its rounds played so far.
+#[receive(
+    contract = "Versus-State",
+    name = "getTournament",
+    parameter = "TournamentId",
+    return_value = "Tournament",
+    error = "CustomContractError"
+)]
+fn contract_state_get_tournament<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<Tournament> {
+    let tournament_id: TournamentId = ctx.parameter_cursor().get()?;
+
+    let tournament = host
+        .state()
+        .tournaments
+        .get(&tournament_id)
+        .ok_or(CustomContractError::TournamentNotFound)?;
+
+    Ok(tournament.clone())
+}
+
+/// Get the number of players in the `Active` state.
+#[receive(
+    contract = "Versus-State",
+    name = "getActivePlayerCount",
+    return_value = "u32",
+    error = "CustomContractError"
+)]
+fn contract_state_get_active_player_count<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<u32> {
+    Ok(host.state().active_player_count)
+}
+
+/// Get the number, total duration and average duration of all recorded
+/// matches.
+#[receive(
+    contract = "Versus-State",
+    name = "getMatchDurationStats",
+    return_value = "MatchDurationStats",
+    error = "CustomContractError"
+)]
+fn contract_state_get_match_duration_stats<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<MatchDurationStats> {
+    let MatchDurations {
+        matches,
+        total_seconds,
+    } = host.state().match_durations;
+
+    Ok(MatchDurationStats {
+        matches,
+        total_seconds,
+        average_seconds: total_seconds.checked_div(matches).unwrap_or(0),
+    })
+}
+
+/// Get the number of the current season.
+#[receive(
+    contract = "Versus-State",
+    name = "getCurrentSeason",
+    return_value = "u32",
+    error = "CustomContractError"
+)]
+fn contract_state_get_current_season<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<u32> {
+    Ok(host.state().current_season)
+}
+
+/// Get the number of players added in a season. Seasons that ended are
+/// counted from the time the season ended, even if no result rolled them over.
+#[receive(
+    contract = "Versus-State",
+    name = "getPlayersAddedInSeason",
+    parameter = "u32",
+    return_value = "u32",
+    error = "CustomContractError"
+)]
+fn contract_state_get_players_added_in_season<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<u32> {
+    let season: u32 = ctx.parameter_cursor().get()?;
+
+    Ok(host.state().players_added.get(&season).map_or(0, |count| *count))
+}
+
+/// Check if a player is added in a game.
+#[receive(
+    contract = "Versus-State",
+    name = "isAdded",
+    parameter = "GamePlayerParams",
+    return_value = "bool",
+    error = "CustomContractError"
+)]
+fn contract_state_is_added<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<bool> {
+    let params: GamePlayerParams = ctx.parameter_cursor().get()?;
+
+    let is_added = host
+        .state()
+        .player_data
+        .get(&(params.game_id, params.player))
+        .is_some_and(|player_data| player_data.state != PlayerState::NotAdded);
+
+    Ok(is_added)
+}
+
+/// Function to view state of the state contract.
+#[receive(
+    contract = "Versus-State",
+    name = "view",
+    return_value = "ReturnBasicState",
+    error = "CustomContractError"
+)]
+fn contract_state_view<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<ReturnBasicState> {
+    let (proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+
+    let state = ReturnBasicState {
+        proxy_address,
+        implementation_address,
+        paused:       host.state().paused,
+        pause_reason: host.state().pause_reason.clone(),
+    };
+    Ok(state)
+}
+
+/// Function to view the state of the state contract together with its
+/// counters, so that a single query gives the full operational picture. This
+/// extends `view`, which is kept unchanged for existing callers. The player
+/// count iterates over the games, not over the players.
+#[receive(
+    contract = "Versus-State",
+    name = "getExtendedState",
+    return_value = "ExtendedState",
+    error = "CustomContractError"
+)]
+fn contract_state_get_extended_state<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ContractResult<ExtendedState> {
+    let (proxy_address, implementation_address) = get_protocol_addresses_from_state(host)?;
+    let state = host.state();
+
+    let mut player_count: u32 = 0;
+    for (_game_id, game) in state.games.iter() {
+        player_count = player_count
+            .checked_add(game.player_count)
+            .ok_or(CustomContractError::ArithmeticOverflow)?;
+    }
+
+    Ok(ExtendedState {
+        proxy_address,
+        implementation_address,
+        paused: state.paused,
+        pause_reason: state.pause_reason.clone(),
+        shutdown: state.shutdown,
+        player_count,
+        active_player_count: state.active_player_count,
+        current_season: state.current_season,
+        total_wins: state.result_totals.wins,
+        total_losses: state.result_totals.losses,
+    })
+}
+
+#[concordium_cfg_test]
+mod tests {
+    use super::*;
+    use test_infrastructure::*;
+
+    const PROXY: ContractAddress = ContractAddress {
+        index:    1,
+        subindex: 0,
+    };
+    const IMPLEMENTATION: ContractAddress = ContractAddress {
+        index:    2,
+        subindex: 0,
+    };
+    const STATE_ADMIN: Address = Address::Account(AccountAddress([9u8; 32]));
+    const REFEREE: AccountAddress = AccountAddress([8u8; 32]);
+    const ACCOUNT_0: AccountAddress = AccountAddress([0u8; 32]);
+    const ACCOUNT_1: AccountAddress = AccountAddress([1u8; 32]);
+    const ACCOUNT_2: AccountAddress = AccountAddress([2u8; 32]);
+    const PLAYER_0: Address = Address::Account(ACCOUNT_0);
+    const PLAYER_1: Address = Address::Account(ACCOUNT_1);
+    const PLAYER_2: Address = Address::Account(ACCOUNT_2);
+
+    /// Creates a host with a state contract initialized with `PROXY` and
+    /// `IMPLEMENTATION`.
+    fn initialized_host() -> TestHost<State<TestStateApi>> {
+        let mut state_builder = TestStateBuilder::new();
+        let mut state = State::new(&mut state_builder, STATE_ADMIN);
+        state.protocol_addresses = ProtocolAddressesState::Initialized {
+            proxy_address:          PROXY,
+            implementation_address: IMPLEMENTATION,
+        };
+        state.referees.insert(Address::Account(REFEREE));
+        TestHost::new(state, state_builder)
+    }
+
+    /// Invokes `addPlayer` as the implementation contract.
+    fn add_player(host: &mut TestHost<State<TestStateApi>>, player: Address) {
+        let parameter_bytes = to_bytes(&GamePlayerParams {
+            game_id: DEFAULT_GAME_ID,
+            player,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+
+        contract_state_set_player_data(&ctx, host, &test_crypto_primitives())
+            .expect_report("Adding a player should pass");
+    }
+
+    /// Invokes `updateBattleResult` as the implementation contract.
+    fn update_battle_result(
+        host: &mut TestHost<State<TestStateApi>>,
+        player: Address,
+        result: BattleResult,
+    ) {
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            game_id: DEFAULT_GAME_ID,
+            player,
+            result,
+            client_ref: None,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+
+        contract_state_update_battle_result(&ctx, host, &test_crypto_primitives())
+            .expect_report("Updating the battle result should pass");
+    }
+
+    /// Invokes `getPlayerData`.
+    fn get_player_data(
+        host: &TestHost<State<TestStateApi>>,
+        player: Address,
+    ) -> (PlayerState, BattleResult) {
+        let parameter_bytes = to_bytes(&GamePlayerParams {
+            game_id: DEFAULT_GAME_ID,
+            player,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_parameter(&parameter_bytes);
+
+        contract_state_get_player_data(&ctx, host).expect_report("Getting player data should pass")
+    }
+
+    /// Invokes `createMatch` as the implementation contract.
+    fn create_match(
+        host: &mut TestHost<State<TestStateApi>>,
+        player_a: Address,
+        player_b: Address,
+    ) -> MatchId {
+        let parameter_bytes = to_bytes(&CreateMatchParams {
+            game_id: DEFAULT_GAME_ID,
+            player_a,
+            player_b,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+
+        contract_state_create_match(&ctx, host).expect_report("Creating a match should pass")
+    }
+
+    /// Invokes `completeMatch` as the implementation contract on behalf of
+    /// `REFEREE` at the given slot time.
+    fn complete_match(
+        host: &mut TestHost<State<TestStateApi>>,
+        match_id: MatchId,
+        winner: Address,
+        slot_time: Timestamp,
+    ) -> CompletedMatch {
+        let parameter_bytes = to_bytes(&CompleteMatchParams {
+            match_id,
+            winner,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(slot_time);
+        ctx.set_invoker(REFEREE);
+
+        contract_state_complete_match(&ctx, host, &test_crypto_primitives())
+            .expect_report("Completing a match should pass")
+    }
+
+    /// Invokes `disputeMatch` as the implementation contract at the given
+    /// slot time.
+    fn dispute_match(
+        host: &mut TestHost<State<TestStateApi>>,
+        match_id: MatchId,
+        player: Address,
+        slot_time: Timestamp,
+    ) -> ContractResult<()> {
+        let parameter_bytes = to_bytes(&DisputeMatchParams {
+            match_id,
+            player,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(slot_time);
+
+        contract_state_dispute_match(&ctx, host)
+    }
+
+    /// Creates crypto primitives with a deterministic stand-in for SHA-256.
+    fn test_crypto_primitives() -> TestCryptoPrimitives {
+        let crypto_primitives = TestCryptoPrimitives::new();
+        crypto_primitives.setup_hash_sha2_256_mock(|data| {
+            let mut hash = [0u8; 32];
+            for (index, byte) in data.iter().enumerate() {
+                let slot = &mut hash[index % 32];
+                *slot = slot.wrapping_mul(31).wrapping_add(*byte).rotate_left(3);
+            }
+            hash[0] ^= data.len() as u8;
+            HashSha2256(hash)
+        });
+        crypto_primitives
+    }
+
+    /// Verifies a Merkle proof against a state root.
+    fn verify_proof(
+        crypto_primitives: &TestCryptoPrimitives,
+        root: &HashSha2256,
+        proof: &MerkleProof,
+    ) -> bool {
+        let mut hash = merkle_leaf(crypto_primitives, &proof.players);
+        let mut position = proof.index;
+        for sibling in &proof.siblings {
+            hash = if position & 1 == 0 {
+                merkle_parent(crypto_primitives, &hash, sibling)
+            } else {
+                merkle_parent(crypto_primitives, sibling, &hash)
+            };
+            position >>= 1;
+        }
+        proof.siblings.len() == usize::from(STATE_ROOT_DEPTH) && hash == *root
+    }
+
+    /// Serializes the parameter of a player in the default game.
+    fn game_player(player: Address) -> Vec<u8> {
+        to_bytes(&GamePlayerParams {
+            game_id: DEFAULT_GAME_ID,
+            player,
+        })
+    }
+
+    /// Serializes the parameter of `getTopByElo` for the default game.
+    fn top_by_elo(limit: u32) -> Vec<u8> {
+        to_bytes(&TopByEloParams {
+            game_id: DEFAULT_GAME_ID,
+            limit,
+        })
+    }
+
+    /// Returns the player waiting in the matchmaking queue of the default game.
+    fn queued_player(host: &TestHost<State<TestStateApi>>) -> Option<Address> {
+        host.state().queued_players.get(&DEFAULT_GAME_ID).map(|player| *player)
+    }
+
+    /// Invokes `joinQueue` as the implementation contract.
+    fn join_queue(
+        host: &mut TestHost<State<TestStateApi>>,
+        player: Address,
+    ) -> ContractResult<Option<MatchId>> {
+        let parameter_bytes = game_player(player);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+
+        contract_state_join_queue(&ctx, host)
+    }
+
+    /// Invokes `getPendingMatches` returning all pending match ids.
+    fn get_pending_match_ids(host: &TestHost<State<TestStateApi>>) -> Vec<MatchId> {
+        let parameter_bytes = to_bytes(&PaginationParams {
+            offset: 0,
+            limit:  MAX_PAGE_SIZE,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_parameter(&parameter_bytes);
+
+        let mut match_ids: Vec<MatchId> = contract_state_get_pending_matches(&ctx, host)
+            .expect_report("Getting pending matches should pass")
+            .into_iter()
+            .map(|(match_id, _)| match_id)
+            .collect();
+        match_ids.sort_unstable();
+        match_ids
+    }
+
+    #[concordium_test]
+    /// Test that the contract can only be initialized once.
+    fn test_initialize() {
+        let mut init_ctx = TestInitContext::empty();
+        init_ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        init_ctx.set_init_origin(AccountAddress([9u8; 32]));
+        let mut state_builder = TestStateBuilder::new();
+        let state = contract_state_init(&init_ctx, &mut state_builder)
+            .expect_report("Contract initialization results in error");
+        let mut host = TestHost::new(state, state_builder);
+
+        let parameter_bytes = to_bytes(&InitializeStateParams {
+            proxy_address:          PROXY,
+            implementation_address: IMPLEMENTATION,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_parameter(&parameter_bytes);
+
+        let result = contract_state_initialize(&ctx, &mut host);
+        claim!(result.is_ok(), "Initializing should pass");
+        claim!(
+            host.state().protocol_addresses
+                == ProtocolAddressesState::Initialized {
+                    proxy_address:          PROXY,
+                    implementation_address: IMPLEMENTATION,
+                },
+            "Protocol addresses should be set"
+        );
+
+        let result = contract_state_initialize(&ctx, &mut host);
+        claim_eq!(result, Err(CustomContractError::AlreadyInitialized));
+    }
+
+    #[concordium_test]
+    /// Test that an added player is suspended and has no result.
+    fn test_add_player() {
+        let mut host = initialized_host();
+
+        add_player(&mut host, PLAYER_0);
+
+        claim_eq!(
+            get_player_data(&host, PLAYER_0),
+            (PlayerState::Suspended, BattleResult::NoResult)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that added players start in the configured default state.
+    fn test_set_default_player_state() {
+        let mut host = initialized_host();
+
+        let parameter_bytes = to_bytes(&PlayerState::NotAdded);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_default_player_state(&ctx, &mut host),
+            Err(CustomContractError::InvalidDefaultPlayerState)
+        );
+        claim_eq!(host.state().default_player_state, PlayerState::Suspended);
+
+        let parameter_bytes = to_bytes(&PlayerState::Active);
+        ctx.set_parameter(&parameter_bytes);
+        let result = contract_state_set_default_player_state(&ctx, &mut host);
+        claim!(result.is_ok(), "Setting the default player state should pass");
+
+        add_player(&mut host, PLAYER_0);
+
+        claim_eq!(get_player_data(&host, PLAYER_0), (PlayerState::Active, BattleResult::NoResult));
+    }
+
+    #[concordium_test]
+    /// Test that only the implementation can add players.
+    fn test_add_player_only_implementation() {
+        let mut host = initialized_host();
+
+        let parameter_bytes = to_bytes(&PLAYER_0);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+
+        let result = contract_state_set_player_data(&ctx, &mut host, &test_crypto_primitives());
+        claim_eq!(result, Err(CustomContractError::OnlyImplementation));
+    }
+
+    #[concordium_test]
+    /// Test that the implementation can update the state of a player.
+    fn test_update_player_state() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+
+        let parameter_bytes = to_bytes(&UpdatePlayerStateParams {
+            game_id: DEFAULT_GAME_ID,
+            player:  PLAYER_0,
+            state:   PlayerState::Suspended,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+
+        let result = contract_state_update_player_state(&ctx, &mut host, &test_crypto_primitives());
+        claim!(result.is_ok(), "Updating the player state should pass");
+        claim_eq!(
+            get_player_data(&host, PLAYER_0),
+            (PlayerState::Suspended, BattleResult::NoResult)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that a player can be suspended and reactivated in a game other
+    /// than the default game, without touching their default game data.
+    fn test_update_player_state_in_game() {
+        let mut host = initialized_host();
+        let parameter_bytes = to_bytes(&None::<String>);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        claim_eq!(contract_state_create_game(&ctx, &mut host), Ok(1));
+        add_player(&mut host, PLAYER_0);
+        let parameter_bytes = to_bytes(&GamePlayerParams {
+            game_id: 1,
+            player:  PLAYER_0,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_player_data(&ctx, &mut host, &test_crypto_primitives()),
+            Ok(())
+        );
+
+        let update_player_state = |host: &mut TestHost<State<TestStateApi>>, state| {
+            let parameter_bytes = to_bytes(&UpdatePlayerStateParams {
+                game_id: 1,
+                player:  PLAYER_0,
+                state,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+            contract_state_update_player_state(&ctx, host, &test_crypto_primitives())
+        };
+        let game_state = |host: &TestHost<State<TestStateApi>>| {
+            host.state().player_data.get(&(1, PLAYER_0)).map(|data| data.state)
+        };
+
+        claim_eq!(update_player_state(&mut host, PlayerState::Active), Ok(()));
+        claim_eq!(game_state(&host), Some(PlayerState::Active));
+        claim_eq!(contract_state_join_pairing_queue(&ctx, &mut host), Ok(()));
+
+        // Suspending the player removes them from the queue of the game.
+        claim_eq!(update_player_state(&mut host, PlayerState::Suspended), Ok(()));
+        claim_eq!(game_state(&host), Some(PlayerState::Suspended));
+        claim!(host.state().pairing_tickets.get(&(1, PLAYER_0)).is_none(), "Player should leave");
+
+        claim_eq!(update_player_state(&mut host, PlayerState::Active), Ok(()));
+        claim_eq!(game_state(&host), Some(PlayerState::Active));
+        claim_eq!(
+            get_player_data(&host, PLAYER_0),
+            (PlayerState::Suspended, BattleResult::NoResult)
+        );
+        claim_eq!(host.state().active_player_count, 1);
+
+        // Players waiting in different games are not paired with each other.
+        claim_eq!(contract_state_join_pairing_queue(&ctx, &mut host), Ok(()));
+        host.state_mut().default_player_state = PlayerState::Active;
+        add_player(&mut host, PLAYER_1);
+        let parameter_bytes = game_player(PLAYER_1);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_join_pairing_queue(&ctx, &mut host), Ok(()));
+        let match_ids = contract_state_pair_players(&ctx, &mut host, &test_crypto_primitives());
+        claim_eq!(match_ids, Ok(Vec::new()));
+        claim_eq!(host.state().pairing_queue_length, 2);
+    }
+
+    #[concordium_test]
+    /// Test that battle results update the result and the win/loss counters.
+    fn test_update_battle_result() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Win);
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Win);
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Loss);
+
+        claim_eq!(get_player_data(&host, PLAYER_0), (PlayerState::Suspended, BattleResult::Loss));
+        let player_data = host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap();
+        claim_eq!(player_data.wins, 2);
+        claim_eq!(player_data.losses, 1);
+    }
+
+    #[concordium_test]
+    /// Test that players with equal wins share a rank.
+    fn test_get_player_rank() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        add_player(&mut host, PLAYER_2);
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Win);
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Win);
+        update_battle_result(&mut host, PLAYER_1, BattleResult::Win);
+        update_battle_result(&mut host, PLAYER_2, BattleResult::Win);
+
+        let rank = |player: Address| {
+            let parameter_bytes = game_player(player);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_get_player_rank(&ctx, &host)
+        };
+
+        claim_eq!(rank(PLAYER_0), Ok(1));
+        claim_eq!(rank(PLAYER_1), Ok(2));
+        claim_eq!(rank(PLAYER_2), Ok(2));
+        claim_eq!(
+            rank(Address::Account(AccountAddress([3u8; 32]))),
+            Err(CustomContractError::PlayerNotFound)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the summary of a player matches the data, rank and counters
+    /// in the state.
+    fn test_get_player_summary() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Win);
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Win);
+        update_battle_result(&mut host, PLAYER_1, BattleResult::Win);
+        update_battle_result(&mut host, PLAYER_1, BattleResult::Loss);
+        update_battle_result(&mut host, PLAYER_1, BattleResult::Loss);
+
+        let summary = |host: &TestHost<State<TestStateApi>>, player: Address| {
+            let parameter_bytes = to_bytes(&GamePlayerParams {
+                game_id: DEFAULT_GAME_ID,
+                player,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_get_player_summary(&ctx, host)
+        };
+
+        let player_1 = summary(&host, PLAYER_1).expect_report("Summary should pass");
+        let player_data = host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap();
+        claim_eq!(to_bytes(&player_1.player_data), to_bytes(&*player_data));
+        claim_eq!((player_1.total_matches, player_1.rank, player_1.current_streak), (3, 2, -2));
+        claim_eq!(player_1.elo, player_data.elo);
+        let player_0 = summary(&host, PLAYER_0).expect_report("Summary should pass");
+        claim_eq!((player_0.total_matches, player_0.rank, player_0.current_streak), (2, 1, 2));
+        claim!(
+            matches!(summary(&host, PLAYER_2), Err(CustomContractError::PlayerNotFound)),
+            "Summary of an unknown player should fail"
+        );
+    }
+
+    #[concordium_test]
+    /// Test that beating a stronger opponent gains more rating than beating a
+    /// weaker one, and that the top Elo query is sorted.
+    fn test_record_match() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        add_player(&mut host, PLAYER_2);
+        host.state_mut().player_data.get_mut(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap().elo = 1400;
+        host.state_mut().player_data.get_mut(&(DEFAULT_GAME_ID, PLAYER_2)).unwrap().elo = 1000;
+
+        let record_match = |host: &mut TestHost<State<TestStateApi>>, winner, loser| {
+            let parameter_bytes = to_bytes(&RecordMatchParams {
+                game_id:          DEFAULT_GAME_ID,
+                winner,
+                loser,
+                duration_seconds: 60,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_record_match(&ctx, host, &test_crypto_primitives())
+        };
+
+        claim!(record_match(&mut host, PLAYER_0, PLAYER_1).is_ok(), "Recording should pass");
+        let gain_against_stronger =
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().elo - 1200;
+        let loser_elo = host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap().elo;
+        claim_eq!(loser_elo, 1400 - gain_against_stronger);
+
+        host.state_mut().player_data.get_mut(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().elo = 1200;
+        claim!(record_match(&mut host, PLAYER_0, PLAYER_2).is_ok(), "Recording should pass");
+        let gain_against_weaker =
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().elo - 1200;
+        claim!(
+            gain_against_stronger > gain_against_weaker,
+            "Beating a stronger opponent should gain more rating"
+        );
+
+        claim_eq!(
+            record_match(&mut host, PLAYER_0, PLAYER_0),
+            Err(CustomContractError::InvalidMatch)
+        );
+
+        let parameter_bytes = top_by_elo(2);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_parameter(&parameter_bytes);
+        let top = contract_state_get_top_by_elo(&ctx, &host).expect_report("Query should pass");
+        claim_eq!(top.len(), 2);
+        claim!(top[0].1 >= top[1].1, "Leaderboard should be sorted descending");
+    }
+
+    #[concordium_test]
+    /// Test that the durations of recorded matches add up to the total and
+    /// average, and that matches without a duration are rejected.
+    fn test_match_duration_stats() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+
+        let record_match = |host: &mut TestHost<State<TestStateApi>>, duration_seconds| {
+            let parameter_bytes = to_bytes(&RecordMatchParams {
+                game_id:          DEFAULT_GAME_ID,
+                winner: PLAYER_0,
+                loser: PLAYER_1,
+                duration_seconds,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_record_match(&ctx, host, &test_crypto_primitives())
+        };
+        let ctx = TestReceiveContext::empty();
+        claim_eq!(
+            contract_state_get_match_duration_stats(&ctx, &host),
+            Ok(MatchDurationStats {
+                matches:         0,
+                total_seconds:   0,
+                average_seconds: 0,
+            })
+        );
+
+        claim_eq!(record_match(&mut host, 0), Err(CustomContractError::InvalidDuration));
+        claim_eq!(record_match(&mut host, 300), Ok(()));
+        claim_eq!(record_match(&mut host, 120), Ok(()));
+        claim_eq!(record_match(&mut host, 181), Ok(()));
+        claim_eq!(
+            contract_state_get_match_duration_stats(&ctx, &host),
+            Ok(MatchDurationStats {
+                matches:         3,
+                total_seconds:   601,
+                average_seconds: 200,
+            })
+        );
+    }
+
+    #[concordium_test]
+    /// Test that players with equal ratings are ordered by wins and then by
+    /// address, independent of the order they were added in.
+    fn test_top_by_elo_tie_breaking() {
+        let player_3 = Address::Account(AccountAddress([3u8; 32]));
+        let top_by_elo = |players: &[Address]| {
+            let mut host = initialized_host();
+            for player in players {
+                add_player(&mut host, *player);
+            }
+            host.state_mut().player_data.get_mut(&(DEFAULT_GAME_ID, PLAYER_2)).unwrap().wins = 1;
+            host.state_mut().player_data.get_mut(&(DEFAULT_GAME_ID, player_3)).unwrap().elo = 1300;
+
+            let parameter_bytes = top_by_elo(MAX_LEADERBOARD_SIZE);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_get_top_by_elo(&ctx, &host).expect_report("Query should pass")
+        };
+
+        let expected = vec![
+            (player_3, 1300),
+            (PLAYER_2, ELO_INITIAL_RATING),
+            (PLAYER_0, ELO_INITIAL_RATING),
+            (PLAYER_1, ELO_INITIAL_RATING),
+        ];
+        claim_eq!(top_by_elo(&[PLAYER_0, PLAYER_1, PLAYER_2, player_3]), expected);
+        claim_eq!(top_by_elo(&[player_3, PLAYER_2, PLAYER_1, PLAYER_0]), expected);
+        claim_eq!(top_by_elo(&[PLAYER_1, player_3, PLAYER_0, PLAYER_2]), expected);
+    }
+
+    #[concordium_test]
+    /// Test that the data of added players is returned and a default for
+    /// players that are not added.
+    fn test_get_player_data_or_default() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Win);
+
+        let get_player_data_or_default = |player: Address| {
+            let parameter_bytes = game_player(player);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_get_player_data_or_default(&ctx, &host)
+                .expect_report("Query should pass")
+        };
+
+        let present = get_player_data_or_default(PLAYER_0);
+        claim_eq!(present.state, PlayerState::Suspended);
+        claim_eq!(present.last_result, BattleResult::Win);
+        claim_eq!(present.wins, 1);
+
+        let absent = get_player_data_or_default(PLAYER_1);
+        claim_eq!(absent.state, PlayerState::NotAdded);
+        claim_eq!(absent.last_result, BattleResult::NoResult);
+        claim_eq!(absent.wins, 0);
+        claim_eq!(absent.losses, 0);
+    }
+
+    #[concordium_test]
+    /// Test that querying the data of a player that is not added to the game
+    /// is rejected instead of trapping.
+    fn test_get_player_data_not_found() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+
+        let parameter_bytes = to_bytes(&GamePlayerParams {
+            game_id: DEFAULT_GAME_ID,
+            player:  PLAYER_1,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_get_player_data(&ctx, &host),
+            Err(CustomContractError::PlayerNotFound)
+        );
+
+        let parameter_bytes = to_bytes(&GamePlayerParams {
+            game_id: 1,
+            player:  PLAYER_0,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_get_player_data(&ctx, &host),
+            Err(CustomContractError::PlayerNotFound)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that deleting a player erases their data.
+    fn test_force_delete_player() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+
+        let force_delete_player = |host: &mut TestHost<State<TestStateApi>>, player: Address| {
+            let parameter_bytes = to_bytes(&player);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_force_delete_player(&ctx, host, &test_crypto_primitives())
+        };
+
+        claim!(force_delete_player(&mut host, PLAYER_0).is_ok(), "Deleting should pass");
+        claim!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).is_none(),
+            "Player data should be erased"
+        );
+        claim!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_1)).is_some(),
+            "Other players should remain"
+        );
+        claim_eq!(
+            force_delete_player(&mut host, PLAYER_0),
+            Err(CustomContractError::PlayerNotFound)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that players are only deleted once no pending match, pending
+    /// result or tournament references them, and that deleting them removes
+    /// them from the queues.
+    fn test_force_delete_referenced_player() {
+        let mut host = initialized_host();
+        host.state_mut().default_player_state = PlayerState::Active;
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        add_player(&mut host, PLAYER_2);
+
+        let force_delete_player = |host: &mut TestHost<State<TestStateApi>>, player: Address| {
+            let parameter_bytes = to_bytes(&player);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_force_delete_player(&ctx, host, &test_crypto_primitives())
+        };
+
+        // A pending match references both players until it is completed.
+        let match_id = create_match(&mut host, PLAYER_0, PLAYER_1);
+        claim_eq!(
+            force_delete_player(&mut host, PLAYER_0),
+            Err(CustomContractError::PlayerReferenced)
+        );
+        complete_match(&mut host, match_id, PLAYER_0, Timestamp::from_timestamp_millis(0));
+
+        // A pending result references both players until it is finalized.
+        host.state_mut().finality_delay_seconds = 60;
+        let parameter_bytes = to_bytes(&RecordMatchParams {
+            game_id:          DEFAULT_GAME_ID,
+            winner:           PLAYER_0,
+            loser:            PLAYER_1,
+            duration_seconds: 60,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        contract_state_record_match(&ctx, &mut host, &test_crypto_primitives())
+            .expect_report("Recording should pass");
+        claim_eq!(
+            force_delete_player(&mut host, PLAYER_1),
+            Err(CustomContractError::PlayerReferenced)
+        );
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(60_000));
+        claim_eq!(
+            contract_state_finalize_results(&ctx, &mut host, &test_crypto_primitives()),
+            Ok(1)
+        );
+
+        // A tournament references its players until its prizes are distributed.
+        let parameter_bytes = to_bytes(&CreateTournamentParams {
+            game_id:      DEFAULT_GAME_ID,
+            players:      vec![PLAYER_1, PLAYER_2],
+            payout_split: vec![10_000],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_create_tournament(&ctx, &mut host), Ok(0));
+        claim_eq!(
+            force_delete_player(&mut host, PLAYER_2),
+            Err(CustomContractError::PlayerReferenced)
+        );
+
+        // Without references the player is deleted and leaves the queues.
+        claim_eq!(join_queue(&mut host, PLAYER_0), Ok(None));
+        claim_eq!(force_delete_player(&mut host, PLAYER_0), Ok(()));
+        claim_eq!(queued_player(&host), None);
+        claim!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).is_none(),
+            "Player data should be erased"
+        );
+        // Completed matches keep the address of the deleted player.
+        claim_eq!(host.state().matches.get(&match_id).unwrap().winner, Some(PLAYER_0));
+    }
+
+    #[concordium_test]
+    /// Test that a win/loss counter at its maximum rejects another increment.
+    fn test_update_battle_result_overflow() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        host.state_mut().player_data.get_mut(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().wins =
+            u32::MAX - 1;
+
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Win);
+        claim_eq!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().wins,
+            u32::MAX
+        );
+
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            game_id:    DEFAULT_GAME_ID,
+            player:     PLAYER_0,
+            result:     BattleResult::Win,
+            client_ref: None,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_parameter(&parameter_bytes);
+
+        let result =
+            contract_state_update_battle_result(&ctx, &mut host, &test_crypto_primitives());
+        claim_eq!(result, Err(CustomContractError::ArithmeticOverflow));
+    }
+
+    #[concordium_test]
+    /// Test that only pending matches are returned by `getPendingMatches`.
+    fn test_get_pending_matches() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        add_player(&mut host, PLAYER_2);
+
+        let match_0 = create_match(&mut host, PLAYER_0, PLAYER_1);
+        let match_1 = create_match(&mut host, PLAYER_1, PLAYER_2);
+        let match_2 = create_match(&mut host, PLAYER_0, PLAYER_2);
+        claim_eq!(get_pending_match_ids(&host), vec![match_0, match_1, match_2]);
+
+        // Complete the first match.
+        let parameter_bytes = to_bytes(&CompleteMatchParams {
+            match_id: match_0,
+            winner:   PLAYER_1,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_invoker(ACCOUNT_1);
+        claim_eq!(
+            contract_state_complete_match(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::NotReferee)
+        );
+        ctx.set_invoker(REFEREE);
+        contract_state_complete_match(&ctx, &mut host, &test_crypto_primitives())
+            .expect_report("Completing should pass");
+        claim_eq!(host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap().wins, 1);
+        claim_eq!(
+            contract_state_complete_match(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::MatchNotPending)
+        );
+
+        // Cancel the second match.
+        let parameter_bytes = to_bytes(&match_1);
+        ctx.set_parameter(&parameter_bytes);
+        contract_state_cancel_match(&ctx, &mut host).expect_report("Cancelling should pass");
+
+        claim_eq!(get_pending_match_ids(&host), vec![match_2]);
+        claim_eq!(host.state().matches.get(&match_0).unwrap().winner, Some(PLAYER_1));
+        claim_eq!(host.state().matches.get(&match_1).unwrap().status, MatchStatus::Cancelled);
+    }
+
+    #[concordium_test]
+    /// Test that the first player joining the queue waits and the second one
+    /// is paired with them.
+    fn test_join_queue() {
+        let mut host = initialized_host();
+        host.state_mut().default_player_state = PlayerState::Active;
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+
+        claim_eq!(join_queue(&mut host, PLAYER_0), Ok(None));
+        claim_eq!(queued_player(&host), Some(PLAYER_0));
+        claim_eq!(join_queue(&mut host, PLAYER_0), Err(CustomContractError::AlreadyQueued));
+
+        let match_id = join_queue(&mut host, PLAYER_1)
+            .expect_report("Joining the queue should pass")
+            .expect_report("Joining a waiting player should create a match");
+        claim_eq!(queued_player(&host), None);
+        let game = host.state().matches.get(&match_id).unwrap().clone();
+        claim_eq!(game.player_a, PLAYER_0);
+        claim_eq!(game.player_b, PLAYER_1);
+        claim_eq!(game.status, MatchStatus::Pending);
+    }
+
+    #[concordium_test]
+    /// Test that only active players can join the queue.
+    fn test_join_queue_not_active() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+
+        claim_eq!(join_queue(&mut host, PLAYER_0), Err(CustomContractError::PlayerNotActive));
+        claim_eq!(join_queue(&mut host, PLAYER_1), Err(CustomContractError::PlayerNotFound));
+    }
+
+    #[concordium_test]
+    /// Test that only the waiting player can leave the queue.
+    fn test_leave_queue() {
+        let mut host = initialized_host();
+        host.state_mut().default_player_state = PlayerState::Active;
+        add_player(&mut host, PLAYER_0);
+        join_queue(&mut host, PLAYER_0).expect_report("Joining the queue should pass");
+
+        let parameter_bytes = game_player(PLAYER_1);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_leave_queue(&ctx, &mut host),
+            Err(CustomContractError::NotQueued)
+        );
+        claim_eq!(queued_player(&host), Some(PLAYER_0));
+
+        let parameter_bytes = game_player(PLAYER_0);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_leave_queue(&ctx, &mut host), Ok(()));
+        claim_eq!(queued_player(&host), None);
+    }
+
+    #[concordium_test]
+    /// Test that a profile is moved to the new address together with its
+    /// pending matches.
+    fn test_transfer_profile() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Win);
+        let match_id = create_match(&mut host, PLAYER_0, PLAYER_1);
+
+        let transfer_profile = |host: &mut TestHost<State<TestStateApi>>, from, to| {
+            let parameter_bytes = to_bytes(&TransferProfileParams {
+                game_id: DEFAULT_GAME_ID,
+                from,
+                to,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_transfer_profile(&ctx, host, &test_crypto_primitives())
+        };
+
+        claim_eq!(
+            transfer_profile(&mut host, PLAYER_0, PLAYER_1),
+            Err(CustomContractError::AlreadyAdded)
+        );
+        claim_eq!(
+            transfer_profile(&mut host, PLAYER_2, PLAYER_2),
+            Err(CustomContractError::PlayerNotFound)
+        );
+        claim_eq!(transfer_profile(&mut host, PLAYER_0, PLAYER_2), Ok(()));
+
+        claim!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).is_none(),
+            "Old key should be removed"
+        );
+        claim_eq!(host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_2)).unwrap().wins, 1);
+        claim_eq!(host.state().matches.get(&match_id).unwrap().player_a, PLAYER_2);
+    }
+
+    #[concordium_test]
+    /// Test that stakes are added per player and returned on completion, and
+    /// that only matches between accounts can be staked on.
+    fn test_stake_match() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        let match_id = create_match(&mut host, PLAYER_0, PLAYER_1);
+
+        for (player, amount) in [(PLAYER_0, 100), (PLAYER_1, 200), (PLAYER_2, 300)] {
+            let parameter_bytes = to_bytes(&StakeMatchParams {
+                match_id,
+                player,
+                amount: Amount::from_micro_ccd(amount),
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            let result = contract_state_stake_match(&ctx, &mut host);
+            if player == PLAYER_2 {
+                claim_eq!(result, Err(CustomContractError::InvalidMatch));
+            } else {
+                claim_eq!(result, Ok(()));
+            }
+        }
+
+        let parameter_bytes = to_bytes(&CompleteMatchParams {
+            match_id,
+            winner: PLAYER_0,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.set_invoker(REFEREE);
+        let completed = contract_state_complete_match(&ctx, &mut host, &test_crypto_primitives());
+        claim_eq!(completed.map(|completed| completed.pot), Ok(Amount::from_micro_ccd(300)));
+
+        // Matches against contracts can not be staked on.
+        let contract = Address::Contract(PROXY);
+        add_player(&mut host, contract);
+        let match_id = create_match(&mut host, PLAYER_0, contract);
+        let parameter_bytes = to_bytes(&StakeMatchParams {
+            match_id,
+            player: PLAYER_0,
+            amount: Amount::from_micro_ccd(100),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_stake_match(&ctx, &mut host),
+            Err(CustomContractError::OnlyAccount)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the stake of a player on a pending match can only be
+    /// refunded once, and only after the shutdown.
+    fn test_refund_stake() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        let match_id = create_match(&mut host, PLAYER_0, PLAYER_1);
+        host.state_mut().matches.get_mut(&match_id).unwrap().stake_a = Amount::from_micro_ccd(100);
+
+        let refund = |host: &mut TestHost<State<TestStateApi>>, player| {
+            let parameter_bytes = to_bytes(&RefundStakeParams {
+                match_id,
+                player,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_refund_stake(&ctx, host)
+        };
+        claim_eq!(refund(&mut host, PLAYER_0), Err(CustomContractError::NotShutDown));
+
+        host.state_mut().shutdown = true;
+        claim_eq!(refund(&mut host, PLAYER_2), Err(CustomContractError::InvalidMatch));
+        claim_eq!(refund(&mut host, PLAYER_0), Ok(Amount::from_micro_ccd(100)));
+        claim_eq!(refund(&mut host, PLAYER_0), Ok(Amount::zero()));
+        claim_eq!(refund(&mut host, PLAYER_1), Ok(Amount::zero()));
+    }
+
+    #[concordium_test]
+    /// Test that the winner is only rewarded for the first match of two players
+    /// within the reward cooldown, independently of who wins.
+    fn test_pair_reward_cooldown() {
+        let mut host = initialized_host();
+        for player in [PLAYER_0, PLAYER_1, PLAYER_2] {
+            add_player(&mut host, player);
+        }
+        let hour = 60 * 60 * 1000;
+        let cooldown_end = REWARD_PAIR_COOLDOWN_HOURS * hour;
+
+        for (player_a, player_b, winner, time, rewardable) in [
+            (PLAYER_0, PLAYER_1, PLAYER_0, 0, true),
+            (PLAYER_0, PLAYER_1, PLAYER_1, hour, false),
+            (PLAYER_2, PLAYER_0, PLAYER_0, hour, true),
+            (PLAYER_1, PLAYER_0, PLAYER_0, cooldown_end, false),
+            (PLAYER_1, PLAYER_0, PLAYER_0, cooldown_end + 1, true),
+        ] {
+            let match_id = create_match(&mut host, player_a, player_b);
+            let time = Timestamp::from_timestamp_millis(time);
+            let completed = complete_match(&mut host, match_id, winner, time);
+            claim_eq!(completed.rewardable, rewardable);
+        }
+    }
+
+    #[concordium_test]
+    /// Test that a match disputed within the window can be overturned, which
+    /// swaps the recorded result of both players.
+    fn test_dispute_match_overturned() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        let match_id = create_match(&mut host, PLAYER_0, PLAYER_1);
+        complete_match(&mut host, match_id, PLAYER_0, Timestamp::from_timestamp_millis(0));
+        claim_eq!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().elo,
+            ELO_INITIAL_RATING + 16
+        );
+
+        let one_hour = Timestamp::from_timestamp_millis(60 * 60 * 1000);
+        claim_eq!(
+            dispute_match(&mut host, match_id, PLAYER_2, one_hour),
+            Err(CustomContractError::InvalidMatch)
+        );
+        claim_eq!(dispute_match(&mut host, match_id, PLAYER_1, one_hour), Ok(()));
+        claim_eq!(host.state().matches.get(&match_id).unwrap().status, MatchStatus::Disputed);
+
+        let parameter_bytes = to_bytes(&ResolveDisputeParams {
+            match_id,
+            overturn: true,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_resolve_dispute(&ctx, &mut host, &test_crypto_primitives()),
+            Ok(())
+        );
+        claim_eq!(
+            contract_state_resolve_dispute(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::MatchNotDisputed)
+        );
+
+        let game = host.state().matches.get(&match_id).unwrap().clone();
+        claim_eq!(game.status, MatchStatus::Resolved);
+        claim_eq!(game.winner, Some(PLAYER_1));
+        let player_0 = host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().clone();
+        let player_1 = host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap().clone();
+        claim_eq!((player_0.wins, player_0.losses), (0, 1));
+        claim_eq!((player_1.wins, player_1.losses), (1, 0));
+        claim_eq!(player_0.elo, ELO_INITIAL_RATING - 16);
+        claim_eq!(player_1.elo, ELO_INITIAL_RATING + 16);
+    }
+
+    #[concordium_test]
+    /// Test that a match can not be disputed after the dispute window.
+    fn test_dispute_match_late() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        let match_id = create_match(&mut host, PLAYER_0, PLAYER_1);
+        claim_eq!(
+            dispute_match(&mut host, match_id, PLAYER_1, Timestamp::from_timestamp_millis(0)),
+            Err(CustomContractError::MatchNotCompleted)
+        );
+        complete_match(&mut host, match_id, PLAYER_0, Timestamp::from_timestamp_millis(0));
+
+        let after_window =
+            Timestamp::from_timestamp_millis(DISPUTE_WINDOW_HOURS * 60 * 60 * 1000 + 1);
+        claim_eq!(
+            dispute_match(&mut host, match_id, PLAYER_1, after_window),
+            Err(CustomContractError::DisputeWindowClosed)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that a result recorded after the end of a season starts a new
+    /// season with reset stats before the result is applied, and that players
+    /// without a result in the new season are reset when they are accessed.
+    fn test_season_rollover() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        add_player(&mut host, PLAYER_2);
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let parameter_bytes = to_bytes(&(MIN_SEASON_LENGTH_SECONDS - 1));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_season_length(&ctx, &mut host),
+            Err(CustomContractError::SeasonTooShort)
+        );
+        let parameter_bytes = to_bytes(&MIN_SEASON_LENGTH_SECONDS);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_set_season_length(&ctx, &mut host), Ok(()));
+
+        let record_match = |host: &mut TestHost<State<TestStateApi>>, winner, loser, seconds| {
+            let parameter_bytes = to_bytes(&RecordMatchParams {
+                game_id:          DEFAULT_GAME_ID,
+                winner,
+                loser,
+                duration_seconds: 60,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(seconds * 1000));
+            contract_state_record_match(&ctx, host, &test_crypto_primitives())
+                .expect_report("Recording should pass");
+        };
+
+        record_match(&mut host, PLAYER_0, PLAYER_1, 10);
+        record_match(&mut host, PLAYER_2, PLAYER_1, 20);
+        record_match(&mut host, PLAYER_0, PLAYER_1, MIN_SEASON_LENGTH_SECONDS - 1);
+        claim_eq!(host.state().current_season, 0);
+        claim_eq!(host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().wins, 2);
+
+        record_match(&mut host, PLAYER_1, PLAYER_0, MIN_SEASON_LENGTH_SECONDS);
+        claim_eq!(host.state().current_season, 1);
+        let player_0 = host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().clone();
+        let player_1 = host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap().clone();
+        claim_eq!((player_0.wins, player_0.losses), (0, 1));
+        claim_eq!((player_1.wins, player_1.losses), (1, 0));
+        claim_eq!(player_1.elo, ELO_INITIAL_RATING + 16);
+        claim_eq!((player_0.season, player_1.season), (1, 1));
+
+        // The stored stats of the player without a result in the new season
+        // are only reset once the player is accessed.
+        let stored = host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_2)).unwrap().clone();
+        claim_eq!((stored.wins, stored.season), (1, 0));
+        let parameter_bytes = game_player(PLAYER_2);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_parameter(&parameter_bytes);
+        let player_2 =
+            contract_state_get_player_full(&ctx, &host).expect_report("Query should pass");
+        claim_eq!((player_2.wins, player_2.losses), (0, 0));
+        claim_eq!(player_2.elo, ELO_INITIAL_RATING);
+        claim_eq!(player_2.season, 1);
+    }
+
+    #[concordium_test]
+    /// Test that with the rollover lock set, a result submitted after the end
+    /// of a season is rejected until the rollover is applied, and then counts
+    /// for the new season.
+    fn test_season_rollover_lock() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let parameter_bytes = to_bytes(&MIN_SEASON_LENGTH_SECONDS);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_set_season_length(&ctx, &mut host), Ok(()));
+        let parameter_bytes = to_bytes(&true);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_set_rollover_lock(&ctx, &mut host), Ok(()));
+
+        let record_match = |host: &mut TestHost<State<TestStateApi>>, seconds| {
+            let parameter_bytes = to_bytes(&RecordMatchParams {
+                game_id:          DEFAULT_GAME_ID,
+                winner:           PLAYER_0,
+                loser:            PLAYER_1,
+                duration_seconds: 60,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(seconds * 1000));
+            contract_state_record_match(&ctx, host, &test_crypto_primitives())
+        };
+        let wins = |host: &TestHost<State<TestStateApi>>| {
+            player_in_season(host.state(), &(DEFAULT_GAME_ID, PLAYER_0)).unwrap().wins
+        };
+
+        claim_eq!(record_match(&mut host, MIN_SEASON_LENGTH_SECONDS - 1), Ok(()));
+        claim_eq!(
+            record_match(&mut host, MIN_SEASON_LENGTH_SECONDS),
+            Err(CustomContractError::SeasonRollover)
+        );
+        claim_eq!(host.state().current_season, 0);
+        claim_eq!(wins(&host), 1);
+
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(
+            MIN_SEASON_LENGTH_SECONDS * 1000,
+        ));
+        claim_eq!(contract_state_roll_over_season(&ctx, &mut host), Ok(1));
+        claim_eq!(wins(&host), 0);
+        claim_eq!(record_match(&mut host, MIN_SEASON_LENGTH_SECONDS), Ok(()));
+        claim_eq!(wins(&host), 1);
+        claim_eq!(contract_state_roll_over_season(&ctx, &mut host), Ok(1));
+    }
+
+    #[concordium_test]
+    /// Test that added players are counted in the season they were added in,
+    /// and that players added again are not counted twice.
+    fn test_players_added_in_season() {
+        let mut host = initialized_host();
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let parameter_bytes = to_bytes(&MIN_SEASON_LENGTH_SECONDS);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_set_season_length(&ctx, &mut host), Ok(()));
+
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        add_player(&mut host, PLAYER_1);
+        for player in [PLAYER_1, PLAYER_2] {
+            let parameter_bytes = to_bytes(&GamePlayerParams {
+                game_id: DEFAULT_GAME_ID,
+                player,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(
+                MIN_SEASON_LENGTH_SECONDS * 1000,
+            ));
+            claim_eq!(
+                contract_state_set_player_data(&ctx, &mut host, &test_crypto_primitives()),
+                Ok(())
+            );
+        }
+
+        let players_added = |season: u32| {
+            let parameter_bytes = to_bytes(&season);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_get_players_added_in_season(&ctx, &host)
+        };
+        claim_eq!(players_added(0), Ok(2));
+        claim_eq!(players_added(1), Ok(1));
+        claim_eq!(players_added(2), Ok(0));
+    }
+
+    #[concordium_test]
+    /// Test that proofs of present and absent players verify against the
+    /// state root and that the root follows the writes of player data.
+    fn test_player_proofs() {
+        let mut host = initialized_host();
+        let crypto_primitives = test_crypto_primitives();
+        let ctx = TestReceiveContext::empty();
+        let state_root = |host: &TestHost<State<TestStateApi>>| {
+            contract_state_get_state_root(&ctx, host).expect_report("Getting the root should pass")
+        };
+        claim_eq!(state_root(&host), EMPTY_NODE);
+
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_2);
+        add_player(&mut host, Address::Contract(PROXY));
+        update_battle_result(&mut host, PLAYER_2, BattleResult::Win);
+        let root = state_root(&host);
+
+        let get_proof = |host: &TestHost<State<TestStateApi>>, player: Address| {
+            let parameter_bytes = to_bytes(&player);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_get_player_proof(&ctx, host, &crypto_primitives)
+                .expect_report("Getting a proof should pass")
+        };
+
+        for player in [PLAYER_0, PLAYER_2, Address::Contract(PROXY)] {
+            let proof = get_proof(&host, player);
+            claim!(verify_proof(&crypto_primitives, &root, &proof), "Proof should verify");
+            let stored = host.state().player_data.get(&(DEFAULT_GAME_ID, player)).unwrap().clone();
+            let entry = to_bytes(&(player, stored));
+            claim!(
+                proof.players.iter().any(|proven| to_bytes(proven) == entry),
+                "Proof should contain the player data"
+            );
+        }
+
+        let proof = get_proof(&host, PLAYER_1);
+        claim!(verify_proof(&crypto_primitives, &root, &proof), "Proof should verify");
+        claim!(
+            proof.players.iter().all(|entry| entry.0 != PLAYER_1),
+            "Proof should show that the player is absent"
+        );
+
+        // Writing player data moves the root, so outdated proofs stop verifying.
+        let outdated_proof = get_proof(&host, PLAYER_0);
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Win);
+        let new_root = state_root(&host);
+        claim!(new_root != root, "State root should change");
+        claim!(
+            !verify_proof(&crypto_primitives, &new_root, &outdated_proof),
+            "An outdated proof should not verify"
+        );
+        claim!(
+            verify_proof(&crypto_primitives, &new_root, &get_proof(&host, PLAYER_0)),
+            "A new proof should verify"
+        );
+
+        // Removing a player restores the root from before they were added.
+        add_player(&mut host, PLAYER_1);
+        claim!(state_root(&host) != new_root, "State root should change");
+        let delete = |host: &mut TestHost<State<TestStateApi>>, player: Address| {
+            let parameter_bytes = to_bytes(&player);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_force_delete_player(&ctx, host, &crypto_primitives)
+        };
+        claim_eq!(delete(&mut host, PLAYER_1), Ok(()));
+        claim_eq!(state_root(&host), new_root);
+        for player in [PLAYER_0, PLAYER_2, Address::Contract(PROXY)] {
+            claim_eq!(delete(&mut host, player), Ok(()));
+        }
+        claim_eq!(state_root(&host), EMPTY_NODE);
+    }
+
+    #[concordium_test]
+    /// Test that the rating of a provisional player moves faster than the
+    /// rating of an established player for the same result.
+    fn test_elo_params() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        host.state_mut().player_data.get_mut(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap().wins = 5;
+        host.state_mut().player_data.get_mut(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap().losses = 5;
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let parameter_bytes = to_bytes(&EloParams {
+            provisional_k:       0,
+            established_k:       16,
+            provisional_matches: 10,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_elo_params(&ctx, &mut host),
+            Err(CustomContractError::InvalidEloParams)
+        );
+        let parameter_bytes = to_bytes(&EloParams {
+            provisional_k:       40,
+            established_k:       16,
+            provisional_matches: 10,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_set_elo_params(&ctx, &mut host), Ok(()));
+
+        // The new player wins against the established player.
+        let parameter_bytes = to_bytes(&RecordMatchParams {
+            game_id:          DEFAULT_GAME_ID,
+            winner:           PLAYER_0,
+            loser:            PLAYER_1,
+            duration_seconds: 60,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        contract_state_record_match(&ctx, &mut host, &test_crypto_primitives())
+            .expect_report("Recording should pass");
+
+        claim_eq!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().elo,
+            ELO_INITIAL_RATING + 20
+        );
+        claim_eq!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap().elo,
+            ELO_INITIAL_RATING - 8
+        );
+    }
+
+    #[concordium_test]
+    /// Test that following the cursor of `getMatches` returns every match
+    /// exactly once in order.
+    fn test_get_matches_pagination() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        for _ in 0..5 {
+            create_match(&mut host, PLAYER_0, PLAYER_1);
+        }
+
+        let mut match_ids = Vec::new();
+        let mut cursor = Some(0);
+        let mut pages = 0;
+        while let Some(from) = cursor {
+            let parameter_bytes = to_bytes(&GetMatchesParams {
+                from,
+                limit: 2,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            let page = contract_state_get_matches(&ctx, &host).expect_report("Query should pass");
+            claim!(page.matches.len() <= 2, "Page should not exceed the limit");
+            match_ids.extend(page.matches.iter().map(|(match_id, _)| *match_id));
+            cursor = page.next;
+            pages += 1;
+        }
+
+        claim_eq!(match_ids, vec![0, 1, 2, 3, 4]);
+        claim_eq!(pages, 3);
+    }
+
+    #[concordium_test]
+    /// Test that a recorded result only counts once it is finalized after
+    /// the finality delay, and that results are finalized in recording order.
+    fn test_finalize_results() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        host.state_mut().finality_delay_seconds = 60;
+
+        let parameter_bytes = to_bytes(&RecordMatchParams {
+            game_id:          DEFAULT_GAME_ID,
+            winner:           PLAYER_0,
+            loser:            PLAYER_1,
+            duration_seconds: 60,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        contract_state_record_match(&ctx, &mut host, &test_crypto_primitives())
+            .expect_report("Recording should pass");
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(30_000));
+        contract_state_record_match(&ctx, &mut host, &test_crypto_primitives())
+            .expect_report("Recording should pass");
+        claim_eq!(host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().wins, 0);
+
+        let finalize_results = |host: &mut TestHost<State<TestStateApi>>, seconds: u64| {
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(seconds * 1000));
+            contract_state_finalize_results(&ctx, host, &test_crypto_primitives())
+                .expect_report("Finalizing should pass")
+        };
+
+        claim_eq!(finalize_results(&mut host, 59), 0);
+        claim_eq!(host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().wins, 0);
+        claim_eq!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap().elo,
+            ELO_INITIAL_RATING
+        );
+
+        claim_eq!(finalize_results(&mut host, 60), 1);
+        claim_eq!(host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().wins, 1);
+        claim_eq!(host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap().losses, 1);
+        claim_eq!(host.state().pending_results_head, 1);
+
+        claim_eq!(finalize_results(&mut host, 90), 1);
+        claim_eq!(host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().wins, 2);
+        claim_eq!(host.state().pending_results_head, 2);
+        claim!(host.state().pending_results.is_empty(), "No results should be pending");
+        claim_eq!(finalize_results(&mut host, 120), 0);
+    }
+
+    #[concordium_test]
+    /// Test that results inside a grace period are rejected when recorded and
+    /// that a pending result which can not be applied is dropped without
+    /// blocking the results behind it.
+    fn test_finalize_results_grace_period() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        host.state_mut().finality_delay_seconds = 60;
+        host.state_mut().battle_grace_seconds = 100;
+
+        let parameter_bytes = to_bytes(&RecordMatchParams {
+            game_id:          DEFAULT_GAME_ID,
+            winner:           PLAYER_0,
+            loser:            PLAYER_1,
+            duration_seconds: 60,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(50_000));
+        claim_eq!(
+            contract_state_record_match(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::GracePeriodActive)
+        );
+
+        // A result inside the grace period that was queued before it was
+        // checked on recording.
+        let state = host.state_mut();
+        state.pending_results.insert(0, PendingResult {
+            game_id:     DEFAULT_GAME_ID,
+            winner:      PLAYER_0,
+            loser:       PLAYER_1,
+            recorded_at: Timestamp::from_timestamp_millis(50_000),
+        });
+        state.next_result_id = 1;
+
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(150_000));
+        claim_eq!(contract_state_record_match(&ctx, &mut host, &test_crypto_primitives()), Ok(()));
+
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(210_000));
+        claim_eq!(
+            contract_state_finalize_results(&ctx, &mut host, &test_crypto_primitives()),
+            Ok(1)
+        );
+        claim_eq!(host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().wins, 1);
+        claim_eq!(host.state().pending_results_head, 2);
+        claim!(host.state().pending_results.is_empty(), "No results should be pending");
+    }
+
+    #[concordium_test]
+    /// Test that an emergency shutdown blocks writes permanently while reads
+    /// keep working.
+    fn test_emergency_shutdown() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        claim_eq!(contract_state_emergency_shutdown(&ctx, &mut host), Ok(()));
+        claim_eq!(
+            contract_state_emergency_shutdown(&ctx, &mut host),
+            Err(CustomContractError::ContractShutDown)
+        );
+
+        let parameter_bytes = to_bytes(&PLAYER_1);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_player_data(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::ContractShutDown)
+        );
+
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            game_id:    DEFAULT_GAME_ID,
+            player:     PLAYER_0,
+            result:     BattleResult::Win,
+            client_ref: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_update_battle_result(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::ContractShutDown)
+        );
+
+        let parameter_bytes = to_bytes(&SetPausedParams {
+            paused: false,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_paused(&ctx, &mut host),
+            Err(CustomContractError::ContractShutDown)
+        );
+
+        claim!(host.state().shutdown, "Shutdown should not be reversible");
+        claim_eq!(
+            get_player_data(&host, PLAYER_0),
+            (PlayerState::Suspended, BattleResult::NoResult)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the complete player data is returned and that unknown players
+    /// are rejected.
+    fn test_get_player_full() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Loss);
+
+        let get_player_full = |player: Address| {
+            let parameter_bytes = game_player(player);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_get_player_full(&ctx, &host)
+        };
+
+        let player_data = get_player_full(PLAYER_0).expect_report("Query should pass");
+        claim_eq!(player_data.state, PlayerState::Suspended);
+        claim_eq!(player_data.last_result, BattleResult::Loss);
+        claim_eq!(player_data.wins, 0);
+        claim_eq!(player_data.losses, 1);
+        claim_eq!(player_data.elo, ELO_INITIAL_RATING);
+
+        claim!(
+            matches!(get_player_full(PLAYER_1), Err(CustomContractError::PlayerNotFound)),
+            "Unknown players should be rejected"
+        );
+    }
+
+    #[concordium_test]
+    /// Test that only referees can submit battle results, record matches and
+    /// finalize results once referees are required.
+    fn test_referee_only() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_2);
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let parameter_bytes = to_bytes(&PLAYER_1);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_add_referee(&ctx, &mut host), Ok(()));
+        let parameter_bytes = to_bytes(&true);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_set_referee_only(&ctx, &mut host), Ok(()));
+
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            game_id:    DEFAULT_GAME_ID,
+            player:     PLAYER_0,
+            result:     BattleResult::Win,
+            client_ref: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_invoker(ACCOUNT_1);
+        claim!(
+            contract_state_update_battle_result(&ctx, &mut host, &test_crypto_primitives()).is_ok()
+        );
+        ctx.set_invoker(ACCOUNT_0);
+        claim_eq!(
+            contract_state_update_battle_result(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::NotReferee)
+        );
+        claim_eq!(get_player_data(&host, PLAYER_0).1, BattleResult::Win);
+
+        let parameter_bytes = to_bytes(&RecordMatchParams {
+            game_id:          DEFAULT_GAME_ID,
+            winner:           PLAYER_2,
+            loser:            PLAYER_0,
+            duration_seconds: 60,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_record_match(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::NotReferee)
+        );
+        claim_eq!(
+            contract_state_finalize_results(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::NotReferee)
+        );
+        ctx.set_invoker(ACCOUNT_1);
+        claim_eq!(contract_state_record_match(&ctx, &mut host, &test_crypto_primitives()), Ok(()));
+        claim_eq!(get_player_data(&host, PLAYER_2).1, BattleResult::Win);
+        claim_eq!(
+            contract_state_finalize_results(&ctx, &mut host, &test_crypto_primitives()),
+            Ok(0)
+        );
+
+        let parameter_bytes = to_bytes(&PLAYER_1);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_remove_referee(&ctx, &mut host), Ok(()));
+        claim_eq!(
+            contract_state_remove_referee(&ctx, &mut host),
+            Err(CustomContractError::NotReferee)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that player data written before the counters and the Elo rating
+    /// were added is read with default values for them.
+    fn test_player_data_versions() {
+        let mut v0_bytes = vec![0u8];
+        v0_bytes.extend(to_bytes(&PlayerState::Active));
+        v0_bytes.extend(to_bytes(&BattleResult::Win));
+        let player_data: PlayerData =
+            from_bytes(&v0_bytes).expect_report("Version 0 data should parse");
+        claim_eq!(player_data.state, PlayerState::Active);
+        claim_eq!(player_data.last_result, BattleResult::Win);
+        claim_eq!(player_data.wins, 0);
+        claim_eq!(player_data.losses, 0);
+        claim_eq!(player_data.elo, ELO_INITIAL_RATING);
+        claim_eq!(player_data.version, PLAYER_DATA_VERSION);
+        claim!(player_data.state_history.to_vec().is_empty(), "History should be empty");
+        claim_eq!(player_data.registered_at, Timestamp::from_timestamp_millis(0));
+        claim!(player_data.rate_window.submitted.is_empty(), "No results should be counted");
+        claim_eq!(player_data.season, 0);
+
+        let mut player_data = PlayerData {
+            version:        PLAYER_DATA_VERSION,
+            state:          PlayerState::Suspended,
+            last_result:    BattleResult::Loss,
+            wins:           4,
+            losses:         7,
+            elo:            1150,
+            state_history:  StateHistory::new(),
+            current_streak: 0,
+            best_streak:    0,
+            last_updated:   Timestamp::from_timestamp_millis(20),
+            registered_at:  Timestamp::from_timestamp_millis(5),
+            rate_window:    RateWindow {
+                submitted: vec![Timestamp::from_timestamp_millis(20)],
+            },
+            season:         3,
+        };
+        let suspended_at = Timestamp::from_timestamp_millis(10);
+        player_data.state_history.push(PlayerState::Suspended, suspended_at);
+        let bytes = to_bytes(&player_data);
+        claim_eq!(bytes[0], PLAYER_DATA_VERSION);
+        let parsed: PlayerData = from_bytes(&bytes).expect_report("Current data should parse");
+        claim_eq!(parsed.state, PlayerState::Suspended);
+        claim_eq!(parsed.last_result, BattleResult::Loss);
+        claim_eq!((parsed.wins, parsed.losses, parsed.elo), (4, 7, 1150));
+        claim_eq!(parsed.state_history.to_vec(), vec![(PlayerState::Suspended, suspended_at)]);
+        claim_eq!(parsed.registered_at, Timestamp::from_timestamp_millis(5));
+        claim_eq!(parsed.rate_window.submitted, vec![Timestamp::from_timestamp_millis(20)]);
+        claim_eq!(parsed.season, 3);
+
+        // The fixed window of version 7 counts as results at its start.
+        let mut v7_bytes = vec![7u8];
+        v7_bytes.extend(to_bytes(&(PlayerState::Active, BattleResult::Win)));
+        v7_bytes.extend(to_bytes(&(1u32, 0u32)));
+        v7_bytes.extend(to_bytes(&1210i32));
+        v7_bytes.extend(to_bytes(&StateHistory::new()));
+        v7_bytes.extend(to_bytes(&(1i32, 1u32)));
+        v7_bytes.extend(to_bytes(&Timestamp::from_timestamp_millis(20)));
+        v7_bytes.extend(to_bytes(&Timestamp::from_timestamp_millis(5)));
+        v7_bytes.extend(to_bytes(&(Timestamp::from_timestamp_millis(15), 2u32)));
+        v7_bytes.extend(to_bytes(&3u32));
+        let parsed: PlayerData =
+            from_bytes(&v7_bytes).expect_report("Version 7 data should parse");
+        claim_eq!(parsed.rate_window.submitted, vec![Timestamp::from_timestamp_millis(15); 2]);
+        claim_eq!(parsed.season, 3);
+
+        let mut future_bytes = bytes;
+        future_bytes[0] = PLAYER_DATA_VERSION + 1;
+        claim!(from_bytes::<PlayerData>(&future_bytes).is_err(), "Unknown versions should fail");
+    }
+
+    #[concordium_test]
+    /// Test that the state history keeps the most recent transitions in order.
+    fn test_player_state_history() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+
+        let transitions = STATE_HISTORY_LENGTH as u64 + 3;
+        for i in 0..transitions {
+            let state = if i % 3 == 0 {
+                PlayerState::Suspended
+            } else {
+                PlayerState::Active
+            };
+            let parameter_bytes = to_bytes(&UpdatePlayerStateParams {
+                game_id: DEFAULT_GAME_ID,
+                player:  PLAYER_0,
+                state,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(i));
+            contract_state_update_player_state(&ctx, &mut host, &test_crypto_primitives())
+                .expect_report("Updating the player state should pass");
+        }
+
+        let parameter_bytes = game_player(PLAYER_0);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_parameter(&parameter_bytes);
+        let history = contract_state_get_player_state_history(&ctx, &host)
+            .expect_report("Query should pass");
+
+        claim_eq!(history.len(), STATE_HISTORY_LENGTH);
+        let times: Vec<u64> = history.iter().map(|(_, time)| time.timestamp_millis()).collect();
+        claim_eq!(times, (3..transitions).collect::<Vec<u64>>());
+        claim_eq!(history[0].0, PlayerState::Suspended);
+        claim_eq!(history[1].0, PlayerState::Active);
+    }
+
+    #[concordium_test]
+    /// Test that wins extend a win streak, losses start a losing streak and
+    /// `NoResult` keeps the streak, also for recorded matches.
+    fn test_win_streak() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+
+        let get_win_streak = |host: &TestHost<State<TestStateApi>>, player: Address| {
+            let parameter_bytes = game_player(player);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_get_win_streak(&ctx, host).expect_report("Query should pass")
+        };
+        let streak = |current, best| WinStreak {
+            current,
+            best,
+        };
+
+        let sequence = [
+            (BattleResult::Win, streak(1, 1)),
+            (BattleResult::Win, streak(2, 2)),
+            (BattleResult::NoResult, streak(2, 2)),
+            (BattleResult::Win, streak(3, 3)),
+            (BattleResult::Loss, streak(-1, 3)),
+            (BattleResult::Loss, streak(-2, 3)),
+            (BattleResult::NoResult, streak(-2, 3)),
+            (BattleResult::Win, streak(1, 3)),
+        ];
+        for (result, expected) in sequence {
+            update_battle_result(&mut host, PLAYER_0, result);
+            claim_eq!(get_win_streak(&host, PLAYER_0), expected);
+        }
+
+        let parameter_bytes = to_bytes(&RecordMatchParams {
+            game_id:          DEFAULT_GAME_ID,
+            winner:           PLAYER_0,
+            loser:            PLAYER_1,
+            duration_seconds: 60,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        claim_eq!(contract_state_record_match(&ctx, &mut host, &test_crypto_primitives()), Ok(()));
+        claim_eq!(get_win_streak(&host, PLAYER_0), streak(2, 3));
+        claim_eq!(get_win_streak(&host, PLAYER_1), streak(-1, 0));
+    }
+
+    #[concordium_test]
+    /// Test that contracts can not be added as players once only accounts are
+    /// allowed.
+    fn test_accounts_only() {
+        let mut host = initialized_host();
+        let contract_player = Address::Contract(ContractAddress {
+            index:    10,
+            subindex: 0,
+        });
+        add_player(&mut host, contract_player);
+
+        let parameter_bytes = to_bytes(&true);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_set_accounts_only(&ctx, &mut host), Ok(()));
+
+        add_player(&mut host, PLAYER_0);
+
+        let parameter_bytes = to_bytes(&GamePlayerParams {
+            game_id: DEFAULT_GAME_ID,
+            player:  Address::Contract(ContractAddress {
+                index:    11,
+                subindex: 0,
+            }),
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_player_data(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::ContractPlayerNotAllowed)
+        );
+        claim!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).is_some(),
+            "Account should be added"
+        );
+        claim!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, contract_player)).is_some(),
+            "Existing contract players should be kept"
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the proxy can set the paused flag, but other contracts can
+    /// not.
+    fn test_set_paused_by_proxy() {
+        let mut host = initialized_host();
+
+        let parameter_bytes = to_bytes(&SetPausedParams {
+            paused: true,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        claim_eq!(contract_state_set_paused(&ctx, &mut host), Ok(()));
+        claim!(host.state().paused, "Proxy should be able to pause");
+
+        ctx.set_sender(PLAYER_0);
+        claim_eq!(
+            contract_state_set_paused(&ctx, &mut host),
+            Err(CustomContractError::OnlyImplementation)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that only inactive players decay and that no rating decays below
+    /// the floor.
+    fn test_decay_ratings() {
+        let mut host = initialized_host();
+        for player in [PLAYER_0, PLAYER_1, PLAYER_2] {
+            add_player(&mut host, player);
+        }
+        host.state_mut().player_data.get_mut(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().elo = 1300;
+        host.state_mut().player_data.get_mut(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap().elo = 1230;
+        host.state_mut().player_data.get_mut(&(DEFAULT_GAME_ID, PLAYER_2)).unwrap().elo = 1300;
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        let parameter_bytes = to_bytes(&DecayParams {
+            inactivity_seconds: 100,
+            amount:             50,
+            floor:              1200,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_set_decay_params(&ctx, &mut host), Ok(()));
+
+        // The third player is active.
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            game_id:    DEFAULT_GAME_ID,
+            player:     PLAYER_2,
+            result:     BattleResult::Win,
+            client_ref: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(50_000));
+        claim!(
+            contract_state_update_battle_result(&ctx, &mut host, &test_crypto_primitives()).is_ok()
+        );
+
+        let mut decay_ratings = |after, limit| {
+            let parameter_bytes = to_bytes(&PlayerCursorParams {
+                after,
+                limit,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(120_000));
+            contract_state_decay_ratings(&ctx, &mut host, &test_crypto_primitives())
+                .expect_report("Decay should pass")
+        };
+        let next = decay_ratings(None, 2);
+        claim!(next.is_some(), "A second page should follow");
+        claim_eq!(decay_ratings(next, 2), None);
+        // A second sweep does not decay the same players again.
+        claim_eq!(decay_ratings(None, 10), None);
+
+        let elo = |player| host.state().player_data.get(&(DEFAULT_GAME_ID, player)).unwrap().elo;
+        claim_eq!(elo(PLAYER_0), 1250);
+        claim_eq!(elo(PLAYER_1), 1200);
+        claim_eq!(elo(PLAYER_2), 1300);
+    }
+
+    #[concordium_test]
+    /// Test that results can only be recorded for a player once the grace
+    /// period after adding them has passed.
+    fn test_battle_grace_period() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+
+        let parameter_bytes = to_bytes(&3_600u64);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_set_battle_grace_period(&ctx, &mut host), Ok(()));
+
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            game_id:    DEFAULT_GAME_ID,
+            player:     PLAYER_0,
+            result:     BattleResult::Win,
+            client_ref: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(3_599_999));
+        claim_eq!(
+            contract_state_update_battle_result(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::GracePeriodActive)
+        );
+        claim_eq!(get_player_data(&host, PLAYER_0).1, BattleResult::NoResult);
+
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(3_600_000));
+        claim!(
+            contract_state_update_battle_result(&ctx, &mut host, &test_crypto_primitives()).is_ok()
+        );
+        claim_eq!(get_player_data(&host, PLAYER_0).1, BattleResult::Win);
+    }
+
+    #[concordium_test]
+    /// Test that all players are reset over two pages, also if a player that
+    /// was already reset is removed between the pages.
+    fn test_reset_all_players() {
+        let mut host = initialized_host();
+        for player in [PLAYER_0, PLAYER_1, PLAYER_2] {
+            add_player(&mut host, player);
+            update_battle_result(&mut host, player, BattleResult::Win);
+            host.state_mut().player_data.get_mut(&(DEFAULT_GAME_ID, player)).unwrap().elo = 1300;
+        }
+
+        let reset_all_players = |host: &mut TestHost<State<TestStateApi>>, after, limit| {
+            let parameter_bytes = to_bytes(&PlayerCursorParams {
+                after,
+                limit,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_reset_all_players(&ctx, host, &test_crypto_primitives())
+                .expect_report("Reset should pass")
+        };
+        let keys: Vec<(GameId, Address)> =
+            host.state().player_data.iter().map(|(key, _)| *key).collect();
+        let progress = reset_all_players(&mut host, None, 2);
+        claim_eq!(progress, ResetProgress {
+            next: Some(keys[1]),
+            done: false,
+        });
+
+        // Removing a player of the first page does not skip a player.
+        host.state_mut().player_data.remove(&keys[0]);
+        claim_eq!(reset_all_players(&mut host, progress.next, 2), ResetProgress {
+            next: Some(keys[2]),
+            done: true,
+        });
+
+        for player in keys[1..].iter() {
+            let player_data = host.state().player_data.get(player).unwrap();
+            claim_eq!(player_data.last_result, BattleResult::NoResult);
+            claim_eq!((player_data.wins, player_data.losses), (0, 0));
+            claim_eq!(player_data.elo, ELO_INITIAL_RATING);
+            claim_eq!((player_data.current_streak, player_data.best_streak), (0, 0));
+        }
+    }
+
+    #[concordium_test]
+    /// Test that a single player is reset and unknown players are rejected.
+    fn test_reset_player() {
+        let mut host = initialized_host();
+        for player in [PLAYER_0, PLAYER_1] {
+            add_player(&mut host, player);
+            update_battle_result(&mut host, player, BattleResult::Win);
+        }
+
+        let mut reset_player = |player: Address| {
+            let parameter_bytes = game_player(player);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_reset_player(&ctx, &mut host, &test_crypto_primitives())
+        };
+        claim_eq!(reset_player(PLAYER_0), Ok(()));
+        claim_eq!(reset_player(PLAYER_2), Err(CustomContractError::PlayerNotFound));
+
+        claim_eq!(host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().wins, 0);
+        claim_eq!(host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap().wins, 1);
+    }
+
+    #[concordium_test]
+    /// Test that the active player count follows additions, state
+    /// transitions and deletions without double counting.
+    fn test_active_player_count() {
+        let mut host = initialized_host();
+        let active_player_count = |host: &TestHost<State<TestStateApi>>| {
+            contract_state_get_active_player_count(&TestReceiveContext::empty(), host)
+        };
+        let update_player_state = |host: &mut TestHost<State<TestStateApi>>, player, state| {
+            let parameter_bytes = to_bytes(&UpdatePlayerStateParams {
+                game_id: DEFAULT_GAME_ID,
+                player,
+                state,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+            contract_state_update_player_state(&ctx, host, &test_crypto_primitives())
+                .expect_report("Updating the player state should pass");
+        };
+
+        host.state_mut().default_player_state = PlayerState::Active;
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        add_player(&mut host, PLAYER_1);
+        claim_eq!(active_player_count(&host), Ok(2));
+
+        update_player_state(&mut host, PLAYER_0, PlayerState::Suspended);
+        update_player_state(&mut host, PLAYER_0, PlayerState::Suspended);
+        claim_eq!(active_player_count(&host), Ok(1));
+        update_player_state(&mut host, PLAYER_0, PlayerState::Active);
+        update_player_state(&mut host, PLAYER_0, PlayerState::Active);
+        claim_eq!(active_player_count(&host), Ok(2));
+
+        // Results of unknown players are rejected instead of adding them.
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            game_id:    DEFAULT_GAME_ID,
+            player:     PLAYER_2,
+            result:     BattleResult::Win,
+            client_ref: None,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        claim_eq!(
+            contract_state_update_battle_result(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::PlayerNotFound)
+        );
+        claim!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_2)).is_none(),
+            "Unknown players should not be added"
+        );
+        claim_eq!(active_player_count(&host), Ok(2));
+
+        let parameter_bytes = to_bytes(&PLAYER_1);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_force_delete_player(&ctx, &mut host, &test_crypto_primitives()),
+            Ok(())
+        );
+        claim_eq!(active_player_count(&host), Ok(1));
+    }
+
+    #[concordium_test]
+    /// Test that the pause reason is stored, returned by `view` and cleared
+    /// on unpause.
+    fn test_pause_with_reason() {
+        let mut host = initialized_host();
+
+        let parameter_bytes = to_bytes(&"a".repeat(MAX_PAUSE_REASON_LENGTH + 1));
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_pause_with_reason(&ctx, &mut host),
+            Err(CustomContractError::PauseReasonTooLong)
+        );
+
+        let reason = String::from("Investigating duplicate results");
+        let parameter_bytes = to_bytes(&reason);
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        claim_eq!(contract_state_pause_with_reason(&ctx, &mut host), Ok(()));
+        let view = contract_state_view(&ctx, &host).expect_report("View should pass");
+        claim!(view.paused, "Contract should be paused");
+        claim_eq!(view.pause_reason, Some(reason));
+
+        let parameter_bytes = to_bytes(&SetPausedParams {
+            paused: false,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_set_paused(&ctx, &mut host), Ok(()));
+        let view = contract_state_view(&ctx, &host).expect_report("View should pass");
+        claim!(!view.paused, "Contract should be unpaused");
+        claim_eq!(view.pause_reason, None);
+    }
+
+    #[concordium_test]
+    /// Test that waiting players are paired deterministically without
+    /// overlaps and that an odd player out keeps waiting.
+    fn test_pair_players() {
+        let crypto_primitives = test_crypto_primitives();
+        let players: Vec<Address> = (0..5u64)
+            .map(|index| {
+                Address::Contract(ContractAddress {
+                    index:    10 + index,
+                    subindex: 0,
+                })
+            })
+            .collect();
+
+        let pair_players = || {
+            let mut host = initialized_host();
+            host.state_mut().default_player_state = PlayerState::Active;
+            for player in &players {
+                add_player(&mut host, *player);
+                let parameter_bytes = game_player(*player);
+                let mut ctx = TestReceiveContext::empty();
+                ctx.set_sender(Address::Contract(IMPLEMENTATION));
+                ctx.set_parameter(&parameter_bytes);
+                contract_state_join_pairing_queue(&ctx, &mut host)
+                    .expect_report("Joining the pairing queue should pass");
+            }
+
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1_000));
+            let match_ids = contract_state_pair_players(&ctx, &mut host, &crypto_primitives)
+                .expect_report("Pairing players should pass");
+            let pairs: Vec<(Address, Address)> = match_ids
+                .iter()
+                .map(|match_id| {
+                    let game = host.state().matches.get(match_id).unwrap();
+                    (game.player_a, game.player_b)
+                })
+                .collect();
+            (host, pairs)
+        };
+
+        let (host, pairs) = pair_players();
+        claim_eq!(pairs.len(), 2);
+        let mut paired: Vec<Address> = pairs.iter().flat_map(|(a, b)| [*a, *b]).collect();
+        paired.sort();
+        paired.dedup();
+        claim_eq!(paired.len(), 4, "No player should be paired twice");
+        claim!(!paired.contains(&players[4]), "The last player should keep waiting");
+        let waiting = (DEFAULT_GAME_ID, players[4]);
+        claim_eq!(host.state().pairing_tickets.get(&waiting).map(|ticket| *ticket), Some(4));
+        claim_eq!(host.state().pairing_queue_head, 4);
+        claim_eq!(host.state().pairing_queue_length, 1);
+
+        let (_host, repeated_pairs) = pair_players();
+        claim_eq!(repeated_pairs, pairs, "Pairing should be deterministic");
+    }
+
+    #[concordium_test]
+    /// Test that pairing looks at a bounded number of tickets per call and
+    /// continues after them in the next call.
+    fn test_pair_players_scan_limit() {
+        let crypto_primitives = test_crypto_primitives();
+        let mut host = initialized_host();
+        host.state_mut().default_player_state = PlayerState::Active;
+        // The tickets before were taken by players that left the queue.
+        host.state_mut().pairing_queue_tail = MAX_PAIRING_QUEUE_SCAN;
+        for player in [PLAYER_0, PLAYER_1] {
+            add_player(&mut host, player);
+            let parameter_bytes = game_player(player);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_join_pairing_queue(&ctx, &mut host)
+                .expect_report("Joining the pairing queue should pass");
+        }
+
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(1_000));
+        claim_eq!(contract_state_pair_players(&ctx, &mut host, &crypto_primitives), Ok(vec![]));
+        claim_eq!(host.state().pairing_queue_head, MAX_PAIRING_QUEUE_SCAN);
+        claim_eq!(host.state().pairing_queue_length, 2);
+
+        claim_eq!(contract_state_pair_players(&ctx, &mut host, &crypto_primitives), Ok(vec![0]));
+        claim_eq!(host.state().pairing_queue_length, 0);
+    }
+
+    #[concordium_test]
+    /// Test that the length and the order of the pairing queue follow players
+    /// joining and leaving it.
+    fn test_get_queue() {
+        let mut host = initialized_host();
+        host.state_mut().default_player_state = PlayerState::Active;
+        let players = [PLAYER_0, PLAYER_1, PLAYER_2];
+        for player in players {
+            add_player(&mut host, player);
+            let parameter_bytes = game_player(player);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_join_pairing_queue(&ctx, &mut host)
+                .expect_report("Joining the pairing queue should pass");
+        }
+
+        let get_queue = |host: &TestHost<State<TestStateApi>>, from, limit| {
+            let parameter_bytes = to_bytes(&GetQueueParams {
+                from,
+                limit,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_get_queue(&ctx, host).expect_report("Query should pass")
+        };
+        let ctx = TestReceiveContext::empty();
+        claim_eq!(contract_state_get_queue_length(&ctx, &host), Ok(3));
+        claim_eq!(get_queue(&host, 0, 10), QueuePage {
+            players: players.iter().map(|player| (DEFAULT_GAME_ID, *player)).collect(),
+            next:    None,
+        });
+        claim_eq!(get_queue(&host, 1, 1), QueuePage {
+            players: vec![(DEFAULT_GAME_ID, PLAYER_1)],
+            next:    Some(2),
+        });
+
+        let parameter_bytes = game_player(PLAYER_1);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_leave_pairing_queue(&ctx, &mut host), Ok(()));
+        claim_eq!(contract_state_get_queue_length(&ctx, &host), Ok(2));
+        let (player_0, player_2) = ((DEFAULT_GAME_ID, PLAYER_0), (DEFAULT_GAME_ID, PLAYER_2));
+        claim_eq!(get_queue(&host, 0, 10).players, vec![player_0, player_2]);
+        claim_eq!(get_queue(&host, 1, 10).players, vec![player_2]);
+
+        // A page looks at a bounded number of tickets, the empty tickets of
+        // players that left are skipped over several pages.
+        let state = host.state_mut();
+        state.pairing_queue.remove(&2);
+        state.pairing_tickets.remove(&player_2);
+        let ticket = MAX_PAIRING_QUEUE_SCAN + 1;
+        state.pairing_queue.insert(ticket, player_2);
+        state.pairing_tickets.insert(player_2, ticket);
+        state.pairing_queue_tail = ticket + 1;
+        claim_eq!(get_queue(&host, 0, 10), QueuePage {
+            players: vec![player_0],
+            next:    Some(MAX_PAIRING_QUEUE_SCAN),
+        });
+        claim_eq!(get_queue(&host, MAX_PAIRING_QUEUE_SCAN, 10), QueuePage {
+            players: vec![player_2],
+            next:    None,
+        });
+    }
+
+    #[concordium_test]
+    /// Test that the pause time is set on pause, kept when pausing again and
+    /// cleared on unpause.
+    fn test_paused_since() {
+        let mut host = initialized_host();
+        let set_paused = |host: &mut TestHost<State<TestStateApi>>, paused, now| {
+            let parameter_bytes = to_bytes(&SetPausedParams {
+                paused,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(now));
+            contract_state_set_paused(&ctx, host).expect_report("Setting paused should pass");
+        };
+        let ctx = TestReceiveContext::empty();
+        claim_eq!(contract_state_get_paused_since(&ctx, &host), Ok(None));
+
+        set_paused(&mut host, true, 1_000);
+        set_paused(&mut host, true, 2_000);
+        claim_eq!(
+            contract_state_get_paused_since(&ctx, &host),
+            Ok(Some(Timestamp::from_timestamp_millis(1_000)))
+        );
+
+        set_paused(&mut host, false, 3_000);
+        claim_eq!(contract_state_get_paused_since(&ctx, &host), Ok(None));
+    }
+
+    #[concordium_test]
+    /// Test that suspending a queued player removes them from both queues, so
+    /// that they are not paired anymore.
+    fn test_suspend_queued_player() {
+        let mut host = initialized_host();
+        host.state_mut().default_player_state = PlayerState::Active;
+        for player in [PLAYER_0, PLAYER_1, PLAYER_2] {
+            add_player(&mut host, player);
+        }
+        join_queue(&mut host, PLAYER_0).expect_report("Joining the queue should pass");
+        let parameter_bytes = game_player(PLAYER_0);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_join_pairing_queue(&ctx, &mut host), Ok(()));
+
+        let parameter_bytes = to_bytes(&UpdatePlayerStateParams {
+            game_id: DEFAULT_GAME_ID,
+            player:  PLAYER_0,
+            state:   PlayerState::Suspended,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        claim_eq!(
+            contract_state_update_player_state(&ctx, &mut host, &test_crypto_primitives()),
+            Ok(())
+        );
+        claim_eq!(queued_player(&host), None);
+        claim_eq!(host.state().pairing_queue_length, 0);
+        let key = (DEFAULT_GAME_ID, PLAYER_0);
+        claim!(host.state().pairing_tickets.get(&key).is_none(), "Player should be removed");
+
+        claim_eq!(join_queue(&mut host, PLAYER_1), Ok(None));
+        claim_eq!(queued_player(&host), Some(PLAYER_1));
+        let parameter_bytes = game_player(PLAYER_2);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_join_pairing_queue(&ctx, &mut host), Ok(()));
+        let match_ids = contract_state_pair_players(&ctx, &mut host, &test_crypto_primitives());
+        claim_eq!(match_ids, Ok(Vec::new()), "The suspended player should not be paired");
+    }
+
+    #[concordium_test]
+    /// Test that a battle result submitted twice with the same client
+    /// reference is only applied once and that old references are pruned.
+    fn test_duplicate_submission() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        let submit = |host: &mut TestHost<State<TestStateApi>>, client_ref| {
+            let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+                game_id: DEFAULT_GAME_ID,
+                player:  PLAYER_0,
+                result:  BattleResult::Win,
+                client_ref,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+            contract_state_update_battle_result(&ctx, host, &test_crypto_primitives()).map(|_| ())
+        };
+
+        claim_eq!(submit(&mut host, Some([1u8; 32])), Ok(()));
+        let duplicate = submit(&mut host, Some([1u8; 32]));
+        claim_eq!(
+            duplicate,
+            Err(CustomContractError::DuplicateSubmission),
+            "Retry should be rejected"
+        );
+        claim_eq!(submit(&mut host, Some([2u8; 32])), Ok(()));
+        claim_eq!(submit(&mut host, None), Ok(()));
+        claim_eq!(submit(&mut host, None), Ok(()));
+        claim_eq!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).map(|data| data.wins),
+            Some(4)
+        );
+
+        // The oldest reference is forgotten once the limit is reached.
+        let to_client_ref = |position: u64| {
+            let mut client_ref = [0u8; 32];
+            client_ref[..8].copy_from_slice(&position.to_le_bytes());
+            client_ref
+        };
+        for position in 2..MAX_CLIENT_REFS {
+            let client_ref = record_client_ref(host.state_mut(), to_client_ref(position));
+            claim_eq!(client_ref, Ok(()));
+        }
+        claim!(host.state().client_refs.contains(&[1u8; 32]), "The reference should be kept");
+        claim_eq!(record_client_ref(host.state_mut(), to_client_ref(MAX_CLIENT_REFS)), Ok(()));
+        claim!(!host.state().client_refs.contains(&[1u8; 32]), "The reference should be pruned");
+        claim_eq!(submit(&mut host, Some([1u8; 32])), Ok(()));
+    }
+
+    #[concordium_test]
+    /// Test that the players of different games are kept apart.
+    fn test_games_are_isolated() {
+        let mut host = initialized_host();
+        let parameter_bytes = to_bytes(&None::<String>);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        let game_id = contract_state_create_game(&ctx, &mut host);
+        claim_eq!(game_id, Ok(1));
+        claim_eq!(contract_state_create_game(&ctx, &mut host), Ok(2));
+
+        let parameter_bytes = to_bytes(&GamePlayerParams {
+            game_id: 3,
+            player:  PLAYER_0,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_player_data(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::GameNotFound)
+        );
+
+        add_player(&mut host, PLAYER_0);
+        let parameter_bytes = to_bytes(&GamePlayerParams {
+            game_id: 1,
+            player:  PLAYER_0,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_player_data(&ctx, &mut host, &test_crypto_primitives()),
+            Ok(())
+        );
+        claim_eq!(contract_state_is_added(&ctx, &host), Ok(true));
+
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            game_id:    1,
+            player:     PLAYER_0,
+            result:     BattleResult::Win,
+            client_ref: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim!(
+            contract_state_update_battle_result(&ctx, &mut host, &test_crypto_primitives()).is_ok()
+        );
+
+        let parameter_bytes = to_bytes(&GamePlayerParams {
+            game_id: 1,
+            player:  PLAYER_0,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let player_data = contract_state_get_player_data(&ctx, &host);
+        claim_eq!(player_data, Ok((PlayerState::Suspended, BattleResult::Win)));
+        claim_eq!(
+            get_player_data(&host, PLAYER_0),
+            (PlayerState::Suspended, BattleResult::NoResult)
+        );
+        claim_eq!(host.state().player_data.get(&(1, PLAYER_0)).map(|data| data.wins), Some(1));
+        claim_eq!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).map(|data| data.wins),
+            Some(0)
+        );
+
+        let parameter_bytes = to_bytes(&GamePlayerParams {
+            game_id: 2,
+            player:  PLAYER_0,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_is_added(&ctx, &host), Ok(false));
+
+        // Matches, tournaments and summaries only see the players of their game.
+        let parameter_bytes = to_bytes(&GamePlayerParams {
+            game_id: 1,
+            player:  PLAYER_1,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_player_data(&ctx, &mut host, &test_crypto_primitives()),
+            Ok(())
+        );
+
+        let record_params = |game_id| {
+            to_bytes(&RecordMatchParams {
+                game_id,
+                winner:           PLAYER_0,
+                loser:            PLAYER_1,
+                duration_seconds: 60,
+            })
+        };
+        let parameter_bytes = record_params(DEFAULT_GAME_ID);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_record_match(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::PlayerNotFound)
+        );
+        let parameter_bytes = record_params(1);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_record_match(&ctx, &mut host, &test_crypto_primitives()), Ok(()));
+        claim_eq!(host.state().player_data.get(&(1, PLAYER_0)).map(|data| data.wins), Some(2));
+        claim_eq!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).map(|data| data.wins),
+            Some(0)
+        );
+
+        let match_params = |game_id| {
+            to_bytes(&CreateMatchParams {
+                game_id,
+                player_a: PLAYER_0,
+                player_b: PLAYER_1,
+            })
+        };
+        let parameter_bytes = match_params(DEFAULT_GAME_ID);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_create_match(&ctx, &mut host),
+            Err(CustomContractError::PlayerNotFound)
+        );
+        let parameter_bytes = match_params(1);
+        ctx.set_parameter(&parameter_bytes);
+        let match_id = contract_state_create_match(&ctx, &mut host).expect_report("Match");
+        complete_match(&mut host, match_id, PLAYER_1, Timestamp::from_timestamp_millis(0));
+        claim_eq!(host.state().player_data.get(&(1, PLAYER_1)).map(|data| data.wins), Some(1));
+        claim_eq!(host.state().matches.get(&match_id).map(|game| game.game_id), Some(1));
+
+        let tournament_params = |game_id| {
+            to_bytes(&CreateTournamentParams {
+                game_id,
+                players:      vec![PLAYER_0, PLAYER_1],
+                payout_split: vec![PAYOUT_SPLIT_TOTAL],
+            })
+        };
+        let parameter_bytes = tournament_params(DEFAULT_GAME_ID);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_create_tournament(&ctx, &mut host),
+            Err(CustomContractError::PlayerNotFound)
+        );
+        let parameter_bytes = tournament_params(1);
+        ctx.set_parameter(&parameter_bytes);
+        let tournament_id =
+            contract_state_create_tournament(&ctx, &mut host).expect_report("Tournament");
+        claim_eq!(
+            host.state().tournaments.get(&tournament_id).map(|tournament| tournament.game_id),
+            Some(1)
+        );
+
+        let summary = |host: &TestHost<State<TestStateApi>>, game_id| {
+            let parameter_bytes = to_bytes(&GamePlayerParams {
+                game_id,
+                player: PLAYER_1,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_get_player_summary(&ctx, host)
+                .map(|summary| (summary.total_matches, summary.rank))
+        };
+        claim_eq!(summary(&host, 1), Ok((2, 2)));
+        claim_eq!(summary(&host, DEFAULT_GAME_ID), Err(CustomContractError::PlayerNotFound));
+
+        // Deleting a player removes them from every game.
+        add_player(&mut host, PLAYER_2);
+        let parameter_bytes = to_bytes(&GamePlayerParams {
+            game_id: 1,
+            player:  PLAYER_2,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_player_data(&ctx, &mut host, &test_crypto_primitives()),
+            Ok(())
+        );
+        let parameter_bytes = to_bytes(&PLAYER_2);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_force_delete_player(&ctx, &mut host, &test_crypto_primitives()),
+            Ok(())
+        );
+        claim!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_2)).is_none(),
+            "The player should be removed from the default game"
+        );
+        claim!(
+            host.state().player_data.get(&(1, PLAYER_2)).is_none(),
+            "The player should be removed from the created game"
+        );
+        claim!(
+            host.state().player_data.get(&(1, PLAYER_0)).is_some(),
+            "Other players should remain"
+        );
+        claim_eq!(
+            contract_state_force_delete_player(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::PlayerNotFound)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that created games are listed with their metadata and their own
+    /// player counts.
+    fn test_get_games() {
+        let mut host = initialized_host();
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(2_000));
+        let parameter_bytes = to_bytes(&Some("a".repeat(MAX_GAME_NAME_LENGTH + 1)));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_create_game(&ctx, &mut host),
+            Err(CustomContractError::GameNameTooLong)
+        );
+        let names: Vec<_> = ["Chess", "Go"].map(|name| to_bytes(&Some(String::from(name)))).into();
+        for parameter_bytes in &names {
+            ctx.set_parameter(parameter_bytes);
+            claim!(contract_state_create_game(&ctx, &mut host).is_ok(), "Game should be created");
+        }
+
+        add_player(&mut host, PLAYER_0);
+        let additions: Vec<_> = [(1, PLAYER_0), (1, PLAYER_1), (2, PLAYER_2), (1, PLAYER_0)]
+            .map(|(game_id, player)| {
+                to_bytes(&GamePlayerParams {
+                    game_id,
+                    player,
+                })
+            })
+            .into();
+        for parameter_bytes in &additions {
+            ctx.set_parameter(parameter_bytes);
+            claim_eq!(
+                contract_state_set_player_data(&ctx, &mut host, &test_crypto_primitives()),
+                Ok(())
+            );
+        }
+
+        let parameter_bytes = to_bytes(&PaginationParams {
+            offset: 1,
+            limit:  10,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let game = |name: &str, player_count| GameInfo {
+            name:       Some(name.into()),
+            created_at: Timestamp::from_timestamp_millis(2_000),
+            player_count,
+        };
+        claim_eq!(
+            contract_state_get_games(&ctx, &host),
+            Ok(vec![(1, game("Chess", 2)), (2, game("Go", 1))])
+        );
+
+        let parameter_bytes = to_bytes(&PLAYER_0);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_force_delete_player(&ctx, &mut host, &test_crypto_primitives()),
+            Ok(())
+        );
+        claim_eq!(host.state().games.get(&DEFAULT_GAME_ID).map(|game| game.player_count), Some(0));
+        claim_eq!(host.state().games.get(&1).map(|game| game.player_count), Some(1));
+    }
+
+    #[concordium_test]
+    /// Test that only registered players can be updated, so that updating the
+    /// state does not register a player.
+    fn test_update_unregistered_player() {
+        let mut host = initialized_host();
+        let parameter_bytes = to_bytes(&UpdatePlayerStateParams {
+            game_id: DEFAULT_GAME_ID,
+            player:  PLAYER_0,
+            state:   PlayerState::Suspended,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        claim_eq!(
+            contract_state_update_player_state(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::PlayerNotFound)
+        );
+        claim!(
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).is_none(),
+            "The player should not be registered"
+        );
+
+        add_player(&mut host, PLAYER_0);
+        claim_eq!(
+            contract_state_update_player_state(&ctx, &mut host, &test_crypto_primitives()),
+            Ok(())
+        );
+        claim_eq!(
+            host
+                .state()
+                .player_data
+                .get(&(DEFAULT_GAME_ID, PLAYER_0))
+                .map(|player_data| player_data.state),
+            Some(PlayerState::Suspended)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the scan-based queries reject cleanly once there are more
+    /// player entries than their scan limit.
+    fn test_scan_limits() {
+        /// Creates a host with `PLAYER_0` and further players, `count` player
+        /// entries in total.
+        fn host_with_players(count: usize) -> TestHost<State<TestStateApi>> {
+            let mut host = initialized_host();
+            add_player(&mut host, PLAYER_0);
+            let player_data = host
+                .state()
+                .player_data
+                .get(&(DEFAULT_GAME_ID, PLAYER_0))
+                .map(|player_data| player_data.clone())
+                .expect_report("Player should be added");
+            for index in 1..count {
+                let player = Address::Contract(ContractAddress {
+                    index:    index as u64,
+                    subindex: 0,
+                });
+                host.state_mut().player_data.insert((DEFAULT_GAME_ID, player), player_data.clone());
+            }
+            host
+        }
+
+        let parameter_bytes = game_player(PLAYER_0);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_parameter(&parameter_bytes);
+        let limit_bytes = top_by_elo(MAX_LEADERBOARD_SIZE);
+        let mut limit_ctx = TestReceiveContext::empty();
+        limit_ctx.set_parameter(&limit_bytes);
+
+        let host = host_with_players(MAX_PLAYER_RANK_SCAN);
+        claim_eq!(contract_state_get_player_rank(&ctx, &host), Ok(1));
+        let host = host_with_players(MAX_PLAYER_RANK_SCAN + 1);
+        claim_eq!(
+            contract_state_get_player_rank(&ctx, &host),
+            Err(CustomContractError::ScanLimitExceeded)
+        );
+
+        let host = host_with_players(MAX_TOP_BY_ELO_SCAN);
+        claim!(contract_state_get_top_by_elo(&limit_ctx, &host).is_ok(), "Scan should pass");
+        let host = host_with_players(MAX_TOP_BY_ELO_SCAN + 1);
+        claim_eq!(
+            contract_state_get_top_by_elo(&limit_ctx, &host),
+            Err(CustomContractError::ScanLimitExceeded)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the status reports the initialization, pause and shutdown
+    /// flags, also before the contract is initialized.
+    fn test_get_status() {
+        let mut state_builder = TestStateBuilder::new();
+        let state = State::new(&mut state_builder, STATE_ADMIN);
+        let host = TestHost::new(state, state_builder);
+        let ctx = TestReceiveContext::empty();
+        claim_eq!(
+            contract_state_get_status(&ctx, &host),
+            Ok(StateStatus {
+                initialized: false,
+                paused:      false,
+                shutdown:    false,
+            })
+        );
+
+        let mut host = initialized_host();
+        host.state_mut().paused = true;
+        host.state_mut().shutdown = true;
+        claim_eq!(
+            contract_state_get_status(&ctx, &host),
+            Ok(StateStatus {
+                initialized: true,
+                paused:      true,
+                shutdown:    true,
+            })
+        );
+    }
+
+    #[concordium_test]
+    /// Test that the last result follows the most recent battle result that
+    /// changed the counters, and is returned by `getPlayerData`.
+    fn test_last_result() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        let player_data = |host: &TestHost<State<TestStateApi>>| {
+            let parameter_bytes = to_bytes(&GamePlayerParams {
+                game_id: DEFAULT_GAME_ID,
+                player:  PLAYER_0,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_get_player_data(&ctx, host).map(|(_, last_result)| last_result)
+        };
+        claim_eq!(player_data(&host), Ok(BattleResult::NoResult));
+
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Win);
+        claim_eq!(player_data(&host), Ok(BattleResult::Win));
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Loss);
+        claim_eq!(player_data(&host), Ok(BattleResult::Loss));
+
+        update_battle_result(&mut host, PLAYER_0, BattleResult::NoResult);
+        claim_eq!(player_data(&host), Ok(BattleResult::Loss));
+        let stored = host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().clone();
+        claim_eq!((stored.wins, stored.losses), (1, 1));
+    }
+
+    #[concordium_test]
+    /// Test that at most the configured number of results are accepted per
+    /// player within any window of the configured length, also for recorded
+    /// matches.
+    fn test_result_rate_limit() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        let parameter_bytes = to_bytes(&RateLimit {
+            max_results:    2,
+            window_seconds: 0,
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_result_rate_limit(&ctx, &mut host),
+            Err(CustomContractError::InvalidRateLimit)
+        );
+        let parameter_bytes = to_bytes(&RateLimit {
+            max_results:    MAX_RATE_LIMIT_RESULTS + 1,
+            window_seconds: 60,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_result_rate_limit(&ctx, &mut host),
+            Err(CustomContractError::InvalidRateLimit)
+        );
+        let parameter_bytes = to_bytes(&RateLimit {
+            max_results:    2,
+            window_seconds: 60,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_set_result_rate_limit(&ctx, &mut host), Ok(()));
+
+        let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+            game_id:    DEFAULT_GAME_ID,
+            player:     PLAYER_0,
+            result:     BattleResult::Win,
+            client_ref: None,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        let mut submit = |now| {
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(now));
+            contract_state_update_battle_result(&ctx, &mut host, &test_crypto_primitives())
+                .map(|_| ())
+        };
+        claim_eq!(submit(1_000), Ok(()));
+        claim_eq!(submit(30_000), Ok(()));
+        claim_eq!(submit(60_999), Err(CustomContractError::RateLimited));
+        claim_eq!(submit(61_000), Ok(()));
+        // A fixed window starting at 61s would accept this result, but the
+        // results at 30s and 61s are within the last 60 seconds.
+        claim_eq!(submit(62_000), Err(CustomContractError::RateLimited));
+        claim_eq!(submit(90_000), Ok(()));
+
+        let player_data = host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0));
+        claim_eq!(player_data.map(|player_data| player_data.wins), Some(4));
+
+        let match_params = |winner, loser| {
+            to_bytes(&RecordMatchParams {
+                game_id:          DEFAULT_GAME_ID,
+                winner,
+                loser,
+                duration_seconds: 60,
+            })
+        };
+        let parameter_bytes = match_params(PLAYER_0, PLAYER_1);
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(90_000));
+        claim_eq!(
+            contract_state_record_match(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::RateLimited)
+        );
+        let parameter_bytes = match_params(PLAYER_1, PLAYER_0);
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(150_000));
+        claim_eq!(contract_state_record_match(&ctx, &mut host, &test_crypto_primitives()), Ok(()));
+        claim_eq!(contract_state_record_match(&ctx, &mut host, &test_crypto_primitives()), Ok(()));
+        claim_eq!(
+            contract_state_record_match(&ctx, &mut host, &test_crypto_primitives()),
+            Err(CustomContractError::RateLimited)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that tournament stakes add up in the pool of the tournament and can
+    /// only be placed by players of the tournament.
+    fn test_stake_tournament() {
+        let mut host = initialized_host();
+        let stake = |host: &mut TestHost<State<TestStateApi>>, tournament_id, player, amount| {
+            let parameter_bytes = to_bytes(&StakeTournamentParams {
+                tournament_id,
+                player,
+                amount,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_stake_tournament(&ctx, host)
+        };
+        claim_eq!(
+            stake(&mut host, 0, PLAYER_0, Amount::from_ccd(1)),
+            Err(CustomContractError::TournamentNotFound)
+        );
+
+        for player in [PLAYER_0, PLAYER_1, PLAYER_2] {
+            add_player(&mut host, player);
+        }
+        for players in [vec![PLAYER_0, PLAYER_1], vec![PLAYER_0, PLAYER_2]] {
+            let parameter_bytes = to_bytes(&CreateTournamentParams {
+                game_id:      DEFAULT_GAME_ID,
+                players,
+                payout_split: vec![PAYOUT_SPLIT_TOTAL],
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_create_tournament(&ctx, &mut host)
+                .expect_report("Creating a tournament should pass");
+        }
+        claim_eq!(
+            stake(&mut host, 0, PLAYER_2, Amount::from_ccd(1)),
+            Err(CustomContractError::NotInTournament)
+        );
+        claim_eq!(stake(&mut host, 0, PLAYER_0, Amount::from_ccd(1)), Ok(()));
+        claim_eq!(stake(&mut host, 0, PLAYER_0, Amount::from_ccd(2)), Ok(()));
+        claim_eq!(stake(&mut host, 1, PLAYER_2, Amount::from_ccd(5)), Ok(()));
+        claim_eq!(
+            host.state().tournament_stakes.get(&(0, PLAYER_0)).map(|stake| *stake),
+            Some(Amount::from_ccd(3))
+        );
+        claim_eq!(host.state().tournaments.get(&0).unwrap().pool, Amount::from_ccd(3));
+        claim_eq!(host.state().tournaments.get(&1).unwrap().pool, Amount::from_ccd(5));
+    }
+
+    #[concordium_test]
+    /// Test that a bracket of four players is played to completion, and that
+    /// only referees can report matches of the current round between players
+    /// that are still in the tournament.
+    fn test_tournament_bracket() {
+        let mut host = initialized_host();
+        let player_3 = Address::Account(AccountAddress([3u8; 32]));
+        let players = vec![PLAYER_0, PLAYER_1, PLAYER_2, player_3];
+        for player in &players[..3] {
+            add_player(&mut host, *player);
+        }
+
+        let parameter_bytes = to_bytes(&CreateTournamentParams {
+            game_id:      DEFAULT_GAME_ID,
+            players:      players.clone(),
+            payout_split: vec![PAYOUT_SPLIT_TOTAL],
+        });
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_create_tournament(&ctx, &mut host),
+            Err(CustomContractError::PlayerNotFound)
+        );
+        add_player(&mut host, player_3);
+        let parameter_bytes = to_bytes(&CreateTournamentParams {
+            game_id:      DEFAULT_GAME_ID,
+            players:      players[..3].to_vec(),
+            payout_split: vec![PAYOUT_SPLIT_TOTAL],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_create_tournament(&ctx, &mut host),
+            Err(CustomContractError::InvalidTournament)
+        );
+        let parameter_bytes = to_bytes(&CreateTournamentParams {
+            game_id:      DEFAULT_GAME_ID,
+            players,
+            payout_split: vec![PAYOUT_SPLIT_TOTAL],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_create_tournament(&ctx, &mut host), Ok(0));
+
+        let report = |host: &mut TestHost<State<TestStateApi>>, winner, loser| {
+            let parameter_bytes = to_bytes(&ReportTournamentMatchParams {
+                tournament_id: 0,
+                winner,
+                loser,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_invoker(REFEREE);
+            contract_state_report_tournament_match(&ctx, host)
+        };
+        let parameter_bytes = to_bytes(&ReportTournamentMatchParams {
+            tournament_id: 0,
+            winner:        PLAYER_0,
+            loser:         PLAYER_1,
+        });
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_invoker(ACCOUNT_0);
+        claim_eq!(
+            contract_state_report_tournament_match(&ctx, &mut host),
+            Err(CustomContractError::NotReferee)
+        );
+        let parameter_bytes = to_bytes(&0u64);
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_advance_round(&ctx, &mut host),
+            Err(CustomContractError::NotReferee)
+        );
+        ctx.set_invoker(REFEREE);
+
+        claim_eq!(
+            report(&mut host, PLAYER_0, PLAYER_2),
+            Err(CustomContractError::NotInCurrentRound)
+        );
+        claim_eq!(report(&mut host, PLAYER_0, PLAYER_1), Ok(()));
+        claim_eq!(
+            report(&mut host, PLAYER_1, PLAYER_0),
+            Err(CustomContractError::PlayerEliminated)
+        );
+        claim_eq!(
+            contract_state_advance_round(&ctx, &mut host),
+            Err(CustomContractError::RoundNotComplete)
+        );
+        claim_eq!(report(&mut host, player_3, PLAYER_2), Ok(()));
+        claim_eq!(contract_state_advance_round(&ctx, &mut host), Ok(()));
+
+        claim_eq!(
+            report(&mut host, PLAYER_1, player_3),
+            Err(CustomContractError::NotInCurrentRound)
+        );
+        claim_eq!(report(&mut host, player_3, PLAYER_0), Ok(()));
+        claim_eq!(contract_state_advance_round(&ctx, &mut host), Ok(()));
+        claim_eq!(
+            contract_state_advance_round(&ctx, &mut host),
+            Err(CustomContractError::TournamentCompleted)
+        );
+
+        let tournament =
+            contract_state_get_tournament(&ctx, &host).expect_report("Query should pass");
+        claim_eq!(tournament.status, TournamentStatus::Completed {
+            champion: player_3,
+        });
+        claim_eq!(tournament.rounds.len(), 2);
+        claim_eq!(tournament.rounds[1], vec![BracketMatch {
+            player_a: PLAYER_0,
+            player_b: player_3,
+            winner:   Some(player_3),
+        }]);
+    }
+
+    #[concordium_test]
+    /// Test that the payout split has to add up to the total, and that the
+    /// finishers of a completed tournament are returned once with their share
+    /// of the pool of the tournament.
+    fn test_distribute_prizes() {
+        let mut host = initialized_host();
+        let player_3 = Address::Account(AccountAddress([3u8; 32]));
+        let players = vec![PLAYER_0, PLAYER_1, PLAYER_2, player_3];
+        for player in &players {
+            add_player(&mut host, *player);
+        }
+
+        for payout_split in [vec![7_000, 2_000], vec![7_000, 2_000, 500, 500, 0]] {
+            let parameter_bytes = to_bytes(&CreateTournamentParams {
+                game_id: DEFAULT_GAME_ID,
+                players: players.clone(),
+                payout_split,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            claim_eq!(
+                contract_state_create_tournament(&ctx, &mut host),
+                Err(CustomContractError::InvalidPayoutSplit)
+            );
+        }
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        let parameter_bytes = to_bytes(&CreateTournamentParams {
+            game_id:      DEFAULT_GAME_ID,
+            players,
+            payout_split: vec![7_000, 2_000, 1_000],
+        });
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(contract_state_create_tournament(&ctx, &mut host), Ok(0));
+
+        let parameter_bytes = to_bytes(&0u64);
+        ctx.set_parameter(&parameter_bytes);
+        ctx.set_invoker(REFEREE);
+        claim_eq!(
+            contract_state_distribute_prizes(&ctx, &mut host),
+            Err(CustomContractError::TournamentNotCompleted)
+        );
+
+        let report = |host: &mut TestHost<State<TestStateApi>>, winner, loser| {
+            let parameter_bytes = to_bytes(&ReportTournamentMatchParams {
+                tournament_id: 0,
+                winner,
+                loser,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_invoker(REFEREE);
+            contract_state_report_tournament_match(&ctx, host)
+        };
+        claim_eq!(report(&mut host, PLAYER_1, PLAYER_0), Ok(()));
+        claim_eq!(report(&mut host, PLAYER_2, player_3), Ok(()));
+        claim_eq!(contract_state_advance_round(&ctx, &mut host), Ok(()));
+        claim_eq!(report(&mut host, PLAYER_2, PLAYER_1), Ok(()));
+        for (player, amount) in [(PLAYER_0, 1_000_000), (player_3, 5)] {
+            let parameter_bytes = to_bytes(&StakeTournamentParams {
+                tournament_id: 0,
+                player,
+                amount: Amount::from_micro_ccd(amount),
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            claim_eq!(contract_state_stake_tournament(&ctx, &mut host), Ok(()));
+        }
+        claim_eq!(contract_state_advance_round(&ctx, &mut host), Ok(()));
+
+        claim_eq!(
+            contract_state_distribute_prizes(&ctx, &mut host),
+            Ok(vec![
+                (PLAYER_2, Amount::from_micro_ccd(700_003)),
+                (PLAYER_1, Amount::from_micro_ccd(200_001)),
+                (PLAYER_0, Amount::from_micro_ccd(100_000)),
+            ])
+        );
+        claim_eq!(host.state().tournaments.get(&0).unwrap().pool, Amount::from_micro_ccd(1));
+        claim!(host.state().tournament_stakes.is_empty(), "The stakes should be cleared");
+        claim_eq!(
+            contract_state_distribute_prizes(&ctx, &mut host),
+            Err(CustomContractError::PrizesAlreadyDistributed)
+        );
+    }
+
+    #[concordium_test]
+    /// Test that player data can only be written directly by the state admin
+    /// while the data fix mode is enabled.
+    fn test_admin_set_player_data() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        let mut player_data =
+            host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().clone();
+        player_data.wins = 7;
+        player_data.elo = 1350;
+        player_data.state = PlayerState::Active;
+
+        let set_player_data = |host: &mut TestHost<State<TestStateApi>>,
+                               sender,
+                               player,
+                               player_data: &PlayerData| {
+            let parameter_bytes = to_bytes(&AdminSetPlayerDataParams {
+                game_id: DEFAULT_GAME_ID,
+                player,
+                player_data: player_data.clone(),
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(sender);
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_admin_set_player_data(&ctx, host, &test_crypto_primitives())
+        };
+        claim_eq!(
+            set_player_data(&mut host, STATE_ADMIN, PLAYER_0, &player_data),
+            Err(CustomContractError::DataFixModeDisabled)
+        );
+
+        let parameter_bytes = to_bytes(&true);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(IMPLEMENTATION));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_data_fix_mode(&ctx, &mut host),
+            Err(CustomContractError::OnlyStateAdmin)
+        );
+        ctx.set_sender(STATE_ADMIN);
+        claim_eq!(contract_state_set_data_fix_mode(&ctx, &mut host), Ok(()));
+
+        claim_eq!(
+            set_player_data(&mut host, Address::Contract(IMPLEMENTATION), PLAYER_0, &player_data),
+            Err(CustomContractError::OnlyStateAdmin)
+        );
+        claim_eq!(set_player_data(&mut host, STATE_ADMIN, PLAYER_0, &player_data), Ok(()));
+        let written = host.state().player_data.get(&(DEFAULT_GAME_ID, PLAYER_0)).unwrap().clone();
+        claim_eq!((written.wins, written.elo), (7, 1350));
+
+        claim_eq!(set_player_data(&mut host, STATE_ADMIN, PLAYER_1, &player_data), Ok(()));
+        claim_eq!(host.state().games.get(&DEFAULT_GAME_ID).unwrap().player_count, 2);
+        claim_eq!(host.state().active_player_count, 2);
+    }
+
+    #[concordium_test]
+    /// Test that a battle result returns the stats of the player before and
+    /// after the result was applied.
+    fn test_result_snapshot() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+
+        let mut submit = |result| {
+            let parameter_bytes = to_bytes(&UpdateBattleResultParams {
+                game_id: DEFAULT_GAME_ID,
+                player: PLAYER_0,
+                result,
+                client_ref: None,
+            });
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+            contract_state_update_battle_result(&ctx, &mut host, &test_crypto_primitives())
+        };
+
+        let snapshot = |wins_before, wins_after| ResultSnapshot {
+            wins_before,
+            wins_after,
+            elo_before: ELO_INITIAL_RATING,
+            elo_after: ELO_INITIAL_RATING,
+        };
+        claim_eq!(submit(BattleResult::Win), Ok(snapshot(0, 1)));
+        claim_eq!(submit(BattleResult::Win), Ok(snapshot(1, 2)));
+        claim_eq!(submit(BattleResult::Loss), Ok(snapshot(2, 2)));
+        claim_eq!(submit(BattleResult::NoResult), Ok(snapshot(2, 2)));
+    }
+
+    #[concordium_test]
+    /// Test that players with fewer completed matches than the leaderboard
+    /// minimum are left out of the leaderboard and the ranks.
+    fn test_leaderboard_min_matches() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        add_player(&mut host, PLAYER_2);
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Win);
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Loss);
+        update_battle_result(&mut host, PLAYER_1, BattleResult::Win);
+        update_battle_result(&mut host, PLAYER_1, BattleResult::Win);
+        host.state_mut().player_data.get_mut(&(DEFAULT_GAME_ID, PLAYER_1)).unwrap().elo = 1300;
+
+        let parameter_bytes = to_bytes(&3u32);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(Address::Contract(PROXY));
+        ctx.set_parameter(&parameter_bytes);
+        claim_eq!(
+            contract_state_set_leaderboard_min_matches(&ctx, &mut host),
+            Err(CustomContractError::OnlyImplementation)
+        );
+
+        let set_min_matches = |host: &mut TestHost<State<TestStateApi>>, min_matches: u32| {
+            let parameter_bytes = to_bytes(&min_matches);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_sender(Address::Contract(IMPLEMENTATION));
+            ctx.set_parameter(&parameter_bytes);
+            claim_eq!(contract_state_set_leaderboard_min_matches(&ctx, host), Ok(()));
+        };
+        let rank = |host: &TestHost<State<TestStateApi>>, player: Address| {
+            let parameter_bytes = game_player(player);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_get_player_rank(&ctx, host)
+        };
+        let top_by_elo = |host: &TestHost<State<TestStateApi>>| {
+            let parameter_bytes = top_by_elo(MAX_LEADERBOARD_SIZE);
+            let mut ctx = TestReceiveContext::empty();
+            ctx.set_parameter(&parameter_bytes);
+            contract_state_get_top_by_elo(&ctx, host).expect_report("Query should pass")
+        };
+
+        // By default all players are listed.
+        claim_eq!(top_by_elo(&host).len(), 3);
+        claim_eq!(rank(&host, PLAYER_2), Ok(3));
+
+        set_min_matches(&mut host, 2);
+        claim_eq!(top_by_elo(&host), vec![(PLAYER_1, 1300), (PLAYER_0, ELO_INITIAL_RATING)]);
+        claim_eq!(rank(&host, PLAYER_0), Ok(2));
+        claim_eq!(rank(&host, PLAYER_2), Err(CustomContractError::NotOnLeaderboard));
+
+        // Players below the minimum do not push others down the ranks.
+        set_min_matches(&mut host, 3);
+        update_battle_result(&mut host, PLAYER_0, BattleResult::Loss);
+        claim_eq!(top_by_elo(&host), vec![(PLAYER_0, ELO_INITIAL_RATING)]);
+        claim_eq!(rank(&host, PLAYER_0), Ok(1));
+        claim_eq!(rank(&host, PLAYER_1), Err(CustomContractError::NotOnLeaderboard));
+    }
+
+    #[concordium_test]
+    /// Test that the extended view reports the protocol addresses, the flags
+    /// and the counters of the state.
+    fn test_get_extended_state() {
+        let mut host = initialized_host();
+        add_player(&mut host, PLAYER_0);
+        add_player(&mut host, PLAYER_1);
+        host.state_mut().default_player_state = PlayerState::Active;
+        add_player(&mut host, PLAYER_2);
+        let match_id = create_match(&mut host, PLAYER_0, PLAYER_1);
+        complete_match(&mut host, match_id, PLAYER_0, Timestamp::from_timestamp_millis(0));
+        update_battle_result(&mut host, PLAYER_2, BattleResult::Win);
+        update_battle_result(&mut host, PLAYER_2, BattleResult::NoResult);
+        let state = host.state_mut();
+        state.paused = true;
+        state.pause_reason = Some("Maintenance window".into());
+        state.shutdown = true;
+        state.current_season = 2;
+
+        let ctx = TestReceiveContext::empty();
+        claim_eq!(
+            contract_state_get_extended_state(&ctx, &host),
+            Ok(ExtendedState {
+                proxy_address:          PROXY,
+                implementation_address: IMPLEMENTATION,
+                paused:                 true,
+                pause_reason:           Some("Maintenance window".into()),
+                shutdown:               true,
+                player_count:           3,
+                active_player_count:    1,
+                current_season:         2,
+                total_wins:             2,
+                total_losses:           1,
+            })
+        );
+    }
+}